@@ -0,0 +1,57 @@
+//! Times building an interpreter and what a precompiled core snapshot could skip:
+//! reading the core sources, and evaluating them in full when the core is built eagerly.
+//! Run with `cargo run --release --example startup_bench`.
+use sigil::{Interpreter, InterpreterBuilder};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 50;
+
+const CORE_SOURCES: &[&str] = &[
+    include_str!("../src/lang/core/macros.sigil"),
+    include_str!("../src/lang/core/fns.sigil"),
+    include_str!("../src/lang/core/transducers.sigil"),
+];
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let reader = Interpreter::default();
+    let timings = [
+        (
+            "build with the core deferred (default)",
+            time(|| drop(Interpreter::default())),
+        ),
+        (
+            "build with the core deferred, then first use of a core fn",
+            time(|| {
+                let mut interpreter = Interpreter::default();
+                interpreter.evaluate_from_source("(inc 1)").unwrap();
+            }),
+        ),
+        (
+            "build with the core evaluated eagerly",
+            time(|| drop(InterpreterBuilder::default().with_eager_core().build())),
+        ),
+        (
+            "read the core sources",
+            time(|| {
+                for source in CORE_SOURCES {
+                    reader.read(source).unwrap();
+                }
+            }),
+        ),
+        ("clone_isolated of an eagerly built interpreter", {
+            let base = InterpreterBuilder::default().with_eager_core().build();
+            time(|| drop(base.clone_isolated()))
+        }),
+    ];
+    for (name, elapsed) in timings {
+        println!("{name}: {elapsed:?}");
+    }
+}
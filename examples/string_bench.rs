@@ -0,0 +1,39 @@
+//! Times string-heavy workloads, e.g. templating loops built from `str` and `print-str`.
+//! Run with `cargo run --release --example string_bench`.
+use sigil::Interpreter;
+use std::time::Instant;
+
+const ITERATIONS: usize = 20;
+
+const WORKLOADS: &[(&str, &str)] = &[
+    (
+        "str in a templating loop",
+        "(loop* [i 0 out \"\"] (if (< i 2000) (recur (inc i) (str out \"<li>\" i \"</li>\")) (count out)))",
+    ),
+    (
+        "print-str over strings",
+        "(loop* [i 0 n 0] (if (< i 2000) (recur (inc i) (+ n (count (print-str \"name:\" \"sigil\" i)))) n))",
+    ),
+    (
+        "str of a single string",
+        "(def! s (str \"abcdefghijklmnopqrstuvwxyz\" \"abcdefghijklmnopqrstuvwxyz\")) (loop* [i 0] (if (< i 5000) (do (str s) (recur (inc i))) i))",
+    ),
+    (
+        "seq over a string",
+        "(def! s (loop* [i 0 out \"\"] (if (< i 200) (recur (inc i) (str out \"0123456789\")) out))) (count (seq s))",
+    ),
+];
+
+fn main() {
+    for (name, source) in WORKLOADS {
+        let mut interpreter = Interpreter::default();
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            interpreter
+                .evaluate_from_source(source)
+                .expect("is valid source");
+        }
+        let elapsed = start.elapsed() / ITERATIONS as u32;
+        println!("{name}: {elapsed:?} per iteration");
+    }
+}
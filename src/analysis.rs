@@ -0,0 +1,262 @@
+//! Static checks over source that do not require evaluation, e.g. for tooling.
+
+use crate::analyzer::lint_form;
+use crate::diagnostics::Diagnostic;
+use crate::hints::hint_tag;
+use crate::interpreter::Interpreter;
+use crate::reader::{read, ReadError};
+use crate::value::{PersistentList, Value};
+
+/// Read `source` and report any issues found in its forms by analyzing them as
+/// evaluating them would, without evaluating any of it. Identical issues are reported once.
+pub fn lint(source: &str) -> Result<Vec<Diagnostic>, ReadError> {
+    let forms = read(source)?;
+    let mut interpreter = Interpreter::default();
+    for form in &forms {
+        // a form the analyzer rejects fails when evaluated with a more useful error,
+        // so only the issues found before the analysis failed are reported
+        lint_form(&mut interpreter, form).ok();
+    }
+    Ok(interpreter.take_diagnostics())
+}
+
+/// What a `TypeHint` is written on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintTarget {
+    /// the name of a `def!`, or of a fn where the hint describes its return value
+    Def,
+    /// a parameter of a fn, named if the fn is defined with `defn` or `def!`
+    Parameter { of: Option<String> },
+}
+
+/// A type hint given as metadata, e.g. `^long n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHint {
+    pub name: String,
+    pub tag: String,
+    pub target: HintTarget,
+}
+
+/// Read `source` and collect the type hints on its definitions and parameters,
+/// e.g. for external type checkers, without evaluating any of it.
+pub fn type_hints(source: &str) -> Result<Vec<TypeHint>, ReadError> {
+    let forms = read(source)?;
+    let mut hints = vec![];
+    for form in &forms {
+        collect_type_hints(form, &mut hints);
+    }
+    Ok(hints)
+}
+
+// `^meta name` yields the name and the tag of any hint
+fn hinted_name(form: &Value) -> Option<(&String, Option<&str>)> {
+    match form {
+        Value::Symbol(name, None) => Some((name, None)),
+        Value::WithMeta(name, meta) => match &**name {
+            Value::Symbol(name, None) => Some((name, hint_tag(meta))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn collect_param_hints(params: &Value, of: Option<&String>, hints: &mut Vec<TypeHint>) {
+    if let Value::Vector(params) = params {
+        for param in params {
+            if let Some((name, Some(tag))) = hinted_name(param) {
+                hints.push(TypeHint {
+                    name: name.clone(),
+                    tag: tag.to_string(),
+                    target: HintTarget::Parameter { of: of.cloned() },
+                });
+            }
+        }
+    }
+}
+
+// the parameters and body of `form` if it is a `(fn* params & body)`
+fn fn_operands(form: &Value) -> Option<PersistentList<Value>> {
+    match form {
+        Value::List(elems) => match elems.first() {
+            Some(Value::Symbol(s, None)) if s == "fn*" => elems.drop_first(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn collect_list_hints(elems: &PersistentList<Value>, hints: &mut Vec<TypeHint>) {
+    let rest = elems.drop_first().unwrap_or_default();
+    match elems.first() {
+        Some(Value::Symbol(s, None)) if s == "quote" => return,
+        Some(Value::Symbol(s, None)) if s == "fn*" => {
+            if let Some(params) = rest.first() {
+                collect_param_hints(params, None, hints);
+            }
+        }
+        Some(Value::Symbol(s, None))
+            if s == "def!" || s == "defn" || s == "defn-memo" || s == "defnk" =>
+        {
+            if let Some((name, tag)) = rest.first().and_then(hinted_name) {
+                if let Some(tag) = tag {
+                    hints.push(TypeHint {
+                        name: name.clone(),
+                        tag: tag.to_string(),
+                        target: HintTarget::Def,
+                    });
+                }
+                let forms = rest.drop_first().unwrap_or_default();
+                let (params, body) = if s == "def!" {
+                    // the parameters of a fn given as the value, e.g. `(def! f (fn* [^long n] n))`
+                    match forms.first().and_then(fn_operands) {
+                        Some(operands) => (
+                            operands.first().cloned(),
+                            operands.drop_first().unwrap_or_default(),
+                        ),
+                        None => (None, forms),
+                    }
+                } else {
+                    // the parameters follow any docstring
+                    let params = forms.iter().find(|form| !matches!(form, Value::String(..)));
+                    (params.cloned(), forms)
+                };
+                if let Some(params) = &params {
+                    collect_param_hints(params, Some(name), hints);
+                }
+                body.iter().for_each(|form| collect_type_hints(form, hints));
+                return;
+            }
+        }
+        _ => {}
+    }
+    for elem in elems {
+        collect_type_hints(elem, hints);
+    }
+}
+
+fn collect_type_hints(form: &Value, hints: &mut Vec<TypeHint>) {
+    match form {
+        Value::List(elems) => collect_list_hints(elems, hints),
+        Value::Vector(elems) => elems
+            .iter()
+            .for_each(|elem| collect_type_hints(elem, hints)),
+        Value::Map(elems) => elems.iter().for_each(|(k, v)| {
+            collect_type_hints(k, hints);
+            collect_type_hints(v, hints);
+        }),
+        Value::Set(elems) => elems
+            .iter()
+            .for_each(|elem| collect_type_hints(elem, hints)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::DiagnosticKind;
+
+    fn lint_kinds(source: &str) -> Vec<DiagnosticKind> {
+        lint(source)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let test_cases = vec![
+            (
+                "(fn* [a b] a)",
+                vec![DiagnosticKind::UnusedParameter("b".to_string())],
+            ),
+            ("(fn* [a & _rest] a)", vec![]),
+            (
+                "(fn* [a & more] more)",
+                vec![DiagnosticKind::UnusedParameter("a".to_string())],
+            ),
+            (
+                "(let* [a 1 b a] 2)",
+                vec![DiagnosticKind::UnusedBinding("b".to_string())],
+            ),
+            ("(let* [a 1] a)", vec![]),
+            (
+                "(do (throw :a) 1 2)",
+                vec![
+                    DiagnosticKind::UnreachableForm,
+                    DiagnosticKind::UnreachableForm,
+                ],
+            ),
+            (
+                "(fn* [] (throw :a) 1)",
+                vec![DiagnosticKind::UnreachableForm],
+            ),
+            ("(let* [] (throw :a))", vec![]),
+            (
+                "(def! a (+ a 1))",
+                vec![DiagnosticKind::SelfReferentialDef("a".to_string())],
+            ),
+            ("(def! f (fn* [n] (f n)))", vec![]),
+            ("(def! a '(a))", vec![]),
+            (
+                "(def! a 1) [(fn* [x] 1)]",
+                vec![DiagnosticKind::UnusedParameter("x".to_string())],
+            ),
+            ("'(fn* [x] 1)", vec![]),
+            // forms are analyzed as evaluating them would, so after expanding any macros
+            (
+                "(defn f [x y] x)",
+                vec![DiagnosticKind::UnusedParameter("y".to_string())],
+            ),
+            (
+                "(defn f [] (throw :a) 1)",
+                vec![DiagnosticKind::UnreachableForm],
+            ),
+            (
+                "(inc 1 2)",
+                vec![DiagnosticKind::SuspiciousArity {
+                    name: "inc".to_string(),
+                    realized: 2,
+                }],
+            ),
+            ("(undefined 1)", vec![]),
+        ];
+        for (source, expected) in test_cases {
+            assert_eq!(lint_kinds(source), expected, "linting `{}`", source);
+        }
+        assert!(lint("(").is_err());
+    }
+
+    #[test]
+    fn test_type_hints() {
+        let source = r#"
+            (def! ^long limit 10)
+            (defn ^string greet "greets" [^string name ^long times] name)
+            (def! f (fn* [^keyword k x] (map (fn* [^long n] n) x)))
+            (let* [g (fn* [^vector v] v)] g)
+            '(fn* [^long quoted] quoted)
+        "#;
+        let hint = |name: &str, tag: &str, target| TypeHint {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            target,
+        };
+        let param_of = |name: &str| HintTarget::Parameter {
+            of: Some(name.to_string()),
+        };
+        let anonymous = HintTarget::Parameter { of: None };
+        assert_eq!(
+            type_hints(source).unwrap(),
+            vec![
+                hint("limit", "long", HintTarget::Def),
+                hint("greet", "string", HintTarget::Def),
+                hint("name", "string", param_of("greet")),
+                hint("times", "long", param_of("greet")),
+                hint("k", "keyword", param_of("f")),
+                hint("n", "long", anonymous.clone()),
+                hint("v", "vector", anonymous),
+            ]
+        );
+    }
+}
@@ -1,14 +1,20 @@
-use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter, Scope, SyntaxError};
+use crate::diagnostics::{references, unused_bindings, DiagnosticKind};
+use crate::hints::{check_parameter, hint_tag};
+use crate::interpreter::{
+    check_arity, EvaluationError, EvaluationResult, Interpreter, Scope, SyntaxError,
+};
 use crate::value::{
-    FnImpl, FnWithCapturesImpl, PersistentList, PersistentMap, PersistentSet, PersistentVector,
-    Value,
+    list_with_values, var_impl_into_inner, FnImpl, FnWithCapturesImpl, PersistentList,
+    PersistentMap, PersistentQueue, PersistentSet, PersistentVector, Value, VarImpl,
 };
 use itertools::Itertools;
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::fmt::Write;
 use std::iter::FromIterator;
 
 const MIN_VARIADIC_PARAM_COUNT: usize = 2;
+const LAMBDA_PARAMETER_PREFIX: &str = ":system-fn-%";
 
 type BindingRef<'a> = (&'a String, &'a Value);
 
@@ -18,6 +24,8 @@ type BindingRef<'a> = (&'a String, &'a Value);
 struct Frame {
     scopes: Vec<Scope>,
     forward_declarations: Vec<Scope>,
+    // the parameters as written, indexed like their rewritten keys
+    param_names: Vec<Value>,
 }
 
 // ref to a Frame in set of Frames and an identifier within that Frame
@@ -38,6 +46,10 @@ fn binding_declares_fn((name, value): &BindingRef) -> Option<String> {
 }
 
 impl<'a> LetBindings<'a> {
+    pub fn as_slice(&self) -> &[BindingRef<'a>] {
+        &self.bindings
+    }
+
     // allow let bindings that declare `fn*`s to capture other
     // let bindings that declare `fn*`s
     pub fn resolve_forward_declarations(&self) -> HashSet<String> {
@@ -62,15 +74,30 @@ pub struct LetForm<'a> {
     pub body: PersistentList<Value>,
 }
 
+// ensure `name` has not already been seen in some set of bindings
+fn validate_unique_name(name: &str, seen: &mut HashSet<String>) -> EvaluationResult<()> {
+    if !seen.insert(name.to_string()) {
+        return Err(SyntaxError::DuplicateBindingName(name.to_string()).into());
+    }
+    Ok(())
+}
+
+// the form read from `^meta form` without its metadata
+fn strip_metadata(form: &Value) -> &Value {
+    form.without_meta()
+}
+
 fn parse_let_bindings(bindings_form: &Value) -> EvaluationResult<LetBindings> {
     match bindings_form {
         Value::Vector(bindings) => {
             let bindings_count = bindings.len();
             if bindings_count % 2 == 0 {
                 let mut validated_bindings = Vec::with_capacity(bindings_count);
+                let mut seen = HashSet::new();
                 for (name, value_form) in bindings.iter().tuples() {
-                    match name {
+                    match strip_metadata(name) {
                         Value::Symbol(s, None) => {
+                            validate_unique_name(s, &mut seen)?;
                             validated_bindings.push((s, value_form));
                         }
                         other => {
@@ -93,7 +120,7 @@ fn parse_let_bindings(bindings_form: &Value) -> EvaluationResult<LetBindings> {
 }
 
 fn parse_let(forms: &PersistentList<Value>) -> EvaluationResult<LetForm> {
-    let bindings_form = forms.first().ok_or(EvaluationError::WrongArity {
+    let bindings_form = forms.first().ok_or(EvaluationError::WrongArityAtLeast {
         expected: 1,
         realized: 0,
     })?;
@@ -110,26 +137,149 @@ pub(crate) fn analyze_let(let_forms: &PersistentList<Value>) -> EvaluationResult
     Ok(let_form)
 }
 
+// values which evaluate to themselves and so can be inlined in place of a reference
+fn is_constant(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Nil | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Keyword(..)
+    )
+}
+
 pub(crate) fn lambda_parameter_key(index: usize, level: usize) -> String {
     let mut key = String::new();
-    let _ = write!(&mut key, ":system-fn-%{}/{}", index, level);
+    let _ = write!(&mut key, "{}{}/{}", LAMBDA_PARAMETER_PREFIX, index, level);
     key
 }
 
+// inverse of `lambda_parameter_key`, yielding `(index, level)`
+pub(crate) fn parse_lambda_parameter_key(key: &str) -> Option<(usize, usize)> {
+    let (index, level) = key.strip_prefix(LAMBDA_PARAMETER_PREFIX)?.split_once('/')?;
+    Some((index.parse().ok()?, level.parse().ok()?))
+}
+
+// checks each `recur` in `forms` is in tail position of the `loop*` or `fn*` it
+// rebinds, the innermost of which is named by `enclosing`; the last of `forms` is
+// in tail position if `tail` is. Forms `is_macro` reports as macro invocations
+// are not expanded here so their operands are not checked.
+pub(crate) fn check_recur_in_tail_position<'a>(
+    forms: impl IntoIterator<Item = &'a Value>,
+    tail: bool,
+    enclosing: &'static str,
+    is_macro: &mut dyn FnMut(&Value) -> bool,
+) -> Result<(), SyntaxError> {
+    let mut forms = forms.into_iter().peekable();
+    while let Some(form) = forms.next() {
+        let is_last = forms.peek().is_none();
+        check_recur_in_form(form, tail && is_last, enclosing, is_macro)?;
+    }
+    Ok(())
+}
+
+fn check_recur_in_form(
+    form: &Value,
+    tail: bool,
+    enclosing: &'static str,
+    is_macro: &mut dyn FnMut(&Value) -> bool,
+) -> Result<(), SyntaxError> {
+    let elems = match form {
+        Value::List(elems) => elems,
+        Value::Vector(elems) => {
+            return check_recur_in_tail_position(elems, false, enclosing, is_macro)
+        }
+        Value::Map(elems) => {
+            for (k, v) in elems {
+                check_recur_in_form(k, false, enclosing, is_macro)?;
+                check_recur_in_form(v, false, enclosing, is_macro)?;
+            }
+            return Ok(());
+        }
+        Value::Set(elems) => {
+            return check_recur_in_tail_position(elems, false, enclosing, is_macro)
+        }
+        Value::WithMeta(form, _) => return check_recur_in_form(form, tail, enclosing, is_macro),
+        _ => return Ok(()),
+    };
+    let operator = match elems.first() {
+        Some(operator) => operator,
+        None => return Ok(()),
+    };
+    let operands = elems.iter().skip(1);
+    // the operands of `let*` and `loop*` after the bindings
+    let body = || elems.iter().skip(2);
+    let check_bindings = |is_macro: &mut dyn FnMut(&Value) -> bool| match elems.iter().nth(1) {
+        Some(Value::Vector(bindings)) => {
+            let values = bindings.iter().skip(1).step_by(2);
+            check_recur_in_tail_position(values, false, enclosing, is_macro)
+        }
+        Some(other) => check_recur_in_form(other, false, enclosing, is_macro),
+        None => Ok(()),
+    };
+    match operator {
+        Value::Symbol(s, None) if s == "quote" || s == "quasiquote" => Ok(()),
+        Value::Symbol(s, None) if s == "recur" => {
+            if !tail {
+                return Err(SyntaxError::RecurNotInTailPosition(
+                    form.clone(),
+                    Some(enclosing),
+                ));
+            }
+            check_recur_in_tail_position(operands, false, enclosing, is_macro)
+        }
+        Value::Symbol(s, None) if s == "if" => {
+            let mut operands = operands;
+            if let Some(predicate) = operands.next() {
+                check_recur_in_form(predicate, false, enclosing, is_macro)?;
+            }
+            for branch in operands {
+                check_recur_in_form(branch, tail, enclosing, is_macro)?;
+            }
+            Ok(())
+        }
+        Value::Symbol(s, None) if s == "do" => {
+            check_recur_in_tail_position(operands, tail, enclosing, is_macro)
+        }
+        Value::Symbol(s, None) if s == "let*" => {
+            check_bindings(is_macro)?;
+            check_recur_in_tail_position(body(), tail, enclosing, is_macro)
+        }
+        Value::Symbol(s, None) if s == "loop*" => {
+            check_bindings(is_macro)?;
+            check_recur_in_tail_position(body(), true, "loop*", is_macro)
+        }
+        Value::Symbol(s, None) if s == "fn*" => {
+            check_recur_in_tail_position(operands.skip(1), true, "fn*", is_macro)
+        }
+        operator if is_macro(operator) => Ok(()),
+        _ => check_recur_in_tail_position(elems, false, enclosing, is_macro),
+    }
+}
+
 pub struct Analyzer<'a> {
     interpreter: &'a mut Interpreter,
+    // set while analyzing quoted data or a name being defined,
+    // where names bound to constants are left as written
+    quoted: bool,
+    // set when analyzing for `analysis::lint` rather than to evaluate, see `Analyzer::report_lints`
+    lint: bool,
 }
 
 impl<'a> Analyzer<'a> {
     fn new(interpreter: &'a mut Interpreter) -> Self {
-        Self { interpreter }
+        Self {
+            interpreter,
+            quoted: false,
+            lint: false,
+        }
     }
 
+    // if `inline_constants` then references to names bound to constants
+    // are replaced with the constant, so they never need to be captured
     fn analyze_lexical_bindings_in_fn(
         &mut self,
         bindings: &PersistentVector<Value>,
         frames: &mut Vec<Frame>,
         captures: &mut Vec<CaptureSet>,
+        inline_constants: bool,
     ) -> EvaluationResult<Value> {
         if bindings.len() % 2 != 0 {
             return Err(SyntaxError::LexicalBindingsMustBePaired(bindings.clone()).into());
@@ -138,9 +288,11 @@ impl<'a> Analyzer<'a> {
         // NOTE: this is duplicated w/ `let*` analysis elsewhere...
         // TODO: consolidate to one analysis phase
         let mut forward_declarations = Scope::new();
+        let mut seen = HashSet::new();
         for (name, value) in bindings.iter().tuples() {
             match name {
                 Value::Symbol(s, None) => {
+                    validate_unique_name(s, &mut seen)?;
                     if binding_declares_fn(&(s, value)).is_some() {
                         forward_declarations.insert(s.clone(), Value::Symbol(s.clone(), None));
                     }
@@ -161,7 +313,7 @@ impl<'a> Analyzer<'a> {
         for (name, value) in bindings.iter().tuples() {
             let analyzed_value = self.analyze_form_in_fn(value, frames, captures)?;
             analyzed_bindings.push_back_mut(name.clone());
-            analyzed_bindings.push_back_mut(analyzed_value);
+            analyzed_bindings.push_back_mut(analyzed_value.clone());
             // lexical bindings serially extend scope per binding:
             match name {
                 Value::Symbol(s, None) => {
@@ -169,7 +321,11 @@ impl<'a> Analyzer<'a> {
                     let scope = local_scopes
                         .get_mut(bindings_scope_index)
                         .expect("did push bindings scope");
-                    scope.insert(s.clone(), Value::Symbol(s.clone(), None));
+                    if inline_constants && is_constant(&analyzed_value) {
+                        scope.insert(s.clone(), analyzed_value.clone());
+                    } else {
+                        scope.insert(s.clone(), Value::Symbol(s.clone(), None));
+                    }
                 }
                 _ => unreachable!("already verified symbol names"),
             }
@@ -209,7 +365,10 @@ impl<'a> Analyzer<'a> {
                     .iter()
                     .map(|(_, capture)| (capture.to_string(), None))
                     .collect();
-                Ok(Value::FnWithCaptures(FnWithCapturesImpl { f, captures }))
+                Ok(Value::FnWithCaptures(FnWithCapturesImpl {
+                    f,
+                    captures: Box::new(captures),
+                }))
             }
             _ => unreachable!("only returns Fn variant"),
         }
@@ -229,6 +388,10 @@ impl<'a> Analyzer<'a> {
             local_scopes.len()
         };
 
+        if self.lint && !self.quoted {
+            self.report_lints(elems);
+        }
+
         // if first elem introduces a new lexical scope...
         let mut iter = elems.iter();
         let mut analyzed_elems = vec![];
@@ -236,16 +399,18 @@ impl<'a> Analyzer<'a> {
             Some(Value::Symbol(s, None)) if s == "let*" => {
                 analyzed_elems.push(Value::Symbol(s.to_string(), None));
                 if let Some(Value::Vector(bindings)) = iter.next() {
+                    self.report_unused_bindings(elems, bindings);
                     let analyzed_bindings =
-                        self.analyze_lexical_bindings_in_fn(bindings, frames, captures)?;
+                        self.analyze_lexical_bindings_in_fn(bindings, frames, captures, true)?;
                     analyzed_elems.push(analyzed_bindings);
                 }
             }
             Some(Value::Symbol(s, None)) if s == "loop*" => {
                 analyzed_elems.push(Value::Symbol(s.to_string(), None));
                 if let Some(Value::Vector(bindings)) = iter.next() {
+                    // `recur` rebinds names in `loop*` so they are never constant
                     let analyzed_bindings =
-                        self.analyze_lexical_bindings_in_fn(bindings, frames, captures)?;
+                        self.analyze_lexical_bindings_in_fn(bindings, frames, captures, false)?;
                     analyzed_elems.push(analyzed_bindings);
                 }
             }
@@ -274,15 +439,160 @@ impl<'a> Analyzer<'a> {
                     local_scopes.push(scope);
                 }
             }
+            Some(Value::Symbol(s, None)) if s == "def!" => {
+                analyzed_elems.push(Value::Symbol(s.to_string(), None));
+                if let Some(name) = iter.next() {
+                    let quoted = std::mem::replace(&mut self.quoted, true);
+                    let analyzed_name = self.analyze_form_in_fn(name, frames, captures);
+                    self.quoted = quoted;
+                    analyzed_elems.push(analyzed_name?);
+                }
+            }
             _ => {}
         }
-        for elem in elems.iter().skip(analyzed_elems.len()) {
-            let analyzed_elem = self.analyze_form_in_fn(elem, frames, captures)?;
-            analyzed_elems.push(analyzed_elem);
-        }
+        // quoted data is only evaluated again where it is unquoted
+        let quoted = match elems.first() {
+            Some(Value::Symbol(s, None)) if s == "quote" || s == "quasiquote" => true,
+            Some(Value::Symbol(s, None)) if s == "unquote" || s == "splice-unquote" => false,
+            _ => self.quoted,
+        };
+        let quoted = std::mem::replace(&mut self.quoted, quoted);
+        let result: EvaluationResult<()> =
+            elems
+                .iter()
+                .skip(analyzed_elems.len())
+                .try_for_each(|elem| {
+                    let analyzed_elem = self.analyze_form_in_fn(elem, frames, captures)?;
+                    analyzed_elems.push(analyzed_elem);
+                    Ok(())
+                });
+        self.quoted = quoted;
+        result?;
         let local_scopes = &mut frames.last_mut().expect("did push").scopes;
         local_scopes.truncate(existing_scopes_count);
-        Ok(Value::List(PersistentList::from_iter(analyzed_elems)))
+        let analyzed_form = Value::List(PersistentList::from_iter(analyzed_elems));
+        self.report_suspicious_arity(&analyzed_form, frames);
+        Ok(analyzed_form)
+    }
+
+    fn report_unused_bindings(
+        &mut self,
+        let_form: &PersistentList<Value>,
+        bindings: &PersistentVector<Value>,
+    ) {
+        let named_bindings = bindings
+            .iter()
+            .tuples()
+            .filter_map(|(name, value)| match name {
+                Value::Symbol(s, None) => Some((s, value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let body = let_form
+            .drop_first()
+            .and_then(|rest| rest.drop_first())
+            .unwrap_or_default();
+        for name in unused_bindings(&named_bindings, &body) {
+            self.interpreter.report(
+                DiagnosticKind::UnusedBinding(name.clone()),
+                &Value::List(let_form.clone()),
+            );
+        }
+    }
+
+    // reports issues in `elems` that do not prevent evaluating it, so are only
+    // looked for when linting: parameters of a `fn*` it never refers to, forms in
+    // a body following one that throws and a `def!` referring to itself
+    fn report_lints(&mut self, elems: &PersistentList<Value>) {
+        let form = || Value::List(elems.clone());
+        let rest = elems.drop_first().unwrap_or_default();
+        let body = match elems.first() {
+            Some(Value::Symbol(s, None)) if s == "let*" || s == "loop*" => {
+                rest.drop_first().unwrap_or_default()
+            }
+            Some(Value::Symbol(s, None)) if s == "fn*" => {
+                let body = rest.drop_first().unwrap_or_default();
+                if let Some(Value::Vector(params)) = rest.first() {
+                    for param in params {
+                        if let Value::Symbol(name, None) = param.without_meta() {
+                            let ignored = name == "&" || name.starts_with('_');
+                            if !ignored && !body.iter().any(|form| references(form, name)) {
+                                let kind = DiagnosticKind::UnusedParameter(name.clone());
+                                self.interpreter.report(kind, &form());
+                            }
+                        }
+                    }
+                }
+                body
+            }
+            Some(Value::Symbol(s, None)) if s == "do" => rest,
+            Some(Value::Symbol(s, None)) if s == "def!" => {
+                if let (Some(Value::Symbol(name, None)), Some(value)) =
+                    (rest.first().map(Value::without_meta), rest.iter().nth(1))
+                {
+                    if references_outside_fn(value, name) {
+                        let kind = DiagnosticKind::SelfReferentialDef(name.clone());
+                        self.interpreter.report(kind, &form());
+                    }
+                }
+                return;
+            }
+            _ => return,
+        };
+        // every form in `body` after one that throws is unreachable
+        let mut throws = false;
+        for form in &body {
+            if throws {
+                self.interpreter
+                    .report(DiagnosticKind::UnreachableForm, form);
+            }
+            throws = throws || is_throw(form);
+        }
+    }
+
+    // report calls to a var holding a fn that do not match the fn's arity
+    fn report_suspicious_arity(&mut self, form: &Value, frames: &[Frame]) {
+        if let Value::List(elems) = form {
+            if let Some(Value::Var(var)) = elems.first() {
+                let f = match var_impl_into_inner(var) {
+                    Some(Value::Fn(f)) => f,
+                    Some(Value::FnWithCaptures(FnWithCapturesImpl { f, .. })) => f,
+                    _ => return,
+                };
+                let realized = elems.len() - 1;
+                if check_arity(&f, realized).is_err() {
+                    self.interpreter.report(
+                        DiagnosticKind::SuspiciousArity {
+                            name: var.identifier.clone(),
+                            realized,
+                        },
+                        &restore_param_names(form, frames),
+                    );
+                }
+            }
+        }
+    }
+
+    // reports a reference to a var whose metadata marks it as `:deprecated`,
+    // e.g. `(def! ^{:deprecated "use `bar`"} foo ...)`
+    fn report_deprecated_var(&mut self, var: &VarImpl, form: &Value) {
+        let meta = match var.meta() {
+            Some(Value::Map(meta)) => meta,
+            _ => return,
+        };
+        let note = match meta.get(&Value::Keyword("deprecated".to_string(), None)) {
+            Some(Value::String(note)) => Some(note.to_string()),
+            Some(deprecated) if deprecated.is_truthy() => None,
+            _ => return,
+        };
+        self.interpreter.report(
+            DiagnosticKind::DeprecatedVar {
+                namespace: var.namespace().to_string(),
+                name: var.identifier.clone(),
+                note,
+            },
+            form,
+        );
     }
 
     // Analyze symbols (recursively) in `form`:
@@ -309,7 +619,11 @@ impl<'a> Analyzer<'a> {
                     }
                     for scope in frame.scopes.iter().rev() {
                         match scope.get(identifier) {
-                            Some(Value::Symbol(resolved_identifier, None)) => {
+                            Some(resolved @ (Value::Symbol(_, None) | Value::Parameter(..))) => {
+                                // a quoted parameter stays the symbol as written
+                                if self.quoted {
+                                    return Ok(form.clone());
+                                }
                                 let reference_outlives_source = frame_index < current_frame_index;
                                 // NOTE: current particularity of the implementation is to _not_
                                 // capture forward declarations from `let*` bindings...
@@ -317,11 +631,21 @@ impl<'a> Analyzer<'a> {
                                     let captures_at_level = captures
                                         .last_mut()
                                         .expect("did push captures to grab earlier frame");
-                                    // TODO: work through lifetimes here to avoid cloning...
-                                    captures_at_level
-                                        .insert((frame_index, resolved_identifier.clone()));
+                                    let capture = match resolved {
+                                        Value::Parameter(index, level) => {
+                                            lambda_parameter_key(*index, *level)
+                                        }
+                                        _ => identifier.clone(),
+                                    };
+                                    captures_at_level.insert((frame_index, capture));
                                 }
-                                return Ok(Value::Symbol(resolved_identifier.clone(), None));
+                                return Ok(resolved.clone());
+                            }
+                            Some(constant) if is_constant(constant) => {
+                                if self.quoted {
+                                    return Ok(form.clone());
+                                }
+                                return Ok(constant.clone());
                             }
                             Some(other) => {
                                 unreachable!("encountered unexpected value in `Scope`: {}", other)
@@ -331,7 +655,20 @@ impl<'a> Analyzer<'a> {
                     }
                 }
                 self.interpreter
-                    .resolve_symbol_to_var(identifier, ns_opt.as_ref())
+                    .load_deferred_source_for(identifier, ns_opt.as_ref())?;
+                let resolved = self
+                    .interpreter
+                    .resolve_symbol_to_var(identifier, ns_opt.as_ref());
+                match &resolved {
+                    Ok(var @ Value::Var(inner)) => {
+                        self.interpreter.record_dependency(var);
+                        self.report_deprecated_var(inner, form)
+                    }
+                    // linted source is not evaluated, so its definitions are never made
+                    Err(EvaluationError::MissingVar(..)) if self.lint => return Ok(form.clone()),
+                    _ => {}
+                }
+                resolved
             }
             Value::List(elems) => {
                 if elems.is_empty() {
@@ -341,6 +678,7 @@ impl<'a> Analyzer<'a> {
                 let first = elems.first().unwrap();
                 let rest = elems.drop_first().expect("list is not empty");
                 if let Some(expansion) = self.interpreter.get_macro_expansion(first, &rest) {
+                    self.interpreter.record_expansion(first);
                     match expansion? {
                         Value::List(elems) => self.analyze_list_in_fn(&elems, frames, captures),
                         other => self.analyze_form_in_fn(&other, frames, captures),
@@ -374,6 +712,22 @@ impl<'a> Analyzer<'a> {
                 }
                 Ok(Value::Set(analyzed_elems))
             }
+            Value::Queue(elems) => {
+                let mut analyzed_elems = PersistentQueue::new();
+                for elem in elems.iter() {
+                    let analyzed_elem = self.analyze_form_in_fn(elem, frames, captures)?;
+                    analyzed_elems.enqueue_mut(analyzed_elem);
+                }
+                Ok(Value::Queue(analyzed_elems))
+            }
+            Value::WithMeta(form, meta) => {
+                let analyzed_form = self.analyze_form_in_fn(form, frames, captures)?;
+                let analyzed_meta = self.analyze_form_in_fn(meta, frames, captures)?;
+                Ok(Value::WithMeta(
+                    Box::new(analyzed_form),
+                    Box::new(analyzed_meta),
+                ))
+            }
             Value::Fn(_) => unreachable!(),
             Value::FnWithCaptures(_) => unreachable!(),
             Value::Primitive(_) => unreachable!(),
@@ -392,16 +746,26 @@ impl<'a> Analyzer<'a> {
     ) -> EvaluationResult<(Scope, bool)> {
         let mut parameters = Scope::new();
         let mut variadic = false;
+        let mut seen = HashSet::new();
         let params_count = params.len();
         for (index, param) in params.iter().enumerate() {
             match param {
                 Value::Symbol(s, None) if s == "&" => {
-                    if index + MIN_VARIADIC_PARAM_COUNT > params_count {
-                        return Err(SyntaxError::VariadicArgMissing.into());
+                    // `&` must be followed by exactly one parameter
+                    match (index + MIN_VARIADIC_PARAM_COUNT).cmp(&params_count) {
+                        Ordering::Greater => return Err(SyntaxError::VariadicArgMissing.into()),
+                        Ordering::Less => {
+                            return Err(SyntaxError::VariadicArgMustBeUnique(Value::Vector(
+                                params.clone(),
+                            ))
+                            .into())
+                        }
+                        Ordering::Equal => {}
                     }
                     variadic = true;
                 }
                 Value::Symbol(s, None) => {
+                    validate_unique_name(s, &mut seen)?;
                     if variadic {
                         if index + 1 != params_count {
                             return Err(SyntaxError::VariadicArgMustBeUnique(Value::Vector(
@@ -410,11 +774,9 @@ impl<'a> Analyzer<'a> {
                             .into());
                         }
 
-                        let parameter = lambda_parameter_key(index - 1, level);
-                        parameters.insert(s.to_string(), Value::Symbol(parameter, None));
+                        parameters.insert(s.to_string(), Value::Parameter(index - 1, level));
                     } else {
-                        let parameter = lambda_parameter_key(index, level);
-                        parameters.insert(s.to_string(), Value::Symbol(parameter, None));
+                        parameters.insert(s.to_string(), Value::Parameter(index, level));
                     }
                 }
                 other => {
@@ -442,18 +804,32 @@ impl<'a> Analyzer<'a> {
         captures: &mut Vec<CaptureSet>,
     ) -> EvaluationResult<Value> {
         let level = frames.len();
-        let (parameters, variadic) = self.extract_scope_from_fn_bindings(params, level)?;
+        let hinted_params = params;
+        let params = strip_param_metadata(params);
+        let (parameters, variadic) = self.extract_scope_from_fn_bindings(&params, level)?;
+        let checks = if self.interpreter.type_checks {
+            type_hint_checks(hinted_params, &parameters)
+        } else {
+            vec![]
+        };
         let arity = if variadic {
             parameters.len() - 1
         } else {
             parameters.len()
         };
-        let mut frame = Frame::default();
+        let mut frame = Frame {
+            param_names: params
+                .iter()
+                .filter(|param| !matches!(param, Value::Symbol(s, None) if s == "&"))
+                .cloned()
+                .collect(),
+            ..Frame::default()
+        };
         frame.scopes.push(parameters);
 
         frames.push(frame);
         // walk the `body`, resolving symbols where possible...
-        let mut analyzed_body = Vec::with_capacity(body.len());
+        let mut analyzed_body = checks;
         for form in body.iter() {
             let analyzed_form = self.analyze_form_in_fn(form, frames, captures)?;
             analyzed_body.push(analyzed_form);
@@ -462,12 +838,105 @@ impl<'a> Analyzer<'a> {
         Ok(Value::Fn(FnImpl {
             body: analyzed_body.into_iter().collect(),
             arity,
+            params: Box::new(params),
             level,
             variadic,
         }))
     }
 }
 
+fn is_throw(form: &Value) -> bool {
+    match form {
+        Value::List(elems) => matches!(elems.first(), Some(Value::Symbol(s, None)) if s == "throw"),
+        _ => false,
+    }
+}
+
+// like `references` but ignores any references made within `fn*` forms
+fn references_outside_fn(form: &Value, name: &str) -> bool {
+    match form {
+        Value::List(elems) => match elems.first() {
+            Some(Value::Symbol(s, None)) if s == "fn*" || s == "quote" => false,
+            _ => elems.iter().any(|elem| references_outside_fn(elem, name)),
+        },
+        Value::Vector(elems) => elems.iter().any(|elem| references_outside_fn(elem, name)),
+        Value::Map(elems) => elems
+            .iter()
+            .any(|(k, v)| references_outside_fn(k, name) || references_outside_fn(v, name)),
+        Value::Set(elems) => elems.iter().any(|elem| references_outside_fn(elem, name)),
+        other => references(other, name),
+    }
+}
+
+// `form` with the rewritten parameters of the enclosing `frames` named as written
+fn restore_param_names(form: &Value, frames: &[Frame]) -> Value {
+    match form {
+        Value::Parameter(index, level) => frames
+            .get(*level)
+            .and_then(|frame| frame.param_names.get(*index).cloned())
+            .unwrap_or_else(|| form.clone()),
+        Value::List(elems) => Value::List(
+            elems
+                .iter()
+                .map(|elem| restore_param_names(elem, frames))
+                .collect(),
+        ),
+        Value::Vector(elems) => Value::Vector(
+            elems
+                .iter()
+                .map(|elem| restore_param_names(elem, frames))
+                .collect(),
+        ),
+        Value::WithMeta(form, meta) => {
+            Value::WithMeta(Box::new(restore_param_names(form, frames)), meta.clone())
+        }
+        other => other.clone(),
+    }
+}
+
+// a parameter written `^meta name`, e.g. with a type hint, yielding the name and the metadata
+fn param_metadata(param: &Value) -> Option<(&Value, &Value)> {
+    match param {
+        Value::WithMeta(name, meta) if matches!(**name, Value::Symbol(..)) => Some((name, meta)),
+        _ => None,
+    }
+}
+
+// the metadata only documents the parameter, other than any type hints checked by
+// `type_hint_checks`, so is dropped
+fn strip_param_metadata(params: &PersistentVector<Value>) -> PersistentVector<Value> {
+    params
+        .iter()
+        .map(|param| match param_metadata(param) {
+            Some((name, _)) => name.clone(),
+            None => param.clone(),
+        })
+        .collect()
+}
+
+// forms checking the arguments bound to each parameter with a type hint in `params`
+// against its hint, see `InterpreterBuilder::with_type_checks`
+fn type_hint_checks(params: &PersistentVector<Value>, parameters: &Scope) -> Vec<Value> {
+    params
+        .iter()
+        .filter_map(|param| {
+            let (name, meta) = param_metadata(param)?;
+            let tag = hint_tag(meta)?;
+            let name = match name {
+                Value::Symbol(name, None) => name,
+                _ => return None,
+            };
+            let parameter = parameters.get(name)?.clone();
+            Some(list_with_values([
+                Value::Primitive(check_parameter),
+                Value::String(name.as_str().into()),
+                Value::String(tag.into()),
+                parameter,
+            ]))
+        })
+        .collect()
+}
+
 pub fn analyze_fn(
     interpreter: &mut Interpreter,
     body: PersistentList<Value>,
@@ -478,3 +947,14 @@ pub fn analyze_fn(
     let mut captures = vec![];
     analyzer.analyze_symbols_in_fn(body, params, &mut frames, &mut captures)
 }
+
+// analyzes `form` as the body of a fn, without evaluating it, reporting the issues
+// `analysis::lint` looks for along with those reported when analyzing fns to evaluate
+pub(crate) fn lint_form(interpreter: &mut Interpreter, form: &Value) -> EvaluationResult<()> {
+    let mut analyzer = Analyzer::new(interpreter);
+    analyzer.lint = true;
+    let body = PersistentList::new().push_front(form.clone());
+    analyzer
+        .analyze_symbols_in_fn(body, &PersistentVector::new(), &mut vec![], &mut vec![])
+        .map(|_| ())
+}
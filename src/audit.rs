@@ -0,0 +1,95 @@
+use crate::value::{list_with_values, map_with_values, Value};
+use std::fmt;
+use std::rc::Rc;
+
+/// An action of interest to hosts auditing what evaluated code did,
+/// see `InterpreterBuilder::with_audit_log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    Define {
+        namespace: String,
+        name: String,
+    },
+    // invocation of a primitive performing IO with the arguments it was given
+    Io {
+        name: &'static str,
+        args: Vec<Value>,
+    },
+    NamespaceSwitch {
+        from: String,
+        to: String,
+    },
+}
+
+impl AuditEvent {
+    /// Represents this event as a map, e.g. `{:event :define :namespace "core" :name "foo"}`.
+    pub fn to_value(&self) -> Value {
+        let keyword = |name: &str| Value::Keyword(name.to_string(), None);
+        let string = |s: &str| Value::String(s.into());
+        let entries = match self {
+            AuditEvent::Define { namespace, name } => vec![
+                (keyword("event"), keyword("define")),
+                (keyword("namespace"), string(namespace)),
+                (keyword("name"), string(name)),
+            ],
+            AuditEvent::Io { name, args } => vec![
+                (keyword("event"), keyword("io")),
+                (keyword("name"), string(name)),
+                (keyword("args"), list_with_values(args.iter().cloned())),
+            ],
+            AuditEvent::NamespaceSwitch { from, to } => vec![
+                (keyword("event"), keyword("namespace-switch")),
+                (keyword("from"), string(from)),
+                (keyword("to"), string(to)),
+            ],
+        };
+        map_with_values(entries)
+    }
+}
+
+pub(crate) type AuditHook = Rc<dyn Fn(&AuditEvent)>;
+
+#[derive(Clone, Default)]
+pub(crate) struct AuditLog {
+    events: Vec<AuditEvent>,
+    // called with each event as it is recorded
+    hook: Option<AuditHook>,
+}
+
+impl AuditLog {
+    pub(crate) fn new(hook: Option<AuditHook>) -> Self {
+        Self {
+            events: vec![],
+            hook,
+        }
+    }
+
+    // a log for a forked interpreter, notifying the same hook
+    pub(crate) fn fork(&self) -> Self {
+        Self::new(self.hook.clone())
+    }
+
+    pub(crate) fn record(&mut self, event: AuditEvent) {
+        if let Some(hook) = &self.hook {
+            hook(&event);
+        }
+        self.events.push(event);
+    }
+
+    pub(crate) fn events(&self) -> &[AuditEvent] {
+        &self.events
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<AuditEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("events", &self.events)
+            .field("hook", &self.hook.as_ref().map(|_| "<fn>"))
+            .finish()
+    }
+}
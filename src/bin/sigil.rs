@@ -0,0 +1,25 @@
+//! Executes the sigil script given as the first argument, e.g. from a `#!/usr/bin/env sigil` line,
+//! with any remaining arguments bound to `*command-line-args*`.
+use sigil::Interpreter;
+use std::{env, process};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let path = match args.next() {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: sigil <script> [args...]");
+            process::exit(2);
+        }
+    };
+
+    let mut interpreter = Interpreter::default();
+    interpreter.intern_args(args);
+    if let Err(err) = interpreter.evaluate_file(&path) {
+        if let Some(status) = err.exit_status() {
+            process::exit(status);
+        }
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
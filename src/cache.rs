@@ -0,0 +1,270 @@
+use crate::value::{
+    PersistentList, PersistentMap, PersistentQueue, PersistentSet, PersistentVector, Value,
+};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use uuid::Uuid;
+
+// identifies files written by this version of the encoding below
+const MAGIC: &[u8] = b"SIGC\x02";
+
+/// Forms read from module sources, stored on disk under a hash of the source they
+/// were read from so later processes can skip reading unchanged files,
+/// see `InterpreterBuilder::with_compile_cache`.
+#[derive(Debug, Clone)]
+pub(crate) struct CompileCache {
+    dir: PathBuf,
+}
+
+impl CompileCache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path_for(&self, source: &str) -> PathBuf {
+        let digest = Sha256::digest(source.as_bytes());
+        let mut name = String::with_capacity(2 * digest.len() + 6);
+        for byte in digest {
+            write!(name, "{:02x}", byte).expect("can write to string");
+        }
+        name.push_str(".sigilc");
+        self.dir.join(name)
+    }
+
+    // the forms read from `source` by an earlier `store`, if any are cached
+    pub(crate) fn load(&self, source: &str) -> Option<Vec<Value>> {
+        let bytes = fs::read(self.path_for(source)).ok()?;
+        let mut decoder = Decoder {
+            bytes: bytes.strip_prefix(MAGIC)?,
+        };
+        let count = decoder.len()?;
+        let forms = (0..count)
+            .map(|_| decoder.value())
+            .collect::<Option<Vec<_>>>()?;
+        decoder.bytes.is_empty().then_some(forms)
+    }
+
+    // caching is best effort: forms holding values without a literal representation,
+    // e.g. from a reader extension, are not stored and failures to write are ignored
+    pub(crate) fn store(&self, source: &str, forms: &[Value]) {
+        let mut bytes = MAGIC.to_vec();
+        encode_len(forms.len(), &mut bytes);
+        if forms.iter().all(|form| encode(form, &mut bytes)) {
+            let _ =
+                fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path_for(source), bytes));
+        }
+    }
+}
+
+fn encode_len(len: usize, bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(&(len as u64).to_le_bytes());
+}
+
+fn encode_str(s: &str, bytes: &mut Vec<u8>) {
+    encode_len(s.len(), bytes);
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+fn encode_name(identifier: &str, ns_opt: &Option<String>, bytes: &mut Vec<u8>) {
+    encode_str(identifier, bytes);
+    match ns_opt {
+        Some(ns) => {
+            bytes.push(1);
+            encode_str(ns, bytes);
+        }
+        None => bytes.push(0),
+    }
+}
+
+fn encode_all<'a>(
+    tag: u8,
+    len: usize,
+    values: impl IntoIterator<Item = &'a Value>,
+    bytes: &mut Vec<u8>,
+) -> bool {
+    bytes.push(tag);
+    encode_len(len, bytes);
+    values.into_iter().all(|value| encode(value, bytes))
+}
+
+// returns `false` if `value` cannot be encoded
+fn encode(value: &Value, bytes: &mut Vec<u8>) -> bool {
+    match value {
+        Value::Nil => bytes.push(0),
+        Value::Bool(b) => bytes.extend_from_slice(&[1, u8::from(*b)]),
+        Value::Number(n) => {
+            bytes.push(2);
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        Value::String(s) => {
+            bytes.push(3);
+            encode_str(s, bytes);
+        }
+        Value::Keyword(identifier, ns_opt) => {
+            bytes.push(4);
+            encode_name(identifier, ns_opt, bytes);
+        }
+        Value::Symbol(identifier, ns_opt) => {
+            bytes.push(5);
+            encode_name(identifier, ns_opt, bytes);
+        }
+        Value::List(elems) => return encode_all(6, elems.len(), elems, bytes),
+        Value::Vector(elems) => return encode_all(7, elems.len(), elems, bytes),
+        Value::Map(elems) => {
+            let entries = elems.iter().flat_map(|(k, v)| [k, v]);
+            return encode_all(8, elems.size(), entries, bytes);
+        }
+        Value::Set(elems) => return encode_all(9, elems.size(), elems, bytes),
+        Value::Bytes(data) => {
+            bytes.push(10);
+            encode_len(data.len(), bytes);
+            bytes.extend_from_slice(data);
+        }
+        Value::Uuid(uuid) => {
+            bytes.push(11);
+            bytes.extend_from_slice(uuid.as_bytes());
+        }
+        Value::Instant(millis) => {
+            bytes.push(12);
+            bytes.extend_from_slice(&millis.to_le_bytes());
+        }
+        Value::Queue(elems) => return encode_all(13, elems.len(), elems, bytes),
+        Value::WithMeta(form, meta) => return encode_all(14, 2, [&**form, &**meta], bytes),
+        _ => return false,
+    }
+    true
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < n {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|taken| taken[0])
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        let taken = self.take(8)?;
+        Some(i64::from_le_bytes(taken.try_into().ok()?))
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        let taken = self.take(8)?;
+        usize::try_from(u64::from_le_bytes(taken.try_into().ok()?)).ok()
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = self.len()?;
+        std::str::from_utf8(self.take(len)?).ok()
+    }
+
+    fn name(&mut self) -> Option<(String, Option<String>)> {
+        let identifier = self.str()?.to_string();
+        let ns_opt = match self.byte()? {
+            0 => None,
+            1 => Some(self.str()?.to_string()),
+            _ => return None,
+        };
+        Some((identifier, ns_opt))
+    }
+
+    fn values(&mut self) -> Option<Vec<Value>> {
+        let len = self.len()?;
+        // guard against a corrupt length reserving more than could be decoded
+        let mut values = Vec::with_capacity(len.min(self.bytes.len()));
+        for _ in 0..len {
+            values.push(self.value()?);
+        }
+        Some(values)
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        let value = match self.byte()? {
+            0 => Value::Nil,
+            1 => Value::Bool(self.byte()? != 0),
+            2 => Value::Number(self.i64()?),
+            3 => Value::String(self.str()?.into()),
+            4 => {
+                let (identifier, ns_opt) = self.name()?;
+                Value::Keyword(identifier, ns_opt)
+            }
+            5 => {
+                let (identifier, ns_opt) = self.name()?;
+                Value::Symbol(identifier, ns_opt)
+            }
+            6 => Value::List(PersistentList::from_iter(self.values()?)),
+            7 => Value::Vector(PersistentVector::from_iter(self.values()?)),
+            8 => {
+                let len = self.len()?;
+                let mut map = PersistentMap::new();
+                for _ in 0..len {
+                    let key = self.value()?;
+                    let value = self.value()?;
+                    map.insert_mut(key, value);
+                }
+                Value::Map(map)
+            }
+            9 => Value::Set(PersistentSet::from_iter(self.values()?)),
+            10 => {
+                let len = self.len()?;
+                Value::Bytes(Rc::from(self.take(len)?))
+            }
+            11 => Value::Uuid(Uuid::from_bytes(self.take(16)?.try_into().ok()?)),
+            12 => Value::Instant(self.i64()?),
+            13 => Value::Queue(PersistentQueue::from_iter(self.values()?)),
+            14 => {
+                let mut values = self.values()?.into_iter();
+                match (values.next(), values.next(), values.next()) {
+                    (Some(form), Some(meta), None) => {
+                        Value::WithMeta(Box::new(form), Box::new(meta))
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompileCache;
+    use crate::reader::read;
+    use std::{env, fs, process};
+
+    #[test]
+    fn test_round_trip() {
+        let dir = env::temp_dir().join(format!("sigil-compile-cache-{}", process::id()));
+        let cache = CompileCache::new(dir.clone());
+        let source = r#"(def! x {:a/b [1 -2 "three" #{nil true}] 'sym #bytes "aGk="})
+            #uuid "550e8400-e29b-41d4-a716-446655440000" #inst "2024-01-02T03:04:05Z" () #queue [1 x] ^:m [2]"#;
+        assert!(cache.load(source).is_none());
+        let forms = read(source).unwrap();
+        cache.store(source, &forms);
+        // compared by their debug output, which unlike equality shows metadata
+        assert_eq!(
+            format!("{:?}", cache.load(source).unwrap()),
+            format!("{:?}", forms)
+        );
+        assert!(cache.load("(def! x 2)").is_none());
+
+        // a corrupt entry is ignored
+        fs::write(cache.path_for(source), b"SIGC\x02\xff").unwrap();
+        assert!(cache.load(source).is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use crate::value::{var_impl_into_inner, PersistentList, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+// the namespace and name of a var
+pub(crate) type VarName = (String, String);
+
+/// Reported to the hook given to `InterpreterBuilder::with_var_change_hook`
+/// each time an existing var is redefined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarChange {
+    pub namespace: String,
+    pub name: String,
+    // every var whose definition refers to this var in a fn body or macro expansion,
+    // directly or through other vars, as `(namespace, name)` pairs
+    pub dependents: Vec<(String, String)>,
+}
+
+type VarChangeFn = dyn Fn(&VarChange);
+
+#[derive(Clone)]
+pub(crate) struct VarChangeHook(Rc<VarChangeFn>);
+
+impl VarChangeHook {
+    pub(crate) fn new(hook: impl Fn(&VarChange) + 'static) -> Self {
+        Self(Rc::new(hook))
+    }
+
+    pub(crate) fn notify(&self, change: &VarChange) {
+        (self.0)(change)
+    }
+}
+
+impl fmt::Debug for VarChangeHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("VarChangeHook(<fn>)")
+    }
+}
+
+// the `fn*` defining a fn (or a macro if `is_macro`) to analyze again when a macro it
+// expanded is redefined
+#[derive(Debug, Clone)]
+pub(crate) struct Definition {
+    // the parameters and body following `fn*`
+    pub(crate) fn_operands: PersistentList<Value>,
+    pub(crate) is_macro: bool,
+}
+
+impl Definition {
+    // the definition of the fn `var` holds if `value_form` is the `fn*` which defined it
+    pub(crate) fn of_fn(var: &Value, value_form: &Value, is_macro: bool) -> Option<Self> {
+        let defines_fn = match var {
+            Value::Var(var) => matches!(var_impl_into_inner(var), Some(Value::Fn(_))),
+            _ => false,
+        };
+        match value_form {
+            Value::List(elems) if defines_fn => match elems.first() {
+                Some(Value::Symbol(operator, None)) if operator == "fn*" => Some(Self {
+                    fn_operands: elems.drop_first()?,
+                    is_macro,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+// which vars the analyzer resolved in the definition of each var
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DependencyGraph {
+    // var -> vars whose definitions referred to it
+    dependents: HashMap<VarName, HashSet<VarName>>,
+    // var -> vars its definition referred to, the reverse of `dependents`
+    references: HashMap<VarName, HashSet<VarName>>,
+    // var -> macros expanded while analyzing its definition
+    expansions: HashMap<VarName, HashSet<VarName>>,
+    // kept only for the definitions in `expansions`
+    definitions: HashMap<VarName, Definition>,
+    // definitions being evaluated, innermost last
+    defining: Vec<VarName>,
+    // redefined vars whose dependents are being invalidated, guarding against cycles
+    invalidating: Vec<VarName>,
+}
+
+impl DependencyGraph {
+    // starts recording the dependencies of `name`, replacing those of any earlier definition
+    pub(crate) fn begin_definition(&mut self, name: VarName) {
+        for reference in self.references.remove(&name).into_iter().flatten() {
+            if let Some(dependents) = self.dependents.get_mut(&reference) {
+                dependents.remove(&name);
+            }
+        }
+        self.expansions.remove(&name);
+        self.definitions.remove(&name);
+        self.defining.push(name);
+    }
+
+    // finishes the innermost definition, keeping `definition` if it expanded any macro
+    pub(crate) fn end_definition(&mut self, definition: Option<Definition>) {
+        let name = self.defining.pop().expect("ends a definition that began");
+        if let Some(definition) = definition.filter(|_| self.expansions.contains_key(&name)) {
+            self.definitions.insert(name, definition);
+        }
+    }
+
+    // whether a definition is being evaluated, so references are worth recording
+    pub(crate) fn is_defining(&self) -> bool {
+        !self.defining.is_empty()
+    }
+
+    pub(crate) fn record_reference(&mut self, var: &VarName) {
+        if let Some(current) = self.defining.last() {
+            if current != var {
+                self.dependents
+                    .entry(var.clone())
+                    .or_default()
+                    .insert(current.clone());
+                self.references
+                    .entry(current.clone())
+                    .or_default()
+                    .insert(var.clone());
+            }
+        }
+    }
+
+    pub(crate) fn record_expansion(&mut self, var: &VarName) {
+        self.record_reference(var);
+        if let Some(current) = self.defining.last() {
+            self.expansions
+                .entry(current.clone())
+                .or_default()
+                .insert(var.clone());
+        }
+    }
+
+    // every var depending on `name`, directly or transitively, in the order they were reached
+    pub(crate) fn dependents_of(&self, name: &VarName) -> Vec<VarName> {
+        let mut seen = HashSet::new();
+        let mut result = vec![];
+        let mut pending = vec![name];
+        while let Some(name) = pending.pop() {
+            let mut dependents = self
+                .dependents
+                .get(name)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            dependents.sort();
+            for dependent in dependents {
+                if dependent != name && seen.insert(dependent) {
+                    result.push(dependent.clone());
+                    pending.push(dependent);
+                }
+            }
+        }
+        result
+    }
+
+    // the definitions which expanded the macro `name` and so hold its previous expansion
+    pub(crate) fn expanders_of(&self, name: &VarName) -> Vec<(VarName, Definition)> {
+        let mut result = self
+            .expansions
+            .iter()
+            .filter(|(_, macros)| macros.contains(name))
+            .filter_map(|(var, _)| {
+                let definition = self.definitions.get(var)?;
+                Some((var.clone(), definition.clone()))
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result
+    }
+
+    // drops everything recorded about the vars of `namespace`
+    pub(crate) fn forget_namespace(&mut self, namespace: &str) {
+        let in_namespace = |(ns, _): &VarName| ns == namespace;
+        self.dependents.retain(|name, _| !in_namespace(name));
+        for dependents in self.dependents.values_mut() {
+            dependents.retain(|name| !in_namespace(name));
+        }
+        self.references.retain(|name, _| !in_namespace(name));
+        for references in self.references.values_mut() {
+            references.retain(|name| !in_namespace(name));
+        }
+        self.expansions.retain(|name, _| !in_namespace(name));
+        self.definitions.retain(|name, _| !in_namespace(name));
+    }
+
+    pub(crate) fn begin_invalidation(&mut self, name: VarName) {
+        self.invalidating.push(name);
+    }
+
+    pub(crate) fn end_invalidation(&mut self) {
+        self.invalidating.pop();
+    }
+
+    pub(crate) fn is_invalidating(&self, name: &VarName) -> bool {
+        self.invalidating.contains(name)
+    }
+}
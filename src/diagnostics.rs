@@ -0,0 +1,127 @@
+use crate::value::{PersistentList, Value};
+use std::fmt;
+
+/// A non-fatal issue found while analyzing or evaluating some `form`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    // the form the issue was found in, standing in for a source position
+    pub form: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    UnusedBinding(String),
+    // a var in some namespace hides the var of the same name in `core`
+    ShadowedVar {
+        namespace: String,
+        name: String,
+    },
+    SuspiciousArity {
+        name: String,
+        realized: usize,
+    },
+    UnusedParameter(String),
+    // the form follows a `throw` so it is never evaluated
+    UnreachableForm,
+    // the value of a `def!` refers to the var being defined outside of any fn
+    SelfReferentialDef(String),
+    // a var of a removed namespace is kept alive by some value, e.g. a fn referring to it
+    RetainedVar {
+        namespace: String,
+        name: String,
+    },
+    // a fn body refers to a var whose metadata marks it as `:deprecated`,
+    // with the note given as its value if that is a string
+    DeprecatedVar {
+        namespace: String,
+        name: String,
+        note: Option<String>,
+    },
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::UnusedBinding(name) => write!(f, "binding `{}` is never used", name),
+            DiagnosticKind::ShadowedVar { namespace, name } => write!(
+                f,
+                "var `{}/{}` shadows the var of the same name in `core`",
+                namespace, name
+            ),
+            DiagnosticKind::SuspiciousArity { name, realized } => write!(
+                f,
+                "`{}` is invoked with {} arguments which does not match its arity",
+                name, realized
+            ),
+            DiagnosticKind::UnusedParameter(name) => {
+                write!(f, "parameter `{}` is never used", name)
+            }
+            DiagnosticKind::UnreachableForm => write!(f, "form is unreachable"),
+            DiagnosticKind::SelfReferentialDef(name) => {
+                write!(f, "definition of `{}` refers to itself", name)
+            }
+            DiagnosticKind::RetainedVar { namespace, name } => write!(
+                f,
+                "var `{}/{}` of a removed namespace is still referenced",
+                namespace, name
+            ),
+            DiagnosticKind::DeprecatedVar {
+                namespace,
+                name,
+                note,
+            } => {
+                write!(f, "var `{}/{}` is deprecated", namespace, name)?;
+                match note {
+                    Some(note) => write!(f, ": {}", note),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "warning: {} in `{}`",
+            self.kind,
+            self.form.to_readable_string()
+        )
+    }
+}
+
+// whether `name` is referred to anywhere in `form`
+pub(crate) fn references(form: &Value, name: &str) -> bool {
+    match form {
+        Value::Symbol(s, None) => s == name,
+        Value::List(elems) => elems.iter().any(|elem| references(elem, name)),
+        Value::Vector(elems) => elems.iter().any(|elem| references(elem, name)),
+        Value::Map(elems) => elems
+            .iter()
+            .any(|(k, v)| references(k, name) || references(v, name)),
+        Value::Set(elems) => elems.iter().any(|elem| references(elem, name)),
+        _ => false,
+    }
+}
+
+// names bound by `let*`-style `bindings` that are not referenced by any later
+// binding nor `body`; names starting with `_` are taken as intentionally unused
+pub(crate) fn unused_bindings<'a>(
+    bindings: &[(&'a String, &Value)],
+    body: &PersistentList<Value>,
+) -> Vec<&'a String> {
+    bindings
+        .iter()
+        .enumerate()
+        .filter(|(index, (name, _))| {
+            !name.starts_with('_')
+                && !bindings[index + 1..]
+                    .iter()
+                    .any(|(_, value)| references(value, name))
+                && !body.iter().any(|form| references(form, name))
+        })
+        .map(|(_, (name, _))| *name)
+        .collect()
+}
@@ -0,0 +1,105 @@
+//! API references generated from the vars of an interpreter and their docstrings.
+
+use crate::interpreter::Interpreter;
+use crate::value::{FnImpl, FnWithCapturesImpl, Value};
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// Markdown describing every public var of each namespace loaded in `interpreter`,
+/// including built-ins, with its signature and any docstring, e.g. `(defn f "doc" [x] ..)`.
+/// Vars marked `^:private` are left out. Parts of `core` implemented in sigil are only
+/// described once loaded, see `Interpreter::load_deferred_sources`.
+pub fn generate_docs(interpreter: &Interpreter) -> String {
+    let mut docs = String::from("# API reference\n");
+    let namespaces = interpreter
+        .namespaces()
+        .iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b));
+    for (name, namespace) in namespaces {
+        let entries = namespace
+            .symbols()
+            .sorted()
+            .filter_map(|identifier| match namespace.get(identifier) {
+                Some(Value::Var(var)) => {
+                    let meta = var.meta();
+                    if is_private(meta.as_ref()) {
+                        return None;
+                    }
+                    let value = interpreter.var_value(var);
+                    Some(entry(identifier, value.as_ref(), meta.as_ref()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            continue;
+        }
+        write!(&mut docs, "\n## `{}`\n", name).expect("can write to string");
+        for entry in entries {
+            docs.push_str(&entry);
+        }
+    }
+    docs
+}
+
+fn is_private(meta: Option<&Value>) -> bool {
+    matches!(
+        meta.and_then(|meta| meta.get_path(&["private"])),
+        Some(Value::Bool(true))
+    )
+}
+
+// the section describing the var `identifier` holding `value`
+fn entry(identifier: &str, value: Option<&Value>, meta: Option<&Value>) -> String {
+    let signature = |f: &FnImpl| {
+        let params = f.params.iter().map(Value::to_string).join(" ");
+        if params.is_empty() {
+            format!("`({})`", identifier)
+        } else {
+            format!("`({} {})`", identifier, params)
+        }
+    };
+    let usage = match value {
+        Some(Value::Fn(f)) | Some(Value::FnWithCaptures(FnWithCapturesImpl { f, .. })) => {
+            signature(f)
+        }
+        Some(Value::Macro(f)) => format!("{} (macro)", signature(f)),
+        Some(Value::Primitive(_)) => "native fn".to_string(),
+        Some(_) => "value".to_string(),
+        None => "unbound".to_string(),
+    };
+    let mut entry = format!("\n### `{}`\n\n{}\n", identifier, usage);
+    if let Some(doc) = meta
+        .and_then(|meta| meta.get_path(&["doc"]))
+        .and_then(Value::as_str)
+    {
+        write!(&mut entry, "\n{}\n", doc).expect("can write to string");
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::InterpreterBuilder;
+
+    #[test]
+    fn test_generate_docs() {
+        let mut interpreter = InterpreterBuilder::default().build();
+        interpreter
+            .evaluate_from_source(
+                "(def! greeting-count 0)
+                 (defn greet \"Greets `who`.\" [who & more] (str \"hi \" who))
+                 (def! ^:private secret 1)
+                 (defmacro unless [test & body] `(if ~test nil (do ~@body)))",
+            )
+            .unwrap();
+        let docs = generate_docs(&interpreter);
+        assert!(docs.starts_with("# API reference\n\n## `core`\n"));
+        assert!(docs.contains("\n### `greet`\n\n`(greet who & more)`\n\nGreets `who`.\n"));
+        assert!(docs.contains("\n### `greeting-count`\n\nvalue\n"));
+        assert!(docs.contains("\n### `unless`\n\n`(unless test & body)` (macro)\n"));
+        assert!(docs.contains("\n### `+`\n\nnative fn\n"));
+        assert!(!docs.contains("secret"));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::value::{Value, VarImpl, WeakVarImpl};
+use std::fmt;
+
+#[derive(Clone)]
+enum Target {
+    Value(Value),
+    Var(VarImpl),
+    WeakVar(WeakVarImpl),
+}
+
+impl fmt::Debug for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Value(value) => write!(f, "Value({:?})", value),
+            Target::Var(var) => write!(f, "Var({})", Value::Var(var.clone())),
+            Target::WeakVar(var) => write!(f, "WeakVar({})", var.name()),
+        }
+    }
+}
+
+/// A callable value, e.g. a fn returned by a script, which a host can store
+/// and invoke later with `FnHandle::call`.
+///
+/// A handle to a var, e.g. from `Interpreter::fn_handle`, calls the current value of the var
+/// so it follows any redefinition without resolving the name again.
+#[derive(Debug, Clone)]
+pub struct FnHandle {
+    target: Target,
+}
+
+impl FnHandle {
+    /// Returns a handle to `f` which must be something sigil can invoke:
+    /// a fn, a closure, a primitive, a keyword or a var.
+    pub fn new(f: Value) -> EvaluationResult<Self> {
+        let target = match f {
+            Value::Var(var) => Target::Var(var),
+            f @ (Value::Fn(..)
+            | Value::FnWithCaptures(..)
+            | Value::Primitive(..)
+            | Value::Keyword(..)) => Target::Value(f),
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "Fn, FnWithCaptures, Primitive, Keyword, Var",
+                    realized: other,
+                })
+            }
+        };
+        Ok(Self { target })
+    }
+
+    /// Returns a handle which does not keep the var behind this handle alive, e.g. once
+    /// the interpreter defining it is dropped. Handles to other values are simply copied.
+    pub fn downgrade(&self) -> Self {
+        let target = match &self.target {
+            Target::Var(var) => Target::WeakVar(var.downgrade()),
+            target => target.clone(),
+        };
+        Self { target }
+    }
+
+    /// Whether calling this handle can still reach its target.
+    pub fn is_alive(&self) -> bool {
+        match &self.target {
+            Target::WeakVar(var) => var.upgrade().is_some(),
+            _ => true,
+        }
+    }
+
+    /// Applies the target of this handle to `args` with `interpreter`, see `Interpreter::call`.
+    pub fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+        match &self.target {
+            Target::Value(f) => interpreter.call(f, args),
+            Target::Var(var) => interpreter.call(&Value::Var(var.clone()), args),
+            Target::WeakVar(weak) => match weak.upgrade() {
+                Some(var) => interpreter.call(&Value::Var(var), args),
+                None => Err(EvaluationError::ReleasedFnHandle(weak.name())),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FnHandle;
+    use crate::interpreter::{EvaluationError, Interpreter};
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_fn_handle() {
+        let mut interpreter = Interpreter::default();
+        let results = interpreter
+            .evaluate_from_source("(defn greet [name] (str \"hi \" name)) (fn* [x] (* x 2))")
+            .unwrap();
+        let double = FnHandle::new(results[1].clone()).unwrap();
+        // `results` also holds the var `greet`
+        drop(results);
+        assert_eq!(
+            double.call(&mut interpreter, &[Number(4)]).unwrap(),
+            Number(8)
+        );
+        assert!(FnHandle::new(Number(1)).is_err());
+
+        let greet = interpreter.fn_handle("core", "greet").unwrap();
+        assert_eq!(
+            greet
+                .call(&mut interpreter, &[String("ada".into())])
+                .unwrap(),
+            String("hi ada".into())
+        );
+        // a var handle follows redefinitions
+        interpreter
+            .evaluate_from_source("(defn greet [name] (str \"hello \" name))")
+            .unwrap();
+        assert_eq!(
+            greet
+                .call(&mut interpreter, &[String("ada".into())])
+                .unwrap(),
+            String("hello ada".into())
+        );
+        assert!(interpreter.fn_handle("core", "does-not-exist").is_err());
+
+        let weak = greet.downgrade();
+        assert!(weak.is_alive());
+        assert_eq!(
+            weak.call(&mut interpreter, &[String("bob".into())])
+                .unwrap(),
+            String("hello bob".into())
+        );
+        drop(greet);
+        drop(interpreter);
+        assert!(!weak.is_alive());
+        let mut interpreter = Interpreter::default();
+        assert!(matches!(
+            weak.call(&mut interpreter, &[]),
+            Err(EvaluationError::ReleasedFnHandle(name)) if name == "core/greet"
+        ));
+        // handles to values are not affected by downgrading
+        let double = double.downgrade();
+        assert_eq!(
+            double.call(&mut interpreter, &[Number(1)]).unwrap(),
+            Number(2)
+        );
+    }
+}
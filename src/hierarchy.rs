@@ -0,0 +1,74 @@
+use crate::value::{PersistentMap, PersistentSet, Value};
+
+/// Relationships between tags (keywords or symbols) built with `derive!`,
+/// consulted by `isa?` for keyword-based dispatch.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hierarchy {
+    // the direct parents of each tag
+    parents: PersistentMap<Value, PersistentSet<Value>>,
+}
+
+impl Hierarchy {
+    pub(crate) fn parents(&self, tag: &Value) -> PersistentSet<Value> {
+        self.parents.get(tag).cloned().unwrap_or_default()
+    }
+
+    // every tag reachable from `tag` through its parents
+    pub(crate) fn ancestors(&self, tag: &Value) -> PersistentSet<Value> {
+        let mut ancestors = PersistentSet::new();
+        let mut pending = vec![tag];
+        while let Some(tag) = pending.pop() {
+            for parent in self.parents.get(tag).into_iter().flatten() {
+                if !ancestors.contains(parent) {
+                    ancestors.insert_mut(parent.clone());
+                    pending.push(parent);
+                }
+            }
+        }
+        ancestors
+    }
+
+    // every tag `tag` is an ancestor of
+    pub(crate) fn descendants(&self, tag: &Value) -> PersistentSet<Value> {
+        self.parents
+            .keys()
+            .filter(|child| self.ancestors(child).contains(tag))
+            .cloned()
+            .collect()
+    }
+
+    // `child` is `parent`, derives from it or, for vectors, each element of `child` is one
+    // of the corresponding element of `parent`
+    pub(crate) fn isa(&self, child: &Value, parent: &Value) -> bool {
+        if child == parent {
+            return true;
+        }
+        if let (Value::Vector(children), Value::Vector(parents)) = (child, parent) {
+            return children.len() == parents.len()
+                && children
+                    .iter()
+                    .zip(parents.iter())
+                    .all(|(child, parent)| self.isa(child, parent));
+        }
+        self.ancestors(child).contains(parent)
+    }
+
+    // returns `false` without changing the hierarchy if the derivation would make a cycle
+    pub(crate) fn derive(&mut self, child: Value, parent: Value) -> bool {
+        if self.isa(&parent, &child) {
+            return false;
+        }
+        let parents = self.parents(&child).insert(parent);
+        self.parents.insert_mut(child, parents);
+        true
+    }
+
+    pub(crate) fn underive(&mut self, child: &Value, parent: &Value) {
+        let parents = self.parents(child).remove(parent);
+        if parents.is_empty() {
+            self.parents.remove_mut(child);
+        } else {
+            self.parents.insert_mut(child.clone(), parents);
+        }
+    }
+}
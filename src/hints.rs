@@ -0,0 +1,56 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::value::Value;
+
+// the tag of metadata written as a type hint, e.g. `^long` reads as `{:tag "long"}`
+pub(crate) fn hint_tag(meta: &Value) -> Option<&str> {
+    meta.get_path(&["tag"]).and_then(Value::as_str)
+}
+
+// whether `value` has the type named by `tag`; tags naming types sigil does not know,
+// e.g. Java classes in code shared with Clojure, only document the value so always match
+pub(crate) fn satisfies(tag: &str, value: &Value) -> bool {
+    match tag {
+        "long" | "int" | "number" | "Long" | "Integer" | "Number" => {
+            matches!(value, Value::Number(..))
+        }
+        "string" | "String" => matches!(value, Value::String(..)),
+        "boolean" | "bool" | "Boolean" => matches!(value, Value::Bool(..)),
+        "keyword" | "Keyword" => matches!(value, Value::Keyword(..)),
+        "symbol" | "Symbol" => matches!(value, Value::Symbol(..)),
+        "list" | "List" => matches!(value, Value::List(..)),
+        "vector" | "Vector" => matches!(value, Value::Vector(..)),
+        "map" | "Map" => matches!(value, Value::Map(..)),
+        "set" | "Set" => matches!(value, Value::Set(..)),
+        "fn" | "Fn" => matches!(
+            value,
+            Value::Fn(..) | Value::FnWithCaptures(..) | Value::Primitive(..)
+        ),
+        "bytes" => matches!(value, Value::Bytes(..)),
+        "uuid" => matches!(value, Value::Uuid(..)),
+        "inst" => matches!(value, Value::Instant(..)),
+        _ => true,
+    }
+}
+
+pub(crate) fn check_type_hint(name: &str, tag: &str, value: &Value) -> EvaluationResult<()> {
+    if satisfies(tag, value) {
+        Ok(())
+    } else {
+        Err(EvaluationError::TypeHintMismatch {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            realized: Box::new(value.clone()),
+        })
+    }
+}
+
+// invoked as `(check name tag param)` at the start of the body of a fn with hinted
+// parameters when type hints are checked, see `InterpreterBuilder::with_type_checks`
+pub(crate) fn check_parameter(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [Value::String(name), Value::String(tag), value] => {
+            check_type_hint(name, tag, value).map(|_| Value::Nil)
+        }
+        _ => unreachable!("only invoked by the analyzer"),
+    }
+}
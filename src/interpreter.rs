@@ -1,43 +1,148 @@
-use crate::analyzer::{analyze_fn, analyze_let, lambda_parameter_key, LetForm};
-use crate::lang::core;
-use crate::namespace::{Namespace, NamespaceError};
-use crate::reader::{read, ReadError};
+use crate::analyzer::{
+    analyze_fn, analyze_let, check_recur_in_tail_position, lambda_parameter_key,
+    parse_lambda_parameter_key, LetBindings, LetForm,
+};
+use crate::audit::{AuditEvent, AuditHook, AuditLog};
+use crate::cache::CompileCache;
+use crate::dependencies::{Definition, DependencyGraph, VarChange, VarChangeHook, VarName};
+use crate::diagnostics::{unused_bindings, Diagnostic, DiagnosticKind};
+use crate::handle::FnHandle;
+use crate::hierarchy::Hierarchy;
+use crate::hints::{check_type_hint, hint_tag};
+use crate::lang::{core, csv, hash, string, sys, time, uuid};
+use crate::module::{FilesystemResolver, ModuleResolver};
+use crate::namespace::{Namespace, NamespaceError, DEFAULT_NAME as DEFAULT_NAMESPACE};
+use crate::reader::{read, read_with_options, ReadError, ReadOptions};
+use crate::recovery::{Recovery, RecoveryHook};
+use crate::streams::Streams;
 use crate::value::{
-    exception_from_system_err, list_with_values, unbound_var, var_impl_into_inner, ExceptionImpl,
-    FnImpl, FnWithCapturesImpl, NativeFn, PersistentList, PersistentMap, PersistentSet,
-    PersistentVector, Value,
+    exception_from_system_err, exception_with_cause, list_with_values, local_cell, map_with_values,
+    next_var_generation, var_impl_into_inner, vector_with_values, ExceptionImpl, FnImpl,
+    FnWithCapturesImpl, NativeFn, PersistentList, PersistentMap, PersistentQueue, PersistentSet,
+    PersistentVector, Value, VarImpl,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::fmt::Write;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
-use std::rc::Rc;
-use std::time::SystemTimeError;
-use std::{fmt, io};
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::{Instant, SystemTimeError};
+use std::{fmt, fs, io};
 use thiserror::Error;
 
 const COMMAND_LINE_ARGS_SYMBOL: &str = "*command-line-args*";
-const SPECIAL_FORMS: &[&str] = &[
-    "def!",           // (def! symbol form)
-    "var",            // (var symbol)
-    "let*",           // (let* [bindings*] form*)
-    "loop*",          // (loop* [bindings*] form*)
-    "recur",          // (recur form*)
-    "if",             // (if predicate consequent alternate?)
-    "do",             // (do form*)
-    "fn*",            // (fn* [parameter*] form*)
-    "quote",          // (quote form)
-    "quasiquote",     // (quasiquote form)
-    "unquote",        // (unquote form)
-    "splice-unquote", // (splice-unquote form)
-    "defmacro!",      // (defmacro! symbol fn*-form)
-    "macroexpand",    // (macroexpand macro-form)
-    "try*",           // (try* form* catch*-form?)
-    "catch*",         // (catch* exc-symbol form*)
-];
+const FILE_SYMBOL: &str = "*file*";
+// most recent result first, see `Interpreter::record_result`
+const RECENT_RESULT_SYMBOLS: [&str; 3] = ["*1", "*2", "*3"];
+const RECENT_ERROR_SYMBOL: &str = "*e";
+// see `InterpreterBuilder::with_macro_expansion_limit`
+const DEFAULT_MACRO_EXPANSION_LIMIT: usize = 256;
+// see `Interpreter::diagnostics`
+const MAX_PENDING_DIAGNOSTICS: usize = 1024;
+// forms the interpreter evaluates itself, named by their operator symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SpecialForm {
+    Def,           // (def! symbol form)
+    Var,           // (var symbol)
+    Let,           // (let* [bindings*] form*)
+    Loop,          // (loop* [bindings*] form*)
+    Recur,         // (recur form*)
+    If,            // (if predicate consequent alternate?)
+    Do,            // (do form*)
+    Fn,            // (fn* [parameter*] form*)
+    Quote,         // (quote form)
+    Quasiquote,    // (quasiquote form)
+    Unquote,       // (unquote form)
+    SpliceUnquote, // (splice-unquote form)
+    Defmacro,      // (defmacro! symbol fn*-form)
+    Macroexpand,   // (macroexpand macro-form)
+    Try,           // (try* form* catch*-form? finally*-form?)
+    Catch,         // (catch* exc-symbol form*)
+    Finally,       // (finally* form*)
+}
+
+impl SpecialForm {
+    pub(crate) const ALL: [SpecialForm; 17] = [
+        SpecialForm::Def,
+        SpecialForm::Var,
+        SpecialForm::Let,
+        SpecialForm::Loop,
+        SpecialForm::Recur,
+        SpecialForm::If,
+        SpecialForm::Do,
+        SpecialForm::Fn,
+        SpecialForm::Quote,
+        SpecialForm::Quasiquote,
+        SpecialForm::Unquote,
+        SpecialForm::SpliceUnquote,
+        SpecialForm::Defmacro,
+        SpecialForm::Macroexpand,
+        SpecialForm::Try,
+        SpecialForm::Catch,
+        SpecialForm::Finally,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SpecialForm::Def => "def!",
+            SpecialForm::Var => "var",
+            SpecialForm::Let => "let*",
+            SpecialForm::Loop => "loop*",
+            SpecialForm::Recur => "recur",
+            SpecialForm::If => "if",
+            SpecialForm::Do => "do",
+            SpecialForm::Fn => "fn*",
+            SpecialForm::Quote => "quote",
+            SpecialForm::Quasiquote => "quasiquote",
+            SpecialForm::Unquote => "unquote",
+            SpecialForm::SpliceUnquote => "splice-unquote",
+            SpecialForm::Defmacro => "defmacro!",
+            SpecialForm::Macroexpand => "macroexpand",
+            SpecialForm::Try => "try*",
+            SpecialForm::Catch => "catch*",
+            SpecialForm::Finally => "finally*",
+        }
+    }
+
+    // the special form named by `identifier`, decided by one match rather than
+    // comparing `identifier` against the name of each special form in turn
+    pub(crate) fn from_name(identifier: &str) -> Option<Self> {
+        let form = match identifier {
+            "def!" => SpecialForm::Def,
+            "var" => SpecialForm::Var,
+            "let*" => SpecialForm::Let,
+            "loop*" => SpecialForm::Loop,
+            "recur" => SpecialForm::Recur,
+            "if" => SpecialForm::If,
+            "do" => SpecialForm::Do,
+            "fn*" => SpecialForm::Fn,
+            "quote" => SpecialForm::Quote,
+            "quasiquote" => SpecialForm::Quasiquote,
+            "unquote" => SpecialForm::Unquote,
+            "splice-unquote" => SpecialForm::SpliceUnquote,
+            "defmacro!" => SpecialForm::Defmacro,
+            "macroexpand" => SpecialForm::Macroexpand,
+            "try*" => SpecialForm::Try,
+            "catch*" => SpecialForm::Catch,
+            "finally*" => SpecialForm::Finally,
+            _ => return None,
+        };
+        Some(form)
+    }
+
+    // the special form `operator` names, if any
+    pub(crate) fn of(operator: &Value) -> Option<Self> {
+        match operator {
+            Value::Symbol(identifier, None) => Self::from_name(identifier),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Error, Clone)]
 pub enum InterpreterError {
@@ -45,10 +150,22 @@ pub enum InterpreterError {
     MissingCommandLineArg(usize, usize),
     #[error("namespace {0} not found")]
     MissingNamespace(String),
+    #[error("namespace {namespace} not found, searched: {}", searched.join(", "))]
+    UnresolvedNamespace {
+        namespace: String,
+        searched: Vec<String>,
+    },
     #[error("system time error: {0}")]
     SystemTimeError(#[from] SystemTimeError),
     #[error("io error: {0}")]
     IOError(IOErrorKindExt),
+    // requested by `sys/exit` and never caught by `try*`
+    #[error("exit requested with status {0}")]
+    Exit(i32),
+    #[error("namespace {0} is in use and cannot be removed")]
+    NamespaceInUse(String),
+    #[error("stream for {0} is closed")]
+    ClosedStream(String),
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +201,89 @@ pub enum SyntaxError {
     VariadicArgMissing,
     #[error("found multiple variadic arguments in `{0}`; only one is allowed.")]
     VariadicArgMustBeUnique(Value),
+    #[error("the name `{0}` is bound more than once in the same binding form")]
+    DuplicateBindingName(String),
+    #[error("`recur` must be in tail position of {} but found `{0}`", describe_recur_target(*.1))]
+    RecurNotInTailPosition(Value, Option<&'static str>),
+}
+
+impl SyntaxError {
+    // see `EvaluationError::data`
+    fn data(&self) -> Value {
+        let form = match self {
+            SyntaxError::LexicalBindingsMustBePaired(bindings) => Value::Vector(bindings.clone()),
+            SyntaxError::LexicalBindingsMustBeVector(form)
+            | SyntaxError::LexicalBindingsMustHaveSymbolNames(form)
+            | SyntaxError::VariadicArgMustBeUnique(form)
+            | SyntaxError::RecurNotInTailPosition(form, _) => form.clone(),
+            SyntaxError::DuplicateBindingName(name) => Value::Symbol(name.clone(), None),
+            SyntaxError::VariadicArgMissing => Value::Nil,
+        };
+        error_data(
+            "syntax-error",
+            [
+                ("message", Value::String(self.to_string().into())),
+                ("form", form),
+            ],
+        )
+    }
+}
+
+impl InterpreterError {
+    // see `EvaluationError::data`
+    fn data(&self) -> Value {
+        let string = |s: &str| Value::String(s.into());
+        match self {
+            InterpreterError::MissingCommandLineArg(index, count) => error_data(
+                "missing-command-line-arg",
+                [
+                    ("index", Value::Number(*index as i64)),
+                    ("count", Value::Number(*count as i64)),
+                ],
+            ),
+            InterpreterError::MissingNamespace(namespace) => {
+                error_data("missing-namespace", [("namespace", string(namespace))])
+            }
+            InterpreterError::UnresolvedNamespace {
+                namespace,
+                searched,
+            } => error_data(
+                "missing-namespace",
+                [
+                    ("namespace", string(namespace)),
+                    (
+                        "searched",
+                        vector_with_values(searched.iter().map(|path| string(path))),
+                    ),
+                ],
+            ),
+            InterpreterError::SystemTimeError(err) => {
+                error_data("system-time-error", [("message", string(&err.to_string()))])
+            }
+            InterpreterError::IOError(err) => {
+                error_data("io-error", [("message", string(&err.to_string()))])
+            }
+            InterpreterError::Exit(status) => {
+                error_data("exit", [("status", Value::Number(*status as i64))])
+            }
+            InterpreterError::NamespaceInUse(namespace) => {
+                error_data("namespace-in-use", [("namespace", string(namespace))])
+            }
+            InterpreterError::ClosedStream(stream) => {
+                error_data("closed-stream", [("stream", string(stream))])
+            }
+        }
+    }
+}
+
+// the data of an error caught as an exception, see `EvaluationError::data`
+fn error_data<'a>(kind: &str, fields: impl IntoIterator<Item = (&'a str, Value)>) -> Value {
+    let kind = ("type", Value::Keyword(kind.to_string(), None));
+    map_with_values(
+        std::iter::once(kind)
+            .chain(fields)
+            .map(|(name, value)| (Value::Keyword(name.to_string(), None), value)),
+    )
 }
 
 #[derive(Debug, Error, Clone)]
@@ -95,24 +295,65 @@ pub enum EvaluationError {
     },
     #[error("form invoked with incorrect arity: provided {realized} arguments but expected {expected} arguments")]
     WrongArity { expected: usize, realized: usize },
+    #[error("form invoked with incorrect arity: provided {realized} arguments but expected at least {expected} arguments")]
+    WrongArityAtLeast { expected: usize, realized: usize },
+    #[error("form invoked with incorrect arity: provided {realized} arguments but expected between {min} and {max} arguments")]
+    WrongArityRange {
+        min: usize,
+        max: usize,
+        realized: usize,
+    },
+    #[error(
+        "form invoked with incorrect arity: expected {params}, got {realized} {}",
+        if *realized == 1 { "arg" } else { "args" }
+    )]
+    WrongArityForParams { params: String, realized: usize },
+    #[error("could not apply `{name}`: {source}")]
+    WrongArityForFn {
+        name: String,
+        source: Box<EvaluationError>,
+    },
     #[error("var `{0}` not found in namespace `{1}`")]
     MissingVar(String, String),
     #[error("symbol `{0}` could not be resolved")]
     UnableToResolveSymbolToValue(String),
-    #[error("cannot invoke the supplied value `{0}`")]
-    CannotInvoke(Value),
+    #[error(
+        "cannot invoke the supplied value `{value}` in `{form}`{}",
+        invoke_hint(value)
+    )]
+    CannotInvoke { value: Box<Value>, form: String },
     #[error("missing value for captured symbol `{0}`")]
     MissingCapturedValue(String),
+    #[error("cannot redefine `{0}` as it names a special form or primitive")]
+    CannotRedefine(String),
     #[error("cannot deref an unbound var `{0}`")]
     CannotDerefUnboundVar(Value),
+    #[error("the var `{0}` behind this fn handle no longer exists")]
+    ReleasedFnHandle(String),
+    #[error("binding `{0}` was used before it was initialized")]
+    UninitializedBinding(String),
     #[error("overflow detected during arithmetic operation of {0} and {1}")]
     Overflow(i64, i64),
     #[error("could not negate {0}")]
     Negation(i64),
     #[error("underflow detected during arithmetic operation of {0} and {1}")]
     Underflow(i64, i64),
+    #[error("cannot divide {0} by zero")]
+    DivideByZero(i64),
     #[error("requested index {0} in collection with length {1}")]
     IndexOutOfBounds(usize, usize),
+    #[error("cannot pop from the empty collection `{0}`")]
+    CannotPopEmptyCollection(Value),
+    #[error("unknown encoding `{0}`; expected one of `:utf-8`, `:base64` or `:hex`")]
+    UnknownEncoding(Value),
+    #[error("could not decode `{0}` as {1}")]
+    CannotDecode(Value, &'static str),
+    #[error("invalid radix {0}; expected a radix between 2 and 36")]
+    InvalidRadix(i64),
+    #[error("invalid range: lower bound {0} is greater than upper bound {1}")]
+    InvalidRange(i64, i64),
+    #[error("cannot derive `{0}` from `{1}` as `{1}` already derives from `{0}`")]
+    CyclicDerivation(Box<Value>, Box<Value>),
     #[error("map cannot be constructed with an odd number of arguments: `{0}` with length `{1}`")]
     MapRequiresPairs(Value, usize),
     #[error("exception: {0}")]
@@ -124,14 +365,354 @@ pub enum EvaluationError {
     #[error("namespace error: {0}")]
     Namespace(#[from] NamespaceError),
     #[error("reader error: {0}")]
-    ReaderError(ReadError, String),
+    ReaderError(Box<ReadError>, String),
+    #[error("error reading `{0}` at line {1}: {2}")]
+    FileReaderError(String, usize, Box<ReadError>),
+    #[error("`{name}` is hinted as `{tag}` but found value `{realized}`")]
+    TypeHintMismatch {
+        name: String,
+        tag: String,
+        realized: Box<Value>,
+    },
+    #[error("expanding macro `{name}` exceeded the limit of {limit} nested expansions")]
+    MacroExpansionLimit { name: String, limit: usize },
+}
+
+#[cold]
+fn cannot_invoke(value: Value, forms: &PersistentList<Value>) -> EvaluationError {
+    EvaluationError::CannotInvoke {
+        value: Box::new(value),
+        form: truncated_form(&Value::List(forms.clone())),
+    }
+}
+
+// the longest printed form included in a `CannotInvoke` error
+const MAX_INVOKE_FORM_LEN: usize = 64;
+
+pub(crate) fn truncated_form(form: &Value) -> String {
+    let mut printed = form.to_string();
+    if let Some((index, _)) = printed.char_indices().nth(MAX_INVOKE_FORM_LEN) {
+        printed.truncate(index);
+        printed.push_str("...");
+    }
+    printed
+}
+
+// suggests what was likely meant when `value` was found in operator position
+fn invoke_hint(value: &Value) -> &'static str {
+    match value {
+        Value::Map(..) | Value::Set(..) => {
+            "; maps and sets cannot be invoked, use `(get coll k)` to look up a key"
+        }
+        Value::Vector(..) => "; vectors cannot be invoked, use `(nth v index)` to get an element",
+        Value::Var(..) => "; the var has no value",
+        Value::Nil | Value::Bool(..) | Value::Number(..) | Value::String(..) | Value::List(..) => {
+            "; to write a list of values rather than a call, quote it with `'` or use a vector"
+        }
+        _ => "",
+    }
+}
+
+// lets `Value`, which converts from itself infallibly, stand wherever a
+// conversion from a `Value` may fail with an `EvaluationError`
+impl From<std::convert::Infallible> for EvaluationError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
+impl EvaluationError {
+    /// The status requested with `(sys/exit status)` if evaluation stopped because of it.
+    pub fn exit_status(&self) -> Option<i32> {
+        match self {
+            EvaluationError::Interpreter(InterpreterError::Exit(status)) => Some(*status),
+            _ => None,
+        }
+    }
+
+    // the data of this error when caught as an exception, see `ex-data`: a map of `:type`
+    // to a keyword naming the kind of error and of the name of each of its fields to its value
+    pub(crate) fn data(&self) -> Value {
+        let string = |s: &str| Value::String(s.into());
+        let number = |n: usize| Value::Number(n as i64);
+        match self {
+            EvaluationError::WrongType { expected, realized } => error_data(
+                "wrong-type",
+                [
+                    ("expected", string(expected)),
+                    ("realized", realized.clone()),
+                ],
+            ),
+            EvaluationError::WrongArity { .. }
+            | EvaluationError::WrongArityAtLeast { .. }
+            | EvaluationError::WrongArityRange { .. }
+            | EvaluationError::WrongArityForParams { .. } => {
+                let (expected, realized) = self.arity().expect("is an arity error");
+                error_data(
+                    "arity-error",
+                    [("expected", expected), ("realized", number(realized))],
+                )
+            }
+            EvaluationError::WrongArityForFn { name, source } => match source.data() {
+                Value::Map(data) => {
+                    Value::Map(data.insert(Value::Keyword("name".to_string(), None), string(name)))
+                }
+                data => data,
+            },
+            EvaluationError::MissingVar(name, namespace) => error_data(
+                "missing-var",
+                [("name", string(name)), ("namespace", string(namespace))],
+            ),
+            EvaluationError::UnableToResolveSymbolToValue(symbol) => {
+                error_data("unresolved-symbol", [("symbol", string(symbol))])
+            }
+            EvaluationError::CannotInvoke { value, form } => error_data(
+                "cannot-invoke",
+                [("value", (**value).clone()), ("form", string(form))],
+            ),
+            EvaluationError::MissingCapturedValue(symbol) => {
+                error_data("missing-captured-value", [("symbol", string(symbol))])
+            }
+            EvaluationError::CannotRedefine(name) => {
+                error_data("cannot-redefine", [("name", string(name))])
+            }
+            EvaluationError::CannotDerefUnboundVar(var) => {
+                error_data("unbound-var", [("var", var.clone())])
+            }
+            EvaluationError::ReleasedFnHandle(name) => {
+                error_data("released-fn-handle", [("name", string(name))])
+            }
+            EvaluationError::UninitializedBinding(name) => {
+                error_data("uninitialized-binding", [("name", string(name))])
+            }
+            EvaluationError::Overflow(a, b) | EvaluationError::Underflow(a, b) => error_data(
+                "arithmetic-error",
+                [(
+                    "operands",
+                    vector_with_values([Value::Number(*a), Value::Number(*b)]),
+                )],
+            ),
+            EvaluationError::Negation(n) | EvaluationError::DivideByZero(n) => error_data(
+                "arithmetic-error",
+                [("operands", vector_with_values([Value::Number(*n)]))],
+            ),
+            EvaluationError::IndexOutOfBounds(index, length) => error_data(
+                "index-out-of-bounds",
+                [("index", number(*index)), ("length", number(*length))],
+            ),
+            EvaluationError::CannotPopEmptyCollection(coll) => {
+                error_data("empty-collection", [("collection", coll.clone())])
+            }
+            EvaluationError::UnknownEncoding(encoding) => {
+                error_data("unknown-encoding", [("encoding", encoding.clone())])
+            }
+            EvaluationError::CannotDecode(value, encoding) => error_data(
+                "cannot-decode",
+                [("value", value.clone()), ("encoding", string(encoding))],
+            ),
+            EvaluationError::InvalidRadix(radix) => {
+                error_data("invalid-radix", [("radix", Value::Number(*radix))])
+            }
+            EvaluationError::InvalidRange(lower, upper) => error_data(
+                "invalid-range",
+                [
+                    ("lower", Value::Number(*lower)),
+                    ("upper", Value::Number(*upper)),
+                ],
+            ),
+            EvaluationError::CyclicDerivation(tag, parent) => error_data(
+                "cyclic-derivation",
+                [("tag", (**tag).clone()), ("parent", (**parent).clone())],
+            ),
+            EvaluationError::MapRequiresPairs(value, length) => error_data(
+                "map-requires-pairs",
+                [("value", value.clone()), ("length", number(*length))],
+            ),
+            EvaluationError::Exception(exc) => exc.data(),
+            EvaluationError::Syntax(err) => err.data(),
+            EvaluationError::Interpreter(err) => err.data(),
+            EvaluationError::Namespace(NamespaceError::ValueInNamespaceWasNotVar(value)) => {
+                error_data("namespace-error", [("value", value.clone())])
+            }
+            EvaluationError::ReaderError(err, source) => error_data(
+                "reader-error",
+                [
+                    ("message", string(&err.to_string())),
+                    ("source", string(source)),
+                ],
+            ),
+            EvaluationError::FileReaderError(file, line, err) => error_data(
+                "reader-error",
+                [
+                    ("message", string(&err.to_string())),
+                    ("file", string(file)),
+                    ("line", number(*line)),
+                ],
+            ),
+            EvaluationError::TypeHintMismatch {
+                name,
+                tag,
+                realized,
+            } => error_data(
+                "type-hint-mismatch",
+                [
+                    ("name", string(name)),
+                    ("tag", string(tag)),
+                    ("realized", (**realized).clone()),
+                ],
+            ),
+            EvaluationError::MacroExpansionLimit { name, limit } => error_data(
+                "macro-expansion-limit",
+                [("name", string(name)), ("limit", number(*limit))],
+            ),
+        }
+    }
+
+    // the arguments expected and the number given by an arity error: a count, `[min max]`
+    // or the parameters of the fn applied, e.g. `"[x & more]"`
+    fn arity(&self) -> Option<(Value, usize)> {
+        match self {
+            EvaluationError::WrongArity { expected, realized }
+            | EvaluationError::WrongArityAtLeast { expected, realized } => {
+                Some((Value::Number(*expected as i64), *realized))
+            }
+            EvaluationError::WrongArityRange { min, max, realized } => Some((
+                vector_with_values([Value::Number(*min as i64), Value::Number(*max as i64)]),
+                *realized,
+            )),
+            EvaluationError::WrongArityForParams { params, realized } => {
+                Some((Value::String(params.as_str().into()), *realized))
+            }
+            _ => None,
+        }
+    }
 }
 
 pub type EvaluationResult<T> = Result<T, EvaluationError>;
+
+/// Determines how evaluation of a sequence of forms proceeds after an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Yield the first error and then stop.
+    Stop,
+    /// Yield every error and keep evaluating the remaining forms.
+    Continue,
+}
+
+/// Yields the result of each top-level form as it is evaluated.
+/// Constructed with `Interpreter::evaluate_forms_iter`.
+pub struct EvaluateForms<'a, I> {
+    interpreter: &'a mut Interpreter,
+    forms: I,
+    policy: ErrorPolicy,
+    halted: bool,
+}
+
+impl<'a, 'f, I> Iterator for EvaluateForms<'a, I>
+where
+    I: Iterator<Item = &'f Value>,
+{
+    type Item = EvaluationResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.halted {
+            return None;
+        }
+        let form = self.forms.next()?;
+        let result = self.interpreter.evaluate(form);
+        if result.is_err() && self.policy == ErrorPolicy::Stop {
+            self.halted = true;
+        }
+        Some(result)
+    }
+}
 pub type SymbolIndex = HashSet<String>;
-// maps identifiers to {Value::Symbol, Value::Var}
+
+// maps identifiers to {Value::Symbol, Value::Parameter, Value::Var}
 // `Var` variant is to allow for recursive fns in `let*`
-pub type Scope = HashMap<String, Value>;
+//
+// the parameters of a fn are stored by ordinal so that looking up a
+// `Value::Parameter` the analyzer resolved a reference to is an index;
+// any other bindings are kept in a flat list as scopes are typically small
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    // the `level` of the fn the parameters belong to and their values
+    parameters: Option<(usize, Vec<Value>)>,
+    // parameters of enclosing fns captured by a closure, keyed by `(index, level)`
+    captured_parameters: Vec<((usize, usize), Value)>,
+    bindings: Vec<(String, Value)>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_parameters(level: usize, parameters: Vec<Value>) -> Self {
+        Self {
+            parameters: Some((level, parameters)),
+            ..Self::default()
+        }
+    }
+
+    pub fn get(&self, identifier: &str) -> Option<&Value> {
+        if let Some((index, level)) = parse_lambda_parameter_key(identifier) {
+            return self.get_parameter(index, level);
+        }
+        self.bindings
+            .iter()
+            .find(|(name, _)| name == identifier)
+            .map(|(_, value)| value)
+    }
+
+    fn get_parameter(&self, index: usize, level: usize) -> Option<&Value> {
+        match &self.parameters {
+            Some((parameters_level, parameters)) if *parameters_level == level => {
+                parameters.get(index)
+            }
+            _ => self
+                .captured_parameters
+                .iter()
+                .find(|(key, _)| *key == (index, level))
+                .map(|(_, value)| value),
+        }
+    }
+
+    fn has_parameters(&self) -> bool {
+        self.parameters.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        let parameters_count = self
+            .parameters
+            .as_ref()
+            .map(|(_, parameters)| parameters.len())
+            .unwrap_or_default();
+        parameters_count + self.captured_parameters.len() + self.bindings.len()
+    }
+
+    pub fn insert(&mut self, identifier: String, value: Value) {
+        if let Some(key) = parse_lambda_parameter_key(&identifier) {
+            match self
+                .captured_parameters
+                .iter_mut()
+                .find(|(existing_key, _)| *existing_key == key)
+            {
+                Some((_, existing)) => *existing = value,
+                None => self.captured_parameters.push((key, value)),
+            }
+            return;
+        }
+        match self
+            .bindings
+            .iter_mut()
+            .find(|(name, _)| *name == identifier)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.bindings.push((identifier, value)),
+        }
+    }
+}
 
 // `scopes` from most specific to least specific
 fn resolve_symbol_in_scopes<'a>(
@@ -146,97 +727,114 @@ fn resolve_symbol_in_scopes<'a>(
     None
 }
 
+// a form holding no `unquote` or `splice-unquote` quasiquotes to itself
+fn is_quasiquote_constant(form: &Value) -> bool {
+    match form {
+        Value::List(elems) => {
+            !matches!(elems.first(), Some(Value::Symbol(s, None)) if s == "unquote" || s == "splice-unquote")
+                && elems.iter().all(is_quasiquote_constant)
+        }
+        Value::Vector(elems) => elems.iter().all(is_quasiquote_constant),
+        Value::WithMeta(form, _) => is_quasiquote_constant(form),
+        _ => true,
+    }
+}
+
+fn quote_form(form: &Value) -> Value {
+    list_with_values(vec![Value::Symbol("quote".to_string(), None), form.clone()])
+}
+
+fn core_call(name: &str, args: Vec<Value>) -> Value {
+    let mut form = vec![Value::Symbol(name.to_string(), Some("core".to_string()))];
+    form.extend(args);
+    list_with_values(form)
+}
+
+fn splice_unquote_argument(form: &Value) -> EvaluationResult<Option<Value>> {
+    match form {
+        Value::List(inner) => match inner.first() {
+            Some(Value::Symbol(s, None)) if s == "splice-unquote" => {
+                match inner.drop_first().and_then(|rest| rest.first().cloned()) {
+                    Some(argument) => Ok(Some(argument)),
+                    None => Err(EvaluationError::WrongArity {
+                        expected: 1,
+                        realized: 0,
+                    }),
+                }
+            }
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+// expands to a single `concat` of the spliced values and of `list`s of the runs
+// of elements between them, rather than one call per element
 fn eval_quasiquote_list_inner<'a>(
     elems: impl Iterator<Item = &'a Value>,
 ) -> EvaluationResult<Value> {
-    let mut result = Value::List(PersistentList::new());
+    let mut segments = vec![];
+    let mut run = vec![];
     for form in elems {
-        match form {
-            Value::List(inner) => {
-                if let Some(first_inner) = inner.first() {
-                    match first_inner {
-                        Value::Symbol(s, None) if s == "splice-unquote" => {
-                            if let Some(rest) = inner.drop_first() {
-                                if let Some(second) = rest.first() {
-                                    result = list_with_values(vec![
-                                        Value::Symbol(
-                                            "concat".to_string(),
-                                            Some("core".to_string()),
-                                        ),
-                                        second.clone(),
-                                        result,
-                                    ]);
-                                }
-                            } else {
-                                return Err(EvaluationError::WrongArity {
-                                    expected: 1,
-                                    realized: 0,
-                                });
-                            }
-                        }
-                        _ => {
-                            result = list_with_values(vec![
-                                Value::Symbol("cons".to_string(), Some("core".to_string())),
-                                eval_quasiquote(form)?,
-                                result,
-                            ]);
-                        }
-                    }
-                } else {
-                    result = list_with_values(vec![
-                        Value::Symbol("cons".to_string(), Some("core".to_string())),
-                        Value::List(PersistentList::new()),
-                        result,
-                    ]);
+        match splice_unquote_argument(form)? {
+            Some(argument) => {
+                if !run.is_empty() {
+                    segments.push(core_call("list", std::mem::take(&mut run)));
                 }
+                segments.push(argument);
             }
-            form => {
-                result = list_with_values(vec![
-                    Value::Symbol("cons".to_string(), Some("core".to_string())),
-                    eval_quasiquote(form)?,
-                    result,
-                ]);
-            }
+            None => run.push(eval_quasiquote(form)?),
         }
     }
-    Ok(result)
+    if segments.is_empty() {
+        return Ok(core_call("list", run));
+    }
+    if !run.is_empty() {
+        segments.push(core_call("list", run));
+    }
+    Ok(core_call("concat", segments))
 }
 
 fn eval_quasiquote_list(elems: &PersistentList<Value>) -> EvaluationResult<Value> {
-    if let Some(first) = elems.first() {
-        match first {
-            Value::Symbol(s, None) if s == "unquote" => {
-                if let Some(rest) = elems.drop_first() {
-                    if let Some(argument) = rest.first() {
-                        return Ok(argument.clone());
-                    }
+    if let Some(Value::Symbol(s, None)) = elems.first() {
+        if s == "unquote" {
+            if let Some(rest) = elems.drop_first() {
+                if let Some(argument) = rest.first() {
+                    return Ok(argument.clone());
                 }
-                return Err(EvaluationError::WrongArity {
-                    realized: 0,
-                    expected: 1,
-                });
             }
-            _ => return eval_quasiquote_list_inner(elems.reverse().iter()),
+            return Err(EvaluationError::WrongArity {
+                realized: 0,
+                expected: 1,
+            });
         }
     }
-    Ok(Value::List(PersistentList::new()))
+    eval_quasiquote_list_inner(elems.iter())
 }
 
 fn eval_quasiquote_vector(elems: &PersistentVector<Value>) -> EvaluationResult<Value> {
-    Ok(list_with_values(vec![
-        Value::Symbol("vec".to_string(), Some("core".to_string())),
-        eval_quasiquote_list_inner(elems.iter().rev())?,
-    ]))
+    Ok(core_call(
+        "vec",
+        vec![eval_quasiquote_list_inner(elems.iter())?],
+    ))
 }
 
 fn eval_quasiquote(value: &Value) -> EvaluationResult<Value> {
     match value {
+        form if is_quasiquote_constant(form) => match form {
+            Value::List(_)
+            | Value::Vector(_)
+            | Value::Map(_)
+            | Value::Symbol(..)
+            | Value::WithMeta(..) => Ok(quote_form(form)),
+            v => Ok(v.clone()),
+        },
         Value::List(elems) => eval_quasiquote_list(elems),
         Value::Vector(elems) => eval_quasiquote_vector(elems),
-        elem @ Value::Map(_) | elem @ Value::Symbol(..) => {
-            let args = vec![Value::Symbol("quote".to_string(), None), elem.clone()];
-            Ok(list_with_values(args.into_iter()))
-        }
+        Value::WithMeta(form, meta) => Ok(core_call(
+            "with-meta",
+            vec![eval_quasiquote(form)?, quote_form(meta)],
+        )),
         v => Ok(v.clone()),
     }
 }
@@ -258,6 +856,99 @@ where
     action(arg)
 }
 
+// the `loop*` or `fn*` form a `recur` must be in tail position of, if known
+fn describe_recur_target(enclosing: Option<&str>) -> String {
+    match enclosing {
+        Some(form) => format!("the enclosing `{}`", form),
+        None => "a `loop*` or `fn*`".to_string(),
+    }
+}
+
+// a `recur` that was not consumed by an enclosing `loop*` or `fn*` was not in tail position
+fn reject_recur(value: Value) -> EvaluationResult<Value> {
+    match value {
+        Value::Recur(args) => {
+            let form = std::iter::once(Value::Symbol("recur".to_string(), None))
+                .chain(args.iter().cloned());
+            Err(SyntaxError::RecurNotInTailPosition(list_with_values(form), None).into())
+        }
+        value => Ok(value),
+    }
+}
+
+pub(crate) fn check_arity(f: &FnImpl, args_count: usize) -> EvaluationResult<()> {
+    if (f.variadic && args_count < f.arity) || (!f.variadic && args_count != f.arity) {
+        return Err(wrong_arity_for_params(f, args_count));
+    }
+    Ok(())
+}
+
+// built apart from `check_arity`, which is inlined into every application of a fn
+#[cold]
+fn wrong_arity_for_params(f: &FnImpl, args_count: usize) -> EvaluationError {
+    EvaluationError::WrongArityForParams {
+        params: Value::Vector((*f.params).clone()).to_string(),
+        realized: args_count,
+    }
+}
+
+// the values of the parameters of `f`, with any variadic arguments passed as one list;
+// apart from `apply_fn_inner` to keep its frame small
+#[inline(never)]
+fn bind_parameters<'a>(f: &FnImpl, args: impl IntoIterator<Item = &'a Value>) -> Vec<Value> {
+    let mut parameters = Vec::with_capacity(f.arity + usize::from(f.variadic));
+    let mut iter = args.into_iter();
+    parameters.extend(iter.by_ref().take(f.arity).cloned());
+    if f.variadic {
+        parameters.push(Value::List(iter.cloned().collect()));
+    }
+    parameters
+}
+
+// most primitives see their operands without metadata, see `core::keeps_metadata`,
+// returning `None` if no operand has any
+fn strip_operand_metadata(native_fn: NativeFn, operands: &[Value]) -> Option<Vec<Value>> {
+    if !operands
+        .iter()
+        .any(|operand| matches!(operand, Value::WithMeta(..)))
+        || core::keeps_metadata(native_fn)
+    {
+        return None;
+    }
+    Some(
+        operands
+            .iter()
+            .map(|operand| operand.without_meta().clone())
+            .collect(),
+    )
+}
+
+// an arity error raised by a primitive, named as the fn called with the wrong arity
+fn name_arity_error(err: EvaluationError, name: Option<String>) -> EvaluationError {
+    match (err, name) {
+        (
+            err @ (EvaluationError::WrongArity { .. }
+            | EvaluationError::WrongArityAtLeast { .. }
+            | EvaluationError::WrongArityRange { .. }),
+            Some(name),
+        ) => EvaluationError::WrongArityForFn {
+            name,
+            source: Box::new(err),
+        },
+        (err, _) => err,
+    }
+}
+
+// name to report for the fn in `operator_form`, if one is known at the call site
+fn invocation_name(operator_form: &Value) -> Option<String> {
+    match operator_form {
+        Value::Symbol(id, None) => Some(id.clone()),
+        Value::Symbol(id, Some(ns)) => Some(format!("{}/{}", ns, id)),
+        Value::Var(var) => Some(var.identifier.clone()),
+        _ => None,
+    }
+}
+
 fn update_captures(
     captures: &mut HashMap<String, Option<Value>>,
     scopes: &[Scope],
@@ -274,885 +965,3748 @@ fn update_captures(
     Ok(())
 }
 
+/// Source for some namespace which is evaluated the first time any of its `names`
+/// is referenced rather than when the namespace is loaded.
+#[derive(Debug, Clone, Copy)]
+pub struct DeferredSource {
+    pub namespace: &'static str,
+    pub source: &'static str,
+}
+
+impl DeferredSource {
+    // the names defined by the top-level forms of `source` starting with a `def`-like
+    // symbol, e.g. `(defn name ...)` or `(defmacro! ^:private name ...)`
+    fn names(&self) -> Result<Vec<String>, ReadError> {
+        let forms = read(self.source)?;
+        Ok(forms
+            .iter()
+            .filter_map(|form| match form {
+                Value::List(elems) => match (elems.first(), elems.iter().nth(1)) {
+                    (Some(Value::Symbol(def, None)), Some(name)) if def.starts_with("def") => {
+                        match name.without_meta() {
+                            Value::Symbol(name, None) => Some(name.clone()),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+// fns called without arguments once `require` has loaded a namespace
+// and before `remove-ns!` removes it, see `ns-hooks`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NamespaceHooks {
+    pub(crate) on_load: Option<Value>,
+    pub(crate) on_unload: Option<Value>,
+}
+
+/// Describes the call of the primitive being applied, see `Interpreter::call_context`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallContext {
+    // the calling form, e.g. `(subs s 1)`, standing in for a source position
+    pub form: Value,
+    // the namespace current when the call was made
+    pub namespace: String,
+    // the file being evaluated, as bound to `*file*`, if any
+    pub file: Option<String>,
+}
+
+impl CallContext {
+    /// The name the primitive was invoked by, if it was called by name, e.g. `subs`.
+    pub fn name(&self) -> Option<String> {
+        match &self.form {
+            Value::List(forms) => forms.first().and_then(invocation_name),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Interpreter {
     current_namespace: String,
     namespaces: HashMap<String, Namespace>,
+    // fns declared by `ns-hooks`, by the namespace declaring them
+    namespace_hooks: HashMap<String, NamespaceHooks>,
     symbol_index: Option<Rc<RefCell<SymbolIndex>>>,
 
     // stack of scopes
     // contains at least one scope, the "default" scope
     pub(crate) scopes: Vec<Scope>,
 
-    // low-res backtrace
+    // low-res backtrace of the forms calling primitives, see `Interpreter::call_context`
     pub(crate) apply_stack: Vec<Value>,
     // index into `apply_stack` pointing at the first form to error
     failed_form: Option<usize>,
-}
 
-impl Default for Interpreter {
-    fn default() -> Self {
-        // build the default scope, which resolves special forms to themselves
-        // so that they fall through to the interpreter's evaluation
-        let mut default_scope = Scope::new();
-        for form in SPECIAL_FORMS {
-            default_scope.insert(form.to_string(), Value::Symbol(form.to_string(), None));
-        }
+    // pairs of (predicate, printer) fns consulted in order when printing values
+    pub(crate) print_methods: Vec<(Value, Value)>,
 
-        let mut interpreter = Interpreter {
-            current_namespace: String::new(),
-            namespaces: HashMap::new(),
-            symbol_index: None,
-            scopes: vec![default_scope],
-            apply_stack: vec![],
-            failed_form: None,
-        };
+    // tags related with `derive!`, see `isa?`
+    pub(crate) hierarchy: Hierarchy,
 
-        // load the "core" namespace
-        interpreter
-            .activate_namespace(core::loader)
-            .expect("is valid namespace");
+    // atoms made by `file-atom` and the file each is persisted to
+    pub(crate) file_atoms: Vec<(Weak<RefCell<Value>>, PathBuf)>,
 
-        // add support for `*command-line-args*`
-        let mut buffer = String::new();
-        let _ = write!(&mut buffer, "(def! {} '())", COMMAND_LINE_ARGS_SYMBOL)
-            .expect("can write to string");
-        interpreter
-            .evaluate_from_source(&buffer)
-            .expect("valid source");
+    // files opened by `reader` and `writer` and not yet closed
+    pub(crate) streams: Streams,
 
-        interpreter
-    }
-}
+    // sources not yet evaluated, see `DeferredSource`
+    deferred_sources: Vec<DeferredSource>,
+    // index into `deferred_sources` of the source defining each name, by namespace
+    deferred_names: Rc<HashMap<&'static str, HashMap<String, usize>>>,
 
-pub type NamespaceLoader = fn(&mut Interpreter) -> EvaluationResult<()>;
+    // the generation of vars started when this interpreter was last forked, or made by
+    // forking, before which vars may be shared with another interpreter, see `owned_var`
+    fork_generation: Cell<u64>,
+    // copies made by `owned_var` by the address of the var each replaced, which
+    // fns analyzed before forking still refer to, see `Interpreter::var_value`
+    var_copies: Rc<HashMap<usize, (VarImpl, VarImpl)>>,
 
-impl Interpreter {
-    pub fn activate_namespace(&mut self, loader: NamespaceLoader) -> EvaluationResult<()> {
-        loader(self)
-    }
+    module_resolver: Rc<dyn ModuleResolver>,
 
-    pub fn set_namespace(&mut self, namespace: &Namespace) {
-        self.current_namespace = namespace.name.to_string();
-    }
+    // see `InterpreterBuilder::with_primitive_redefinition`
+    allow_primitive_redefinition: bool,
 
-    pub fn register_symbol_index(&mut self, symbol_index: Rc<RefCell<SymbolIndex>>) {
-        let mut index = symbol_index.borrow_mut();
-        for namespace in self.namespaces.values() {
-            for symbol in namespace.symbols() {
-                index.insert(symbol.clone());
-            }
-        }
-        drop(index);
+    // warnings collected until taken by the host, in the order reported,
+    // and the same warnings for finding any already pending
+    diagnostics: Vec<Diagnostic>,
+    pending_diagnostics: HashSet<Diagnostic>,
 
-        self.symbol_index = Some(symbol_index);
-    }
+    // origin of the monotonic clock read by `nano-time` and `monotonic-ms`
+    pub(crate) started_at: Instant,
 
-    // Returns the name of the loaded namespace
-    pub fn load_namespace(&mut self, namespace: Namespace) -> EvaluationResult<()> {
-        let key = &namespace.name;
-        if let Some(existing) = self.namespaces.get_mut(key) {
-            existing.merge(&namespace)?;
-        } else {
-            self.namespaces.insert(key.clone(), namespace);
-        }
-        Ok(())
+    // present when auditing, see `InterpreterBuilder::with_audit_log`
+    audit_log: Option<AuditLog>,
+
+    // see `InterpreterBuilder::with_recovery_hook`
+    recovery_hook: Option<RecoveryHook>,
+
+    // which vars each definition referred to, see `Interpreter::var_changed`
+    // shared with any fork until either changes, see `Interpreter::dependencies_mut`
+    dependencies: RefCell<Rc<DependencyGraph>>,
+
+    // see `InterpreterBuilder::with_var_change_hook`
+    var_change_hook: Option<VarChangeHook>,
+
+    // see `Interpreter::register_reader_extension`
+    pub(crate) read_options: ReadOptions,
+
+    // see `InterpreterBuilder::with_compile_cache`
+    compile_cache: Option<CompileCache>,
+
+    // number of macro expansions currently in progress, see `InterpreterBuilder::with_macro_expansion_limit`
+    macro_expansion_depth: usize,
+    macro_expansion_limit: usize,
+
+    // see `InterpreterBuilder::with_type_checks`
+    pub(crate) type_checks: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct InterpreterBuilder {
+    eager_core: bool,
+    module_resolver: Option<Rc<dyn ModuleResolver>>,
+    // (namespace, name, value)
+    bindings: Vec<(String, String, Value)>,
+    allow_primitive_redefinition: bool,
+    audit_log: Option<AuditLog>,
+    recovery_hook: Option<RecoveryHook>,
+    var_change_hook: Option<VarChangeHook>,
+    compile_cache: Option<CompileCache>,
+    macro_expansion_limit: Option<usize>,
+    type_checks: bool,
+}
+
+impl InterpreterBuilder {
+    /// Evaluate all of the sigil source backing the default namespaces
+    /// when building rather than the first time each part is referenced.
+    pub fn with_eager_core(mut self) -> Self {
+        self.eager_core = true;
+        self
     }
 
-    /// Store `args` in the var referenced by `COMMAND_LINE_ARGS_SYMBOL`.
-    pub fn intern_args(&mut self, args: impl Iterator<Item = String>) {
-        let form = args.map(Value::String).collect();
-        self.intern_var(COMMAND_LINE_ARGS_SYMBOL, Value::List(form))
-            .expect("'*command-line-args* constructed correctly");
+    /// Find the source of namespaces loaded with `require` using `resolver`
+    /// rather than the default `FilesystemResolver`.
+    pub fn with_module_resolver(mut self, resolver: impl ModuleResolver + 'static) -> Self {
+        self.module_resolver = Some(Rc::new(resolver));
+        self
     }
 
-    /// Read the interned command line argument at position `n` in the collection.
-    pub fn command_line_arg(&mut self, n: usize) -> EvaluationResult<String> {
-        match self.resolve_symbol(COMMAND_LINE_ARGS_SYMBOL, None)? {
-            Value::List(args) => match args.iter().nth(n) {
-                Some(value) => match value {
-                    Value::String(arg) => Ok(arg.clone()),
-                    _ => unreachable!(),
-                },
-                None => Err(EvaluationError::Interpreter(
-                    InterpreterError::MissingCommandLineArg(n, args.len()),
-                )),
-            },
-            _ => panic!("error to not intern command line args as a list"),
-        }
+    /// Allow `def!` to replace vars holding primitives, e.g. `+`.
+    /// Special forms can never be redefined.
+    pub fn with_primitive_redefinition(mut self) -> Self {
+        self.allow_primitive_redefinition = true;
+        self
     }
 
-    pub fn current_namespace(&self) -> &str {
-        &self.current_namespace
+    /// Record each var definition, invocation of an IO primitive and namespace switch
+    /// in a log available to the host via `Interpreter::audit_log` and to sigil via `(audit-log)`.
+    pub fn with_audit_log(mut self) -> Self {
+        self.audit_log.get_or_insert_with(AuditLog::default);
+        self
     }
 
-    fn intern_var(&mut self, identifier: &str, value: Value) -> EvaluationResult<Value> {
-        let current_namespace = self.current_namespace().to_string();
+    /// Like `with_audit_log` but also calls `hook` with each event as it is recorded.
+    pub fn with_audit_hook(mut self, hook: impl Fn(&AuditEvent) + 'static) -> Self {
+        let hook: AuditHook = Rc::new(hook);
+        self.audit_log = Some(AuditLog::new(Some(hook)));
+        self
+    }
 
-        let ns = self
-            .namespaces
-            .get_mut(&current_namespace)
-            .expect("current namespace always resolves");
-        let result = ns
-            .intern(identifier, &value)
-            .map_err(|err| -> EvaluationError { err.into() })?;
-        if let Some(index) = &self.symbol_index {
-            let mut index = index.borrow_mut();
-            index.insert(identifier.to_string());
-        }
-        Ok(result)
+    /// Calls `hook` with each error about to propagate from `Interpreter::evaluate` and the
+    /// top-level form that raised it so the host can decide how to `Recovery` from it.
+    /// Requests to exit, e.g. from `sys/exit`, are never passed to `hook`.
+    pub fn with_recovery_hook(
+        mut self,
+        hook: impl Fn(&EvaluationError, &Value) -> Recovery + 'static,
+    ) -> Self {
+        self.recovery_hook = Some(RecoveryHook::new(hook));
+        self
     }
 
-    fn intern_unbound_var(&mut self, identifier: &str) -> EvaluationResult<Value> {
-        let current_namespace = self.current_namespace().to_string();
+    /// Calls `hook` each time an existing var is redefined, e.g. by hot-reload tooling
+    /// re-evaluating a changed file, with the vars whose fn bodies depend on it.
+    /// Regardless of this hook, fns and macros defined by a `fn*` that expanded a redefined
+    /// macro are analyzed again, after `hook` returns, without evaluating the rest of their
+    /// definitions. Other values are not evaluated again and keep the previous expansion.
+    pub fn with_var_change_hook(mut self, hook: impl Fn(&VarChange) + 'static) -> Self {
+        self.var_change_hook = Some(VarChangeHook::new(hook));
+        self
+    }
 
-        let ns = self
-            .namespaces
-            .get_mut(&current_namespace)
-            .expect("current namespace always resolves");
-        let result = ns.intern_unbound(identifier);
-        if let Some(index) = &self.symbol_index {
-            let mut index = index.borrow_mut();
-            index.insert(identifier.to_string());
-        }
-        Ok(result)
+    /// Keep the forms read from files loaded with `require`, `load-file` or
+    /// `Interpreter::evaluate_file` in `dir`, keyed by a hash of the file's contents, so that
+    /// unchanged files are not read again by later processes. Files are always read
+    /// when any reader extension is registered as its output may change between processes.
+    pub fn with_compile_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.compile_cache = Some(CompileCache::new(dir.into()));
+        self
     }
 
-    fn unintern_var(&mut self, identifier: &str) {
-        let current_namespace = self.current_namespace().to_string();
+    /// Fail with `EvaluationError::MacroExpansionLimit` when the expansion of a macro
+    /// produces more than `limit` nested macro expansions, e.g. from a macro expanding to itself.
+    /// Defaults to 256.
+    pub fn with_macro_expansion_limit(mut self, limit: usize) -> Self {
+        self.macro_expansion_limit = Some(limit);
+        self
+    }
 
-        let ns = self
-            .namespaces
-            .get_mut(&current_namespace)
-            .expect("current namespace always resolves");
-        ns.remove(identifier);
+    /// Check the type hints given as metadata on the parameters of fns, e.g. `(fn* [^long n] ...)`,
+    /// each time the fn is invoked and those on the names of `def!`s when defined, failing with
+    /// `EvaluationError::TypeHintMismatch` for a value of another type. A hint on the name of
+    /// a fn describes its return value and is not checked, nor are hints naming unknown types.
+    pub fn with_type_checks(mut self) -> Self {
+        self.type_checks = true;
+        self
     }
 
-    // return a ref to some var in the current namespace
-    fn resolve_var_in_current_namespace(&self, identifier: &str) -> EvaluationResult<Value> {
-        let ns_desc = self.current_namespace();
-        self.resolve_var_in_namespace(identifier, ns_desc)
+    /// Define each `(namespace, name, value)` in the built interpreter, see `Interpreter::define`.
+    pub fn with_bindings<N, I>(mut self, bindings: impl IntoIterator<Item = (N, I, Value)>) -> Self
+    where
+        N: Into<String>,
+        I: Into<String>,
+    {
+        self.bindings.extend(
+            bindings
+                .into_iter()
+                .map(|(namespace, name, value)| (namespace.into(), name.into(), value)),
+        );
+        self
     }
 
-    // namespace -> var
-    fn resolve_var_in_namespace(&self, identifier: &str, ns_desc: &str) -> EvaluationResult<Value> {
-        self.namespaces
-            .get(ns_desc)
-            .ok_or_else(|| {
-                EvaluationError::Interpreter(InterpreterError::MissingNamespace(
-                    ns_desc.to_string(),
-                ))
-            })
-            .and_then(|ns| {
-                ns.get(identifier).cloned().ok_or_else(|| {
-                    EvaluationError::MissingVar(identifier.to_string(), ns_desc.to_string())
-                })
-            })
+    /// Builds the interpreter, panicking if any of the `with_bindings` cannot be defined;
+    /// see `InterpreterBuilder::try_build` to handle that error instead.
+    pub fn build(self) -> Interpreter {
+        self.try_build().expect("can define bindings")
     }
 
-    // symbol -> namespace -> var
-    pub(crate) fn resolve_symbol_to_var(
-        &self,
-        identifier: &str,
-        ns_opt: Option<&String>,
-    ) -> EvaluationResult<Value> {
-        // if namespaced, check there
-        if let Some(ns_desc) = ns_opt {
-            return self.resolve_var_in_namespace(identifier, ns_desc);
+    /// Builds the interpreter, failing if any of the `with_bindings` cannot be defined.
+    pub fn try_build(self) -> EvaluationResult<Interpreter> {
+        // build the default scope, which resolves special forms to themselves
+        // so that they fall through to the interpreter's evaluation
+        let mut default_scope = Scope::new();
+        for form in SpecialForm::ALL {
+            let name = form.name();
+            default_scope.insert(name.to_string(), Value::Symbol(name.to_string(), None));
         }
-        // else resolve in lexical scopes
-        if let Some(value) = resolve_symbol_in_scopes(self.scopes.iter().rev(), identifier) {
-            return Ok(value.clone());
+
+        let mut interpreter = Interpreter {
+            current_namespace: String::new(),
+            namespaces: HashMap::new(),
+            namespace_hooks: HashMap::new(),
+            symbol_index: None,
+            scopes: vec![default_scope],
+            apply_stack: vec![],
+            failed_form: None,
+            print_methods: vec![],
+            hierarchy: Hierarchy::default(),
+            file_atoms: vec![],
+            streams: Streams::default(),
+            deferred_sources: vec![],
+            deferred_names: Rc::default(),
+            fork_generation: Cell::new(0),
+            var_copies: Rc::default(),
+            module_resolver: self
+                .module_resolver
+                .unwrap_or_else(|| Rc::new(FilesystemResolver::default())),
+            allow_primitive_redefinition: self.allow_primitive_redefinition,
+            diagnostics: vec![],
+            pending_diagnostics: HashSet::new(),
+            started_at: Instant::now(),
+            audit_log: None,
+            recovery_hook: None,
+            dependencies: RefCell::default(),
+            var_change_hook: None,
+            read_options: ReadOptions::default(),
+            compile_cache: self.compile_cache,
+            macro_expansion_depth: 0,
+            macro_expansion_limit: self
+                .macro_expansion_limit
+                .unwrap_or(DEFAULT_MACRO_EXPANSION_LIMIT),
+            type_checks: self.type_checks,
+        };
+
+        // load the "core" namespace
+        interpreter
+            .activate_namespace(core::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(time::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(uuid::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(csv::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(hash::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(string::loader)
+            .expect("is valid namespace");
+        interpreter
+            .activate_namespace(sys::loader)
+            .expect("is valid namespace");
+        #[cfg(feature = "toml")]
+        interpreter
+            .activate_namespace(crate::lang::toml::loader)
+            .expect("is valid namespace");
+        #[cfg(feature = "yaml")]
+        interpreter
+            .activate_namespace(crate::lang::yaml::loader)
+            .expect("is valid namespace");
+
+        // add support for `*command-line-args*`
+        let mut buffer = String::new();
+        let _ = write!(&mut buffer, "(def! {} '())", COMMAND_LINE_ARGS_SYMBOL)
+            .expect("can write to string");
+        // and `*file*`, bound to the path of the file being evaluated, if any
+        write!(&mut buffer, "(def! {} nil)", FILE_SYMBOL).expect("can write to string");
+        // and the recent results and error of an interactive session
+        for symbol in RECENT_RESULT_SYMBOLS.iter().chain([&RECENT_ERROR_SYMBOL]) {
+            write!(&mut buffer, "(def! {} nil)", symbol).expect("can write to string");
         }
-        // otherwise check current namespace
-        self.resolve_var_in_current_namespace(identifier)
-    }
+        interpreter
+            .evaluate_from_source(&buffer)
+            .expect("valid source");
 
-    // symbol -> namespace -> var -> value
-    fn resolve_symbol(&self, identifier: &str, ns_opt: Option<&String>) -> EvaluationResult<Value> {
-        match self.resolve_symbol_to_var(identifier, ns_opt)? {
-            Value::Var(v) => match var_impl_into_inner(&v) {
-                Some(value) => Ok(value),
-                None => Ok(Value::Var(v)),
-            },
-            other => Ok(other),
+        if self.eager_core {
+            interpreter.load_deferred_sources().expect("valid source");
         }
+
+        for (namespace, name, value) in self.bindings {
+            interpreter.define(&namespace, &name, value)?;
+        }
+
+        // only audit what happens once the interpreter is handed to the host
+        interpreter.audit_log = self.audit_log;
+        interpreter.recovery_hook = self.recovery_hook;
+        interpreter.var_change_hook = self.var_change_hook;
+
+        Ok(interpreter)
     }
+}
 
-    fn enter_scope(&mut self) {
-        self.scopes.push(Scope::default());
+impl Default for Interpreter {
+    fn default() -> Self {
+        InterpreterBuilder::default().build()
     }
+}
 
-    fn insert_value_in_current_scope(&mut self, identifier: &str, value: Value) {
-        let scope = self.scopes.last_mut().expect("always one scope");
-        scope.insert(identifier.to_string(), value);
+pub type NamespaceLoader = fn(&mut Interpreter) -> EvaluationResult<()>;
+
+impl Interpreter {
+    /// Returns a copy of this interpreter whose later (re-)definitions are not seen by this
+    /// interpreter and vice versa. Vars and namespaces are shared until either interpreter
+    /// changes one, which copies it first, so forking is cheap however much is defined.
+    /// The values held by vars are shared rather than copied, so an atom defined before
+    /// forking is the same atom in both interpreters and `swap!` in either is seen by the
+    /// other. This includes a `file-atom`, which either interpreter keeps persisting.
+    pub fn fork(&self) -> Self {
+        let generation = next_var_generation();
+        self.fork_generation.set(generation);
+        Interpreter {
+            current_namespace: self.current_namespace.clone(),
+            namespaces: self.namespaces.clone(),
+            namespace_hooks: self.namespace_hooks.clone(),
+            symbol_index: self
+                .symbol_index
+                .as_ref()
+                .map(|index| Rc::new(RefCell::new(index.borrow().clone()))),
+            scopes: self.scopes[..1].to_vec(),
+            apply_stack: vec![],
+            failed_form: None,
+            print_methods: self.print_methods.clone(),
+            hierarchy: self.hierarchy.clone(),
+            file_atoms: self.file_atoms.clone(),
+            // open files are not shared, so streams opened before forking stay with `self`
+            streams: Streams::default(),
+            deferred_sources: self.deferred_sources.clone(),
+            deferred_names: self.deferred_names.clone(),
+            fork_generation: Cell::new(generation),
+            var_copies: self.var_copies.clone(),
+            module_resolver: self.module_resolver.clone(),
+            allow_primitive_redefinition: self.allow_primitive_redefinition,
+            diagnostics: vec![],
+            pending_diagnostics: HashSet::new(),
+            started_at: self.started_at,
+            audit_log: self.audit_log.as_ref().map(AuditLog::fork),
+            recovery_hook: self.recovery_hook.clone(),
+            dependencies: self.dependencies.clone(),
+            var_change_hook: self.var_change_hook.clone(),
+            read_options: self.read_options.clone(),
+            compile_cache: self.compile_cache.clone(),
+            macro_expansion_depth: 0,
+            macro_expansion_limit: self.macro_expansion_limit,
+            type_checks: self.type_checks,
+        }
     }
 
-    /// Exits the current lexical scope.
-    /// NOTE: exposed for some prelude functionality.
-    pub fn leave_scope(&mut self) {
-        let _ = self.scopes.pop().expect("no underflow in scope stack");
+    /// Returns an interpreter for evaluating independently of this one, e.g. for each of
+    /// many test cases, see `fork`. Build this interpreter `with_eager_core` so that the
+    /// sigil parts of `core` are evaluated once rather than by each copy.
+    pub fn clone_isolated(&self) -> Self {
+        self.fork()
     }
 
-    fn apply_macro(
-        &mut self,
-        f: &FnImpl,
-        operands: &PersistentList<Value>,
-    ) -> EvaluationResult<Value> {
-        let result = self.apply_fn_inner(f, operands, operands.len())?;
-        if let Value::List(forms) = result {
-            return self.expand_macro_if_present(&forms);
+    // the var bound to `identifier` in `namespace`, first replaced by a copy of itself if it
+    // may be shared with another interpreter by `fork` so that changing it is not seen there
+    fn owned_var(&mut self, namespace: &str, identifier: &str) -> Option<VarImpl> {
+        let ns = self.namespaces.get_mut(namespace)?;
+        let var = match ns.get(identifier) {
+            Some(Value::Var(var)) => var.clone(),
+            _ => return None,
+        };
+        if var.generation() >= self.fork_generation.get() {
+            return Some(var);
         }
-        Ok(result)
+        let copy = var.copy();
+        ns.rebind(identifier, &copy);
+        // fns analyzed before forking refer to `var`, or to a var it was copied from
+        let copies = Rc::make_mut(&mut self.var_copies);
+        for (_, replacement) in copies.values_mut() {
+            if replacement.is_same(&var) {
+                *replacement = copy.clone();
+            }
+        }
+        copies.insert(var.address(), (var, copy.clone()));
+        Some(copy)
     }
 
-    fn expand_macro_if_present(
-        &mut self,
-        forms: &PersistentList<Value>,
-    ) -> EvaluationResult<Value> {
-        if let Some(first) = forms.first() {
-            let rest = forms.drop_first().expect("list is not empty");
-            if let Some(expansion) = self.get_macro_expansion(first, &rest) {
-                expansion
-            } else {
-                Ok(Value::List(forms.clone()))
-            }
-        } else {
-            Ok(Value::List(PersistentList::new()))
+    // the dependencies of this interpreter's definitions, for changing them
+    fn dependencies_mut(&self) -> RefMut<'_, DependencyGraph> {
+        RefMut::map(self.dependencies.borrow_mut(), Rc::make_mut)
+    }
+
+    pub fn activate_namespace(&mut self, loader: NamespaceLoader) -> EvaluationResult<()> {
+        loader(self)
+    }
+
+    pub fn set_namespace(&mut self, namespace: &Namespace) {
+        self.switch_namespace(namespace.name.to_string());
+    }
+
+    // makes `namespace` current, returning the namespace it replaces
+    fn switch_namespace(&mut self, namespace: String) -> String {
+        if namespace != self.current_namespace {
+            let from = self.current_namespace.clone();
+            let to = namespace.clone();
+            self.audit(|| AuditEvent::NamespaceSwitch { from, to });
         }
+        std::mem::replace(&mut self.current_namespace, namespace)
     }
 
-    /// Apply the given `Fn` to the supplied `args`.
-    /// Exposed for various `prelude` functions.
-    pub(crate) fn apply_fn_inner<'a>(
+    /// Read `#name form` in any source this interpreter reads as the value `extension`
+    /// returns given `form`, see `ReadOptions::with_extension`.
+    pub fn register_reader_extension(
         &mut self,
-        FnImpl {
-            body,
-            arity,
-            level,
-            variadic,
-        }: &FnImpl,
-        args: impl IntoIterator<Item = &'a Value>,
-        args_count: usize,
-    ) -> EvaluationResult<Value> {
-        let arity = *arity;
-        let level = *level;
-        let variadic = *variadic;
+        name: impl Into<String>,
+        extension: impl Fn(&Value) -> Result<Value, String> + 'static,
+    ) {
+        self.read_options.add_extension(name, extension);
+    }
 
-        let correct_arity = if variadic {
-            args_count >= arity
-        } else {
-            args_count == arity
-        };
-        if !correct_arity {
-            return Err(EvaluationError::WrongArity {
-                expected: arity,
-                realized: args_count,
-            });
+    // reads the forms in `source` from `file`, reporting any error with the line it occurred on
+    fn read_file_source(&self, file: &str, source: &str) -> EvaluationResult<Vec<Value>> {
+        let cache = self.compile_cache.as_ref().filter(|_| {
+            !self.read_options.has_extensions() && self.current_namespace_aliases().is_empty()
+        });
+        if let Some(forms) = cache.and_then(|cache| cache.load(source)) {
+            return Ok(forms);
         }
-        self.enter_scope();
-        let mut iter = args.into_iter().enumerate();
-        if arity > 0 {
-            for (index, arg) in &mut iter {
-                let parameter = lambda_parameter_key(index, level);
-                self.insert_value_in_current_scope(&parameter, arg.clone());
+        let forms = self.read(source).map_err(|err| {
+            let line = err.line(source);
+            EvaluationError::FileReaderError(file.to_string(), line, Box::new(err))
+        })?;
+        if let Some(cache) = cache {
+            cache.store(source, &forms);
+        }
+        Ok(forms)
+    }
 
-                if index == arity - 1 {
-                    break;
-                }
+    /// Read the forms in `source` with any registered reader extensions, resolving
+    /// `::alias/name` through the aliases of the current namespace.
+    pub fn read(&self, source: &str) -> Result<Vec<Value>, ReadError> {
+        read_with_options(source, &self.current_read_options())
+    }
+
+    // the read options with the aliases of the current namespace
+    pub(crate) fn current_read_options(&self) -> ReadOptions {
+        let mut options = self.read_options.clone();
+        options.set_aliases(self.current_namespace_aliases().clone());
+        options
+    }
+
+    pub fn register_symbol_index(&mut self, symbol_index: Rc<RefCell<SymbolIndex>>) {
+        symbol_index.borrow_mut().extend(self.known_symbols());
+        self.symbol_index = Some(symbol_index);
+    }
+
+    // symbols of every loaded namespace and those of sources not yet evaluated
+    fn known_symbols(&self) -> SymbolIndex {
+        let mut index = SymbolIndex::new();
+        for namespace in self.namespaces.values() {
+            for symbol in namespace.symbols() {
+                index.insert(symbol.clone());
             }
         }
-        if variadic {
-            let operand = Value::List(iter.map(|(_, arg)| arg.clone()).collect());
-            let parameter = lambda_parameter_key(arity, level);
-            self.insert_value_in_current_scope(&parameter, operand);
-        }
-        let mut result = self.eval_do_inner(body);
-        if let Ok(Value::FnWithCaptures(FnWithCapturesImpl { f, mut captures })) = result {
-            update_captures(&mut captures, &self.scopes)?;
-            result = Ok(Value::FnWithCaptures(FnWithCapturesImpl { f, captures }))
+        for names in self.deferred_names.values() {
+            index.extend(names.keys().cloned());
         }
-        self.leave_scope();
-        result
+        index
     }
 
-    fn apply_fn(
-        &mut self,
-        f: &FnImpl,
-        operand_forms: PersistentList<Value>,
-    ) -> EvaluationResult<Value> {
-        let mut args = Vec::with_capacity(operand_forms.len());
-        for form in &operand_forms {
-            let result = self.evaluate_form(form)?;
-            args.push(result);
+    /// Returns the known symbols starting with `prefix` in sorted order,
+    /// consulting the registered `SymbolIndex` if there is one.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let matches = |symbols: &SymbolIndex| -> Vec<String> {
+            let mut matches = symbols
+                .iter()
+                .filter(|symbol| symbol.starts_with(prefix))
+                .cloned()
+                .collect::<Vec<_>>();
+            matches.sort();
+            matches
+        };
+        match &self.symbol_index {
+            Some(index) => matches(&index.borrow()),
+            None => matches(&self.known_symbols()),
         }
-        self.apply_fn_inner(f, &args, args.len())
     }
 
-    fn apply_primitive(
-        &mut self,
-        native_fn: NativeFn,
-        operand_forms: PersistentList<Value>,
-    ) -> EvaluationResult<Value> {
-        let mut operands = vec![];
-        for operand_form in &operand_forms {
-            let operand = self.evaluate_form(operand_form)?;
-            operands.push(operand);
+    // Returns the name of the loaded namespace
+    pub fn load_namespace(&mut self, namespace: Namespace) -> EvaluationResult<()> {
+        let key = &namespace.name;
+        for identifier in namespace.symbols() {
+            self.owned_var(key, identifier);
         }
-        native_fn(self, &operands)
+        if let Some(existing) = self.namespaces.get_mut(key) {
+            existing.merge(&namespace)?;
+        } else {
+            self.namespaces.insert(key.clone(), namespace);
+        }
+        Ok(())
     }
 
-    pub fn extend_from_captures(
-        &mut self,
-        captures: &HashMap<String, Option<Value>>,
-    ) -> EvaluationResult<()> {
-        self.enter_scope();
-        for (capture, value) in captures {
-            if let Some(value) = value {
-                self.insert_value_in_current_scope(capture, value.clone());
-            } else {
-                self.leave_scope();
-                return Err(EvaluationError::MissingCapturedValue(capture.to_string()));
+    /// Removes `namespace` and its vars so that memory held by them can be reclaimed,
+    /// e.g. after evaluating a script loaded with `require` which may be loaded again.
+    /// Values defined elsewhere which refer to a removed var, like fns, keep that var
+    /// and its last value alive; a `DiagnosticKind::RetainedVar` is reported for each.
+    /// The default namespace and the current namespace cannot be removed.
+    pub fn unload_namespace(&mut self, namespace: &str) -> EvaluationResult<()> {
+        if namespace == DEFAULT_NAMESPACE || namespace == self.current_namespace {
+            return Err(EvaluationError::Interpreter(
+                InterpreterError::NamespaceInUse(namespace.to_string()),
+            ));
+        }
+        if !self.namespaces.contains_key(namespace) {
+            return Err(EvaluationError::Interpreter(
+                InterpreterError::MissingNamespace(namespace.to_string()),
+            ));
+        }
+        // the namespace is kept if its hook fails so that it may be retried
+        self.run_namespace_hook(namespace, |hooks| hooks.on_unload.as_ref())?;
+        self.namespace_hooks.remove(namespace);
+        let removed = self.namespaces.remove(namespace).ok_or_else(|| {
+            EvaluationError::Interpreter(InterpreterError::MissingNamespace(namespace.to_string()))
+        })?;
+        for ns in self.namespaces.values_mut() {
+            ns.remove_aliases_to(namespace);
+        }
+        self.dependencies_mut().forget_namespace(namespace);
+
+        let mut vars = removed
+            .symbols()
+            .filter_map(|name| match removed.get(name) {
+                Some(Value::Var(var)) => Some((name.clone(), var.downgrade())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+        drop(removed);
+        for (name, var) in vars {
+            if var.upgrade().is_some() {
+                let form = Value::Symbol(name.clone(), Some(namespace.to_string()));
+                let kind = DiagnosticKind::RetainedVar {
+                    namespace: namespace.to_string(),
+                    name,
+                };
+                self.report(kind, &form);
             }
         }
         Ok(())
     }
 
-    fn eval_def_inner(&mut self, id: &str, value_form: &Value) -> EvaluationResult<Value> {
-        // need to only adjust var if this `def!` is successful
-        // also optimistically allocate in the interpreter so that
-        // the def body can capture references to itself (e.g. for recursive fn)
-        //
-        // to address this:
-        // get the existing var, or intern a sentinel value if it is missing
-        let (var, var_already_exists) = match self.resolve_var_in_current_namespace(id) {
-            Ok(v @ Value::Var(..)) => (v, true),
-            Err(EvaluationError::MissingVar(..)) => (self.intern_unbound_var(id)?, false),
-            e @ Err(_) => return e,
-            _ => unreachable!(),
-        };
-        let value = self.evaluate_form(value_form).map_err(|err| {
-            // and if the evaluation is not ok,
-            if !var_already_exists {
-                // and the var did not already exist, unintern the sentinel allocation
-                self.unintern_var(id);
-            }
-            // (if the var did already exist, then simply leave alone)
-            err
-        })?;
-        // and if the evaluation is ok, unconditionally update the var
-        match &var {
-            Value::Var(var) => var.update(value),
-            _ => unreachable!(),
+    /// Interns `value` as `name` in `namespace`, creating the namespace if it does not exist.
+    /// Fails with `EvaluationError::CannotRedefine` if `name` is that of a special form.
+    pub fn define(&mut self, namespace: &str, name: &str, value: Value) -> EvaluationResult<Value> {
+        // a var named like a special form could never be referred to
+        if SpecialForm::from_name(name).is_some() {
+            return Err(EvaluationError::CannotRedefine(name.to_string()));
+        }
+        self.load_deferred_source_for(name, Some(&namespace.to_string()))?;
+        self.owned_var(namespace, name);
+        let ns = self
+            .namespaces
+            .entry(namespace.to_string())
+            .or_insert_with(|| Namespace::new(namespace));
+        let var = ns
+            .intern(name, &value)
+            .map_err(|err| -> EvaluationError { err.into() })?;
+        if let Some(index) = &self.symbol_index {
+            let mut index = index.borrow_mut();
+            index.insert(name.to_string());
         }
+        self.audit(|| AuditEvent::Define {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        });
         Ok(var)
     }
 
-    fn eval_def(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if !(operand_forms.len() == 1 || operand_forms.len() == 2) {
-            return Err(EvaluationError::WrongArity {
-                expected: 2,
-                realized: operand_forms.len(),
-            });
+    /// Events recorded so far if auditing, see `InterpreterBuilder::with_audit_log`.
+    pub fn audit_log(&self) -> &[AuditEvent] {
+        self.audit_log
+            .as_ref()
+            .map(AuditLog::events)
+            .unwrap_or_default()
+    }
+
+    /// Removes and returns the events recorded so far.
+    pub fn take_audit_log(&mut self) -> Vec<AuditEvent> {
+        self.audit_log
+            .as_mut()
+            .map(AuditLog::take)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_auditing(&self) -> bool {
+        self.audit_log.is_some()
+    }
+
+    // `event` is only built when auditing
+    pub(crate) fn audit(&mut self, event: impl FnOnce() -> AuditEvent) {
+        if let Some(audit_log) = &mut self.audit_log {
+            audit_log.record(event());
         }
-        let name_form = operand_forms.first().unwrap();
-        let rest = operand_forms.drop_first().expect("list is not empty");
-        match name_form {
-            Value::Symbol(id, None) => {
-                if rest.is_empty() {
-                    return self.intern_unbound_var(id);
-                }
-                let value_form = rest.first().unwrap();
-                self.eval_def_inner(id, value_form)
-            }
-            other => Err(EvaluationError::WrongType {
-                expected: "SymbolWithoutNamespace",
-                realized: other.clone(),
-            }),
+    }
+
+    /// Warnings collected so far, see `Diagnostic`. At most 1024 are kept until taken,
+    /// after which further warnings are dropped.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns the warnings collected so far, clearing them.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.pending_diagnostics.clear();
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    // record a warning unless an identical one is already pending or too many are
+    pub(crate) fn report(&mut self, kind: DiagnosticKind, form: &Value) {
+        if self.diagnostics.len() >= MAX_PENDING_DIAGNOSTICS {
+            return;
+        }
+        let diagnostic = Diagnostic {
+            kind,
+            form: form.clone(),
+        };
+        if self.pending_diagnostics.insert(diagnostic.clone()) {
+            self.diagnostics.push(diagnostic);
         }
     }
 
-    fn eval_var(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if operand_forms.len() != 1 {
-            return Err(EvaluationError::WrongArity {
-                expected: 1,
-                realized: operand_forms.len(),
-            });
+    /// Evaluates `source` the first time any name it defines is referenced, failing
+    /// if it cannot be read, e.g. to load the sigil parts of a namespace on demand.
+    pub fn defer_source(&mut self, source: DeferredSource) -> EvaluationResult<()> {
+        let names = source.names().map_err(|err| {
+            EvaluationError::ReaderError(Box::new(err), source.source.to_string())
+        })?;
+        let index = self.deferred_sources.len();
+        let deferred_names = Rc::make_mut(&mut self.deferred_names)
+            .entry(source.namespace)
+            .or_default();
+        for name in names {
+            deferred_names.entry(name).or_insert(index);
         }
-        let name_form = operand_forms.first().unwrap();
-        match name_form {
-            Value::Symbol(s, ns_opt) => {
-                if let Some(ns_desc) = ns_opt {
-                    self.resolve_var_in_namespace(s, ns_desc)
-                } else {
-                    self.resolve_var_in_current_namespace(s)
-                }
-            }
-            other => Err(EvaluationError::WrongType {
-                expected: "Symbol",
-                realized: other.clone(),
-            }),
+        self.deferred_sources.push(source);
+        Ok(())
+    }
+
+    /// Evaluates any remaining deferred sources.
+    pub fn load_deferred_sources(&mut self) -> EvaluationResult<()> {
+        while !self.deferred_sources.is_empty() {
+            self.load_deferred_source(0)?;
         }
+        Ok(())
     }
 
-    fn eval_let(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        let LetForm { bindings, body } = analyze_let(&operand_forms)?;
-        let forward_declarations = bindings.resolve_forward_declarations();
-        if !forward_declarations.is_empty() {
-            self.enter_scope();
-            for identifier in &forward_declarations {
-                let var = unbound_var("", identifier);
-                self.insert_value_in_current_scope(identifier, var);
-            }
+    // evaluates the deferred source providing `identifier`, if there is one
+    pub(crate) fn load_deferred_source_for(
+        &mut self,
+        identifier: &str,
+        ns_opt: Option<&String>,
+    ) -> EvaluationResult<()> {
+        if self.deferred_sources.is_empty() {
+            return Ok(());
         }
-        self.enter_scope();
-        for (identifier, value_form) in bindings {
-            match self.evaluate_form(value_form) {
-                Ok(value) => {
-                    if let Some(Value::Var(var)) =
-                        resolve_symbol_in_scopes(self.scopes.iter().rev(), identifier)
-                    {
-                        var.update(value);
-                    } else {
-                        self.insert_value_in_current_scope(identifier, value);
-                    }
-                }
-                e @ Err(_) => {
-                    self.leave_scope();
-                    if !forward_declarations.is_empty() {
-                        self.leave_scope();
-                    }
-                    return e;
+        // unqualified references may also resolve to `core`
+        let ns_desc = ns_opt
+            .map(String::as_str)
+            .unwrap_or(&self.current_namespace);
+        let deferred_index = |namespace: &str| {
+            self.deferred_names
+                .get(namespace)
+                .and_then(|names| names.get(identifier))
+                .copied()
+        };
+        let index = deferred_index(ns_desc).or_else(|| match ns_opt {
+            None => deferred_index(DEFAULT_NAMESPACE),
+            Some(_) => None,
+        });
+        match index {
+            Some(index) => self.load_deferred_source(index),
+            None => Ok(()),
+        }
+    }
+
+    fn load_deferred_source(&mut self, index: usize) -> EvaluationResult<()> {
+        let deferred = self.deferred_sources.remove(index);
+        // sources after `index` shift down by one
+        for names in Rc::make_mut(&mut self.deferred_names).values_mut() {
+            names.retain(|_, deferred_index| *deferred_index != index);
+            for deferred_index in names.values_mut() {
+                if *deferred_index > index {
+                    *deferred_index -= 1;
                 }
             }
         }
-        let result = self.eval_do_inner(&body);
-        self.leave_scope();
-        if !forward_declarations.is_empty() {
-            self.leave_scope();
-        }
+        // loading the implementation of a namespace on demand is not audited
+        let audit_log = self.audit_log.take();
+        let result = self.evaluate_in_namespace(deferred.namespace, deferred.source);
+        self.audit_log = audit_log;
         result
     }
 
-    fn eval_loop(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        let LetForm { bindings, body } = analyze_let(&operand_forms)?;
-        self.enter_scope();
-        let mut bindings_keys = vec![];
-        for (name, value_form) in bindings.into_iter() {
-            let value = self.evaluate_form(value_form)?;
-            bindings_keys.push(name);
-            self.insert_value_in_current_scope(name, value)
+    /// Loads `namespace` from the installed `ModuleResolver` unless it is already loaded.
+    pub fn require(&mut self, namespace: &str) -> EvaluationResult<()> {
+        if self.namespaces.contains_key(namespace) {
+            return Ok(());
         }
-        let mut result = self.eval_do_inner(&body);
-        while let Ok(Value::Recur(next_bindings)) = result {
-            if next_bindings.len() != bindings_keys.len() {
-                self.leave_scope();
-                return Err(EvaluationError::WrongArity {
-                    expected: bindings_keys.len(),
-                    realized: next_bindings.len(),
-                });
-            }
-            for (key, value) in bindings_keys.iter().zip(next_bindings.iter()) {
-                self.insert_value_in_current_scope(key, value.clone());
-            }
-            result = self.eval_do_inner(&body);
+        let source = self
+            .module_resolver
+            .resolve(namespace)
+            .map_err(|err| EvaluationError::Interpreter(err.into()))?
+            .ok_or_else(|| {
+                let searched = self.module_resolver.searched(namespace);
+                let err = if searched.is_empty() {
+                    InterpreterError::MissingNamespace(namespace.to_string())
+                } else {
+                    InterpreterError::UnresolvedNamespace {
+                        namespace: namespace.to_string(),
+                        searched,
+                    }
+                };
+                EvaluationError::Interpreter(err)
+            })?;
+        self.load_namespace(Namespace::new(namespace))?;
+        let file = self.module_resolver.describe(namespace);
+        let result = self.with_file(&file, |interpreter| {
+            let forms = interpreter.read_file_source(&file, &source)?;
+            interpreter.evaluate_forms_in_namespace(namespace, &forms)?;
+            interpreter.run_namespace_hook(namespace, |hooks| hooks.on_load.as_ref())
+        });
+        if result.is_err() {
+            // allow another attempt to `require` a namespace that failed to load
+            self.namespaces.remove(namespace);
+            self.namespace_hooks.remove(namespace);
         }
-        self.leave_scope();
         result
     }
 
-    fn eval_recur(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        let mut result = PersistentVector::new();
-        for form in operand_forms.into_iter() {
-            let value = self.evaluate_form(form)?;
-            result.push_back_mut(value);
-        }
-        Ok(Value::Recur(result))
+    // see `ns-hooks`
+    pub(crate) fn set_namespace_hooks(&mut self, hooks: NamespaceHooks) {
+        self.namespace_hooks
+            .insert(self.current_namespace.clone(), hooks);
     }
 
-    fn eval_if(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if !(operand_forms.len() == 2 || operand_forms.len() == 3) {
-            return Err(EvaluationError::WrongArity {
-                expected: 2,
-                realized: operand_forms.len(),
-            });
+    // calls the hook of `namespace` chosen by `select`, if declared, with `namespace` current
+    fn run_namespace_hook(
+        &mut self,
+        namespace: &str,
+        select: fn(&NamespaceHooks) -> Option<&Value>,
+    ) -> EvaluationResult<()> {
+        let Some(hook) = self
+            .namespace_hooks
+            .get(namespace)
+            .and_then(select)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let current_namespace = self.switch_namespace(namespace.to_string());
+        let result = self.call(&hook, &[]);
+        self.switch_namespace(current_namespace);
+        result.map(|_| ())
+    }
+
+    // evaluate `source` at the top-level of `namespace`,
+    // regardless of the state of any in-progress evaluation
+    fn evaluate_in_namespace(&mut self, namespace: &str, source: &str) -> EvaluationResult<()> {
+        let forms = self
+            .read(source)
+            .map_err(|err| EvaluationError::ReaderError(Box::new(err), source.to_string()))?;
+        self.evaluate_forms_in_namespace(namespace, &forms)
+    }
+
+    fn evaluate_forms_in_namespace(
+        &mut self,
+        namespace: &str,
+        forms: &[Value],
+    ) -> EvaluationResult<()> {
+        let current_namespace = self.switch_namespace(namespace.to_string());
+        let scopes = self.scopes.split_off(1);
+        let apply_stack = std::mem::take(&mut self.apply_stack);
+        let failed_form = self.failed_form.take();
+
+        let result = forms
+            .iter()
+            .try_for_each(|form| self.evaluate_form(form).map(|_| ()));
+
+        self.switch_namespace(current_namespace);
+        self.scopes.extend(scopes);
+        self.apply_stack = apply_stack;
+        self.failed_form = failed_form;
+        result
+    }
+
+    /// Store `args` in the var referenced by `COMMAND_LINE_ARGS_SYMBOL`.
+    pub fn intern_args(&mut self, args: impl Iterator<Item = String>) {
+        let form = args.map(|arg| Value::String(arg.into())).collect();
+        self.intern_var(COMMAND_LINE_ARGS_SYMBOL, Value::List(form))
+            .expect("'*command-line-args* constructed correctly");
+    }
+
+    /// Keep `*1`, `*2` and `*3` holding the three most recent results and `*e` the most
+    /// recent error, as an exception, after evaluating the top-level form yielding `result`.
+    /// The REPL calls this after each form it evaluates.
+    pub fn record_result(&mut self, result: &EvaluationResult<Value>) {
+        for symbol in RECENT_RESULT_SYMBOLS.iter().chain([&RECENT_ERROR_SYMBOL]) {
+            self.owned_var(DEFAULT_NAMESPACE, symbol);
         }
-        let predicate_form = operand_forms.first().unwrap();
-        let predicate = self.evaluate_form(predicate_form)?;
-        let falsey = matches!(predicate, Value::Nil | Value::Bool(false));
-        let rest = operand_forms.drop_first().expect("list is not empty");
-        let consequent_form = rest.first().unwrap();
-        match rest.len() {
-            2 => {
-                if !falsey {
-                    self.evaluate_form(consequent_form)
-                } else {
-                    let rest = rest.drop_first().expect("list is not empty");
-                    let alternate_form = rest.first().unwrap();
-                    self.evaluate_form(alternate_form)
+        let ns = self
+            .namespaces
+            .get_mut(DEFAULT_NAMESPACE)
+            .expect("default namespace always resolves");
+        match result {
+            Ok(value) => {
+                let mut next = value.clone();
+                for symbol in RECENT_RESULT_SYMBOLS {
+                    let previous = match ns.get(symbol) {
+                        Some(Value::Var(var)) => var_impl_into_inner(var).unwrap_or(Value::Nil),
+                        _ => Value::Nil,
+                    };
+                    ns.intern(symbol, &next).expect("recent results are vars");
+                    next = previous;
                 }
             }
-            1 => {
-                if !falsey {
-                    self.evaluate_form(consequent_form)
-                } else {
-                    Ok(Value::Nil)
-                }
+            Err(err) if err.exit_status().is_some() => {}
+            Err(err) => {
+                let exception = exception_from_system_err(err.clone());
+                ns.intern(RECENT_ERROR_SYMBOL, &exception)
+                    .expect("recent error is a var");
             }
-            _ => unreachable!("validated len of `if` form"),
         }
     }
 
-    fn eval_do_inner(&mut self, forms: &PersistentList<Value>) -> EvaluationResult<Value> {
-        forms
-            .iter()
-            .try_fold(Value::Nil, |_, next| self.evaluate_form(next))
+    /// Read the interned command line argument at position `n` in the collection.
+    pub fn command_line_arg(&mut self, n: usize) -> EvaluationResult<String> {
+        match self.resolve_symbol(COMMAND_LINE_ARGS_SYMBOL, None)? {
+            Value::List(args) => match args.iter().nth(n) {
+                Some(value) => match value {
+                    Value::String(arg) => Ok(arg.to_string()),
+                    _ => unreachable!(),
+                },
+                None => Err(EvaluationError::Interpreter(
+                    InterpreterError::MissingCommandLineArg(n, args.len()),
+                )),
+            },
+            _ => panic!("error to not intern command line args as a list"),
+        }
     }
 
-    fn eval_do(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        self.eval_do_inner(&operand_forms)
+    pub fn current_namespace(&self) -> &str {
+        &self.current_namespace
     }
 
-    fn eval_fn(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if operand_forms.is_empty() {
-            return Err(EvaluationError::WrongArity {
-                expected: 1,
-                realized: 0,
-            });
-        }
-        let params_form = operand_forms.first().unwrap();
-        let body = operand_forms.drop_first().expect("list is not empty");
-        match params_form {
-            Value::Vector(params) => analyze_fn(self, body, params),
-            other => Err(SyntaxError::LexicalBindingsMustBeVector(other.clone()).into()),
-        }
+    // every loaded namespace by name
+    pub(crate) fn namespaces(&self) -> &HashMap<String, Namespace> {
+        &self.namespaces
     }
 
-    fn eval_quote(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if operand_forms.len() != 1 {
-            return Err(EvaluationError::WrongArity {
-                expected: 1,
-                realized: operand_forms.len(),
-            });
-        }
-        Ok(operand_forms.first().cloned().unwrap())
+    // aliases of the current namespace, from alias to the name of the namespace it stands for
+    pub(crate) fn current_namespace_aliases(&self) -> &Rc<HashMap<String, String>> {
+        self.namespaces
+            .get(&self.current_namespace)
+            .expect("current namespace always resolves")
+            .aliases()
     }
 
-    fn eval_quasiquote(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        if operand_forms.len() != 1 {
-            return Err(EvaluationError::WrongArity {
-                expected: 1,
-                realized: operand_forms.len(),
-            });
+    pub(crate) fn add_alias(&mut self, alias: &str, namespace: &str) -> EvaluationResult<()> {
+        if !self.namespaces.contains_key(namespace) {
+            return Err(EvaluationError::Interpreter(
+                InterpreterError::MissingNamespace(namespace.to_string()),
+            ));
         }
-        let operand_form = operand_forms.first().unwrap();
-        let expansion = eval_quasiquote(operand_form)?;
-        self.evaluate_form(&expansion)
+        self.namespaces
+            .get_mut(&self.current_namespace)
+            .expect("current namespace always resolves")
+            .add_alias(alias, namespace);
+        Ok(())
     }
 
-    fn eval_defmacro(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        match self.eval_def(operand_forms)? {
-            Value::Var(var) => match var_impl_into_inner(&var) {
-                Some(Value::Fn(f)) => {
-                    var.update(Value::Macro(f));
-                    Ok(Value::Var(var))
-                }
-                Some(other) => {
-                    self.unintern_var(&var.identifier);
-                    Err(EvaluationError::WrongType {
-                        expected: "Fn",
-                        realized: other,
-                    })
+    fn intern_var(&mut self, identifier: &str, value: Value) -> EvaluationResult<Value> {
+        let current_namespace = self.current_namespace().to_string();
+        self.owned_var(&current_namespace, identifier);
+
+        let ns = self
+            .namespaces
+            .get_mut(&current_namespace)
+            .expect("current namespace always resolves");
+        let result = ns
+            .intern(identifier, &value)
+            .map_err(|err| -> EvaluationError { err.into() })?;
+        if let Some(index) = &self.symbol_index {
+            let mut index = index.borrow_mut();
+            index.insert(identifier.to_string());
+        }
+        self.audit(|| AuditEvent::Define {
+            namespace: current_namespace,
+            name: identifier.to_string(),
+        });
+        Ok(result)
+    }
+
+    fn intern_unbound_var(&mut self, identifier: &str) -> EvaluationResult<Value> {
+        let current_namespace = self.current_namespace().to_string();
+
+        let ns = self
+            .namespaces
+            .get_mut(&current_namespace)
+            .expect("current namespace always resolves");
+        let result = ns.intern_unbound(identifier);
+        if let Some(index) = &self.symbol_index {
+            let mut index = index.borrow_mut();
+            index.insert(identifier.to_string());
+        }
+        self.audit(|| AuditEvent::Define {
+            namespace: current_namespace,
+            name: identifier.to_string(),
+        });
+        Ok(result)
+    }
+
+    fn unintern_var(&mut self, identifier: &str) {
+        let current_namespace = self.current_namespace().to_string();
+
+        let ns = self
+            .namespaces
+            .get_mut(&current_namespace)
+            .expect("current namespace always resolves");
+        ns.remove(identifier);
+    }
+
+    // return a ref to some var in the current namespace
+    fn resolve_var_in_current_namespace(&self, identifier: &str) -> EvaluationResult<Value> {
+        let ns_desc = self.current_namespace();
+        self.resolve_var_in_namespace(identifier, ns_desc)
+    }
+
+    // namespace -> var
+    fn resolve_var_in_namespace(&self, identifier: &str, ns_desc: &str) -> EvaluationResult<Value> {
+        self.namespaces
+            .get(ns_desc)
+            .or_else(|| {
+                let namespace = self.current_namespace_aliases().get(ns_desc)?;
+                self.namespaces.get(namespace)
+            })
+            .ok_or_else(|| {
+                EvaluationError::Interpreter(InterpreterError::MissingNamespace(
+                    ns_desc.to_string(),
+                ))
+            })
+            .and_then(|ns| {
+                ns.get(identifier).cloned().ok_or_else(|| {
+                    EvaluationError::MissingVar(identifier.to_string(), ns_desc.to_string())
+                })
+            })
+    }
+
+    // symbol -> namespace -> var
+    pub(crate) fn resolve_symbol_to_var(
+        &self,
+        identifier: &str,
+        ns_opt: Option<&String>,
+    ) -> EvaluationResult<Value> {
+        // if namespaced, check there
+        if let Some(ns_desc) = ns_opt {
+            return self.resolve_var_in_namespace(identifier, ns_desc);
+        }
+        // else resolve in lexical scopes
+        if let Some(value) = resolve_symbol_in_scopes(self.scopes.iter().rev(), identifier) {
+            return Ok(value.clone());
+        }
+        // otherwise check current namespace, falling back to `core`
+        match self.resolve_var_in_current_namespace(identifier) {
+            Err(err @ EvaluationError::MissingVar(..))
+                if self.current_namespace != DEFAULT_NAMESPACE =>
+            {
+                self.resolve_var_in_namespace(identifier, DEFAULT_NAMESPACE)
+                    .map_err(|_| err)
+            }
+            result => result,
+        }
+    }
+
+    // note the definition being evaluated, if any, refers to `var`, which the analyzer
+    // resolved in it
+    pub(crate) fn record_dependency(&self, var: &Value) {
+        if !self.dependencies.borrow().is_defining() {
+            return;
+        }
+        if let Value::Var(var) = var {
+            let name = (var.namespace().to_string(), var.identifier.clone());
+            self.dependencies_mut().record_reference(&name);
+        }
+    }
+
+    // note the definition being evaluated, if any, expanded the macro `operator`
+    // when the analyzer met it
+    pub(crate) fn record_expansion(&self, operator: &Value) {
+        if !self.dependencies.borrow().is_defining() {
+            return;
+        }
+        let var = match operator {
+            Value::Symbol(identifier, ns_opt) => {
+                match self.resolve_symbol_to_var(identifier, ns_opt.as_ref()) {
+                    Ok(Value::Var(var)) => var,
+                    _ => return,
                 }
-                None => {
-                    self.unintern_var(&var.identifier);
-                    Err(EvaluationError::WrongType {
-                        expected: "Fn",
-                        realized: Value::Var(var),
-                    })
+            }
+            Value::Var(var) => var.clone(),
+            _ => return,
+        };
+        let name = (var.namespace().to_string(), var.identifier.clone());
+        self.dependencies_mut().record_expansion(&name);
+    }
+
+    // Vars are resolved once when a fn body is analyzed and redefinition
+    // updates the same var in place. The exception is a var shared with another
+    // interpreter by `fork`, which is copied before changing, so look up the copy
+    // of a var a fn analyzed before forking refers to.
+    pub(crate) fn var_value(&self, var: &VarImpl) -> Option<Value> {
+        if !self.var_copies.is_empty() {
+            if let Some((_, copy)) = self.var_copies.get(&var.address()) {
+                return var_impl_into_inner(copy);
+            }
+        }
+        var_impl_into_inner(var)
+    }
+
+    // symbol -> namespace -> var -> value
+    fn resolve_symbol(&self, identifier: &str, ns_opt: Option<&String>) -> EvaluationResult<Value> {
+        match self.resolve_symbol_to_var(identifier, ns_opt)? {
+            Value::Var(v) => match var_impl_into_inner(&v) {
+                Some(value) => Ok(value),
+                None if v.is_local_cell() => {
+                    Err(EvaluationError::UninitializedBinding(v.identifier.clone()))
                 }
+                None => Ok(Value::Var(v)),
             },
-            _ => unreachable!("eval def only returns Value::Var"),
+            other => Ok(other),
         }
     }
 
-    fn eval_macroexpand(
+    fn enter_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    fn insert_value_in_current_scope(&mut self, identifier: &str, value: Value) {
+        let scope = self.scopes.last_mut().expect("always one scope");
+        scope.insert(identifier.to_string(), value);
+    }
+
+    /// Exits the current lexical scope.
+    /// NOTE: exposed for some prelude functionality.
+    pub fn leave_scope(&mut self) {
+        let _ = self.scopes.pop().expect("no underflow in scope stack");
+    }
+
+    // `operator` names the macro in the error raised when the expansion limit is reached
+    fn apply_macro(
         &mut self,
-        operand_forms: PersistentList<Value>,
+        operator: &Value,
+        f: &FnImpl,
+        operands: &PersistentList<Value>,
     ) -> EvaluationResult<Value> {
-        do_to_exactly_one_arg(operand_forms, |arg| match self.evaluate_form(arg)? {
-            Value::List(elems) => self.expand_macro_if_present(&elems),
-            other => Ok(other),
-        })
+        if self.macro_expansion_depth >= self.macro_expansion_limit {
+            return Err(EvaluationError::MacroExpansionLimit {
+                name: operator.to_string(),
+                limit: self.macro_expansion_limit,
+            });
+        }
+        self.macro_expansion_depth += 1;
+        let result =
+            self.apply_fn_inner(f, operands, operands.len())
+                .and_then(|result| match result {
+                    Value::List(forms) => self.expand_macro_if_present(&forms),
+                    other => Ok(other),
+                });
+        self.macro_expansion_depth -= 1;
+        result
     }
 
-    fn eval_try(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
-        let catch_form = match operand_forms.last() {
-            Some(Value::List(last_form)) => match last_form.first() {
-                Some(Value::Symbol(s, None)) if s == "catch*" => {
-                    // FIXME: deduplicate analysis of `catch*` here...
-                    if let Some(catch_form) = last_form.drop_first() {
-                        if let Some(exception_symbol) = catch_form.first() {
-                            match exception_symbol {
-                                s @ Value::Symbol(_, None) => {
-                                    if let Some(exception_body) = catch_form.drop_first() {
-                                        let mut exception_binding = PersistentVector::new();
-                                        exception_binding.push_back_mut(s.clone());
-                                        let body =
-                                            analyze_fn(self, exception_body, &exception_binding)?;
-                                        Some(body)
-                                    } else {
-                                        None
-                                    }
-                                }
-                                other => {
-                                    return Err(SyntaxError::LexicalBindingsMustHaveSymbolNames(
-                                        other.clone(),
-                                    )
-                                    .into());
-                                }
-                            }
-                        } else {
-                            None
-                        }
-                    } else {
-                        return Err(EvaluationError::WrongArity {
-                            expected: 2,
-                            realized: 0,
-                        });
-                    }
-                }
-                _ => None,
-            },
-            // FIXME: avoid clones here...
-            Some(f @ Value::Fn(..)) => Some(f.clone()),
-            Some(f @ Value::FnWithCaptures(..)) => Some(f.clone()),
-            _ => None,
-        };
-        let forms_to_eval = if catch_form.is_none() {
-            operand_forms
+    fn expand_macro_if_present(
+        &mut self,
+        forms: &PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        if let Some(first) = forms.first() {
+            let rest = forms.drop_first().expect("list is not empty");
+            if let Some(expansion) = self.get_macro_expansion(first, &rest) {
+                expansion
+            } else {
+                Ok(Value::List(forms.clone()))
+            }
         } else {
-            let mut forms_to_eval = vec![];
-            for (index, form) in operand_forms.iter().enumerate() {
-                if index == operand_forms.len() - 1 {
-                    break;
-                }
-                forms_to_eval.push(form.clone());
+            Ok(Value::List(PersistentList::new()))
+        }
+    }
+
+    /// Apply the given `Fn` to the supplied `args`.
+    /// Exposed for various `prelude` functions.
+    pub(crate) fn apply_fn_inner<'a>(
+        &mut self,
+        f: &FnImpl,
+        args: impl IntoIterator<Item = &'a Value>,
+        args_count: usize,
+    ) -> EvaluationResult<Value> {
+        check_arity(f, args_count)?;
+        let parameters = bind_parameters(f, args);
+        self.scopes
+            .push(Scope::with_parameters(f.level, parameters));
+        let mut result = self.eval_do_inner(&f.body);
+        if let Ok(Value::Recur(next_parameters)) = &result {
+            result = self.recur_into_fn(f, next_parameters);
+        }
+        if let Ok(form @ Value::FnWithCaptures(f)) = &result {
+            result = self.eval_fn_with_captures(form, f);
+        }
+        self.leave_scope();
+        result
+    }
+
+    // `recur` rebinds the parameters, with any variadic arguments passed as one sequence;
+    // the scope of the fn is left by the caller
+    #[inline(never)]
+    fn recur_into_fn(
+        &mut self,
+        f: &FnImpl,
+        next_parameters: &PersistentVector<Value>,
+    ) -> EvaluationResult<Value> {
+        let expected = f.arity + usize::from(f.variadic);
+        let mut next_parameters = next_parameters.clone();
+        loop {
+            if next_parameters.len() != expected {
+                return Err(EvaluationError::WrongArity {
+                    expected,
+                    realized: next_parameters.len(),
+                });
             }
-            PersistentList::from_iter(forms_to_eval)
-        };
-        let apply_stack_pointer = self.apply_stack.len();
-        match self.eval_do_inner(&forms_to_eval) {
-            Ok(result) => Ok(result),
-            Err(err) => match catch_form {
-                Some(Value::Fn(FnImpl { body, level, .. })) => {
-                    self.failed_form.take();
-                    self.apply_stack.truncate(apply_stack_pointer);
-                    self.enter_scope();
-                    let parameter = lambda_parameter_key(0, level);
-                    self.insert_value_in_current_scope(&parameter, exception_from_system_err(err));
-                    let result = self.eval_do_inner(&body);
-                    self.leave_scope();
-                    result
-                }
-                Some(Value::FnWithCaptures(FnWithCapturesImpl {
-                    f: FnImpl { body, level, .. },
-                    mut captures,
-                })) => {
-                    self.failed_form.take();
-                    self.apply_stack.truncate(apply_stack_pointer);
-                    // FIXME: here we pull values from scopes just to turn around and put them back in a child scope.
-                    // Can we skip this?
-                    update_captures(&mut captures, &self.scopes)?;
-                    self.extend_from_captures(&captures)?;
-                    self.enter_scope();
-                    let parameter = lambda_parameter_key(0, level);
-                    self.insert_value_in_current_scope(&parameter, exception_from_system_err(err));
-                    let result = self.eval_do_inner(&body);
-                    self.leave_scope();
+            self.leave_scope();
+            let parameters = next_parameters.iter().cloned().collect();
+            self.scopes
+                .push(Scope::with_parameters(f.level, parameters));
+            match self.eval_do_inner(&f.body)? {
+                Value::Recur(parameters) => next_parameters = parameters,
+                result => return Ok(result),
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn apply_fn_with_captures(
+        &mut self,
+        operator_form: &Value,
+        FnWithCapturesImpl { f, captures }: &FnWithCapturesImpl,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        // the arguments are evaluated in the caller's scope, without the captures
+        // which could shadow the caller's parameters
+        match self.evaluate_fn_args(operator_form, f, operand_forms) {
+            Ok(args) => {
+                self.extend_from_captures(captures)?;
+                let result = self.apply_fn_inner(f, &args, args.len());
+                self.leave_scope();
+                result
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    #[inline(never)]
+    fn apply_fn(
+        &mut self,
+        operator_form: &Value,
+        f: &FnImpl,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        match self.evaluate_fn_args(operator_form, f, operand_forms) {
+            Ok(args) => self.apply_fn_inner(f, &args, args.len()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn evaluate_fn_args(
+        &mut self,
+        operator_form: &Value,
+        f: &FnImpl,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Vec<Value>> {
+        let mut args = Vec::with_capacity(operand_forms.len());
+        for form in &operand_forms {
+            let result = self.evaluate_form(form)?;
+            args.push(result);
+        }
+        if let Err(err) = check_arity(f, args.len()) {
+            return Err(match invocation_name(operator_form) {
+                Some(name) => EvaluationError::WrongArityForFn {
+                    name,
+                    source: Box::new(err),
+                },
+                None => err,
+            });
+        }
+        Ok(args)
+    }
+
+    // `(:k coll)` and `(:k coll not-found)` are sugar for `get`
+    #[inline(never)]
+    fn apply_keyword(
+        &mut self,
+        keyword: &Value,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        if !(operand_forms.len() == 1 || operand_forms.len() == 2) {
+            return Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: operand_forms.len(),
+            });
+        }
+        let mut operands = vec![];
+        for operand_form in &operand_forms {
+            let operand = self.evaluate_form(operand_form)?;
+            operands.push(operand);
+        }
+        let not_found = operands.get(1).cloned().unwrap_or(Value::Nil);
+        Ok(operands[0].lookup(keyword).unwrap_or(not_found))
+    }
+
+    // `forms` is the whole call, kept on the `apply_stack` while the primitive is applied
+    #[inline(never)]
+    fn apply_primitive(
+        &mut self,
+        native_fn: NativeFn,
+        forms: &PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        self.apply_stack.push(Value::List(forms.clone()));
+        let mut operands = vec![];
+        for operand_form in forms.iter().skip(1) {
+            match self.evaluate_form(operand_form) {
+                Ok(operand) => operands.push(operand),
+                Err(err) => {
+                    self.record_failed_form();
+                    return Err(err);
+                }
+            }
+        }
+        self.call_primitive(native_fn, &operands)
+    }
+
+    // apart from `apply_primitive` so the frame held while the operands are evaluated is small
+    #[inline(never)]
+    fn call_primitive(
+        &mut self,
+        native_fn: NativeFn,
+        operands: &[Value],
+    ) -> EvaluationResult<Value> {
+        let stripped;
+        let operands = match strip_operand_metadata(native_fn, operands) {
+            Some(operands) => {
+                stripped = operands;
+                &stripped
+            }
+            None => operands,
+        };
+        match native_fn(self, operands) {
+            Ok(result) => {
+                self.apply_stack.pop().unwrap();
+                Ok(result)
+            }
+            Err(err) => {
+                self.record_failed_form();
+                Err(self.name_primitive_in_error(err))
+            }
+        }
+    }
+
+    // the innermost form on the `apply_stack` is the first to fail unless one already has
+    fn record_failed_form(&mut self) {
+        if self.failed_form.is_none() {
+            self.failed_form = Some(self.apply_stack.len() - 1);
+        }
+    }
+
+    // arity errors raised by a primitive name it, as they do for fns; only errors the
+    // primitive raised itself are left unnamed by the time they reach its caller
+    #[cold]
+    fn name_primitive_in_error(&self, err: EvaluationError) -> EvaluationError {
+        let name = self.call_context().and_then(|context| context.name());
+        name_arity_error(err, name)
+    }
+
+    /// Applies `native_fn` to the already evaluated `args` on behalf of another fn, e.g.
+    /// by `map` or `apply`, naming the primitive itself in any arity error it raises.
+    pub(crate) fn apply_primitive_to_args(
+        &mut self,
+        native_fn: NativeFn,
+        args: &[Value],
+    ) -> EvaluationResult<Value> {
+        let stripped;
+        let args = match strip_operand_metadata(native_fn, args) {
+            Some(args) => {
+                stripped = args;
+                &stripped
+            }
+            None => args,
+        };
+        native_fn(self, args).map_err(|err| name_arity_error(err, self.primitive_name(native_fn)))
+    }
+
+    // the name of the var holding `native_fn`, if any
+    #[cold]
+    fn primitive_name(&self, native_fn: NativeFn) -> Option<String> {
+        self.namespaces.values().find_map(|namespace| {
+            namespace
+                .symbols()
+                .find_map(|identifier| match namespace.get(identifier) {
+                    Some(Value::Var(var)) => match self.var_value(var) {
+                        Some(Value::Primitive(f)) if f as usize == native_fn as usize => {
+                            Some(identifier.clone())
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+        })
+    }
+
+    /// Describes the innermost call of a primitive being applied, if any, so that
+    /// a `NativeFn` can report where it was called from, e.g. in an error message.
+    pub fn call_context(&self) -> Option<CallContext> {
+        let form = self.apply_stack.last()?.clone();
+        let file = self
+            .namespaces
+            .get(DEFAULT_NAMESPACE)
+            .and_then(|namespace| namespace.get(FILE_SYMBOL))
+            .and_then(|file| match file {
+                Value::Var(var) => self.var_value(var),
+                _ => None,
+            })
+            .and_then(|file| file.as_str().map(String::from));
+        Some(CallContext {
+            form,
+            namespace: self.current_namespace.clone(),
+            file,
+        })
+    }
+
+    pub fn extend_from_captures(
+        &mut self,
+        captures: &HashMap<String, Option<Value>>,
+    ) -> EvaluationResult<()> {
+        self.enter_scope();
+        for (capture, value) in captures {
+            if let Some(value) = value {
+                self.insert_value_in_current_scope(capture, value.clone());
+            } else {
+                self.leave_scope();
+                return Err(EvaluationError::MissingCapturedValue(capture.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    // yields the var and whether it already existed
+    // `tag` is the type hint to check the value against, if any
+    fn eval_def_inner(
+        &mut self,
+        id: &str,
+        value_form: &Value,
+        tag: Option<&str>,
+    ) -> EvaluationResult<(Value, bool)> {
+        // need to only adjust var if this `def!` is successful
+        // also optimistically allocate in the interpreter so that
+        // the def body can capture references to itself (e.g. for recursive fn)
+        //
+        // to address this:
+        // get the existing var, or intern a sentinel value if it is missing
+        // (a var shared with another interpreter by `fork` is first copied)
+        let current_namespace = self.current_namespace.clone();
+        let (var, var_already_exists) = match self.owned_var(&current_namespace, id) {
+            Some(var) => (Value::Var(var), true),
+            None => (self.intern_unbound_var(id)?, false),
+        };
+        let value = self
+            .evaluate_form(value_form)
+            .and_then(|value| match tag {
+                // a hint on the name of a fn describes its return value
+                Some(tag) if !matches!(value, Value::Fn(..) | Value::FnWithCaptures(..)) => {
+                    check_type_hint(id, tag, &value).map(|_| value)
+                }
+                _ => Ok(value),
+            })
+            .inspect_err(|_| {
+                // and if the evaluation is not ok,
+                if !var_already_exists {
+                    // and the var did not already exist, unintern the sentinel allocation
+                    self.unintern_var(id);
+                }
+                // (if the var did already exist, then simply leave alone)
+            })?;
+        // and if the evaluation is ok, unconditionally update the var
+        match &var {
+            Value::Var(var) => var.update(value),
+            _ => unreachable!(),
+        }
+        Ok((var, var_already_exists))
+    }
+
+    #[inline(never)]
+    fn eval_def(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let (var, redefined) = self.define_var(operand_forms, false)?;
+        if redefined {
+            self.var_changed(&var)?;
+        }
+        Ok(var)
+    }
+
+    // yields the var defined by `def!` (or `defmacro!` if `is_macro`) and whether it was
+    // redefined, leaving any notification of the change to the caller
+    fn define_var(
+        &mut self,
+        operand_forms: PersistentList<Value>,
+        is_macro: bool,
+    ) -> EvaluationResult<(Value, bool)> {
+        if !(operand_forms.len() == 1 || operand_forms.len() == 2) {
+            return Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: operand_forms.len(),
+            });
+        }
+        let (name_form, meta) = self.eval_name_metadata(operand_forms.first().unwrap())?;
+        let rest = operand_forms.drop_first().expect("list is not empty");
+        let var = match name_form {
+            Value::Symbol(id, None) => {
+                // evaluate any deferred source defining `id` first
+                // so that it does not later clobber this definition
+                self.load_deferred_source_for(id, None)?;
+                self.check_redefinition(id)?;
+                if self.current_namespace != DEFAULT_NAMESPACE
+                    && self.resolve_var_in_namespace(id, DEFAULT_NAMESPACE).is_ok()
+                {
+                    let kind = DiagnosticKind::ShadowedVar {
+                        namespace: self.current_namespace.clone(),
+                        name: id.to_string(),
+                    };
+                    let form = operand_forms.push_front(Value::Symbol("def!".to_string(), None));
+                    self.report(kind, &Value::List(form));
+                }
+                if rest.is_empty() {
+                    (self.intern_unbound_var(id)?, false)
+                } else {
+                    let value_form = rest.first().unwrap();
+                    let name = (self.current_namespace.clone(), id.to_string());
+                    self.dependencies_mut().begin_definition(name);
+                    let tag = meta
+                        .as_ref()
+                        .and_then(hint_tag)
+                        .filter(|_| self.type_checks);
+                    let result = self.eval_def_inner(id, value_form, tag);
+                    let definition = result
+                        .as_ref()
+                        .ok()
+                        .and_then(|(var, _)| Definition::of_fn(var, value_form, is_macro));
+                    self.dependencies_mut().end_definition(definition);
+                    result?
+                }
+            }
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "SymbolWithoutNamespace",
+                    realized: other.clone(),
+                })
+            }
+        };
+        if let Value::Var(var) = &var.0 {
+            var.set_meta(meta);
+        }
+        Ok(var)
+    }
+
+    // Invalidates what depends on the redefined `var`: fns and macros which expanded it
+    // as a macro hold its previous expansion so are analyzed again. Other dependents
+    // refer to the var itself and see the new value, so are only reported to the host.
+    fn var_changed(&mut self, var: &Value) -> EvaluationResult<()> {
+        let var = match var {
+            Value::Var(var) => var,
+            _ => unreachable!("def only yields vars"),
+        };
+        let name = (var.namespace().to_string(), var.identifier.clone());
+        if let Some(hook) = &self.var_change_hook {
+            let dependents = self.dependencies.borrow().dependents_of(&name);
+            hook.notify(&VarChange {
+                namespace: name.0.clone(),
+                name: name.1.clone(),
+                dependents,
+            });
+        }
+        if !matches!(var_impl_into_inner(var), Some(Value::Macro(_))) {
+            return Ok(());
+        }
+        let expanders = self.dependencies.borrow().expanders_of(&name);
+        self.dependencies_mut().begin_invalidation(name);
+        let result = expanders
+            .into_iter()
+            .try_for_each(|(dependent, definition)| self.redefine(dependent, definition));
+        self.dependencies_mut().end_invalidation();
+        result
+    }
+
+    // analyzes the `fn*` of `definition` again in its namespace, updating the fn (or macro)
+    // held by `name` without evaluating the rest of its `def!`
+    fn redefine(&mut self, name: VarName, definition: Definition) -> EvaluationResult<()> {
+        if self.dependencies.borrow().is_invalidating(&name) {
+            return Ok(());
+        }
+        let (namespace, identifier) = &name;
+        let var = match self.owned_var(namespace, identifier) {
+            Some(var)
+                if matches!(
+                    var_impl_into_inner(&var),
+                    Some(Value::Fn(_) | Value::Macro(_))
+                ) =>
+            {
+                var
+            }
+            _ => return Ok(()),
+        };
+        let current_namespace = std::mem::replace(&mut self.current_namespace, namespace.clone());
+        self.dependencies_mut().begin_definition(name);
+        let result = self.eval_fn(definition.fn_operands.clone());
+        let is_macro = definition.is_macro;
+        self.dependencies_mut().end_definition(Some(definition));
+        self.current_namespace = current_namespace;
+        match result? {
+            Value::Fn(f) if is_macro => var.update(Value::Macro(f)),
+            f => var.update(f),
+        }
+        self.var_changed(&Value::Var(var))
+    }
+
+    // a name given as `^meta name` yields the name and the evaluated `meta` map
+    fn eval_name_metadata<'a>(
+        &mut self,
+        form: &'a Value,
+    ) -> EvaluationResult<(&'a Value, Option<Value>)> {
+        let (name, meta_form) = match form {
+            Value::WithMeta(name, meta_form) => (&**name, &**meta_form),
+            _ => return Ok((form, None)),
+        };
+        match self.evaluate_form(meta_form)? {
+            Value::Nil => Ok((name, None)),
+            meta @ Value::Map(..) => Ok((name, Some(meta))),
+            other => Err(EvaluationError::WrongType {
+                expected: "Map, Nil",
+                realized: other,
+            }),
+        }
+    }
+
+    fn check_redefinition(&self, id: &str) -> EvaluationResult<()> {
+        if SpecialForm::from_name(id).is_some() {
+            return Err(EvaluationError::CannotRedefine(id.to_string()));
+        }
+        if self.allow_primitive_redefinition {
+            return Ok(());
+        }
+        if let Ok(Value::Var(var)) = self.resolve_var_in_current_namespace(id) {
+            if let Some(Value::Primitive(_)) = var_impl_into_inner(&var) {
+                return Err(EvaluationError::CannotRedefine(id.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn eval_var(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        if operand_forms.len() != 1 {
+            return Err(EvaluationError::WrongArity {
+                expected: 1,
+                realized: operand_forms.len(),
+            });
+        }
+        let name_form = operand_forms.first().unwrap();
+        match name_form {
+            Value::Symbol(s, ns_opt) => {
+                self.load_deferred_source_for(s, ns_opt.as_ref())?;
+                if let Some(ns_desc) = ns_opt {
+                    self.resolve_var_in_namespace(s, ns_desc)
+                } else {
+                    self.resolve_var_in_current_namespace(s)
+                }
+            }
+            other => Err(EvaluationError::WrongType {
+                expected: "Symbol",
+                realized: other.clone(),
+            }),
+        }
+    }
+
+    // `eval_let` is on the recursive path of evaluation so the work done before
+    // the bindings are evaluated is kept out of its frame
+
+    #[inline(never)]
+    fn report_unused_let_bindings(
+        &mut self,
+        bindings: &LetBindings,
+        body: &PersistentList<Value>,
+        operand_forms: &PersistentList<Value>,
+    ) {
+        let unused = unused_bindings(bindings.as_slice(), body);
+        if !unused.is_empty() {
+            let form =
+                Value::List(operand_forms.push_front(Value::Symbol("let*".to_string(), None)));
+            for name in unused {
+                self.report(DiagnosticKind::UnusedBinding(name.clone()), &form);
+            }
+        }
+    }
+
+    // bindings referenced by a `fn*` before they are bound see a cell that is
+    // filled in once the binding is evaluated
+    #[inline(never)]
+    fn declare_forward_cells<'a>(
+        &mut self,
+        forward_declarations: &'a HashSet<String>,
+    ) -> HashMap<&'a str, VarImpl> {
+        let mut cells = HashMap::new();
+        if !forward_declarations.is_empty() {
+            self.enter_scope();
+            for identifier in forward_declarations {
+                let cell = local_cell(identifier);
+                self.insert_value_in_current_scope(identifier, Value::Var(cell.clone()));
+                cells.insert(identifier.as_str(), cell);
+            }
+        }
+        cells
+    }
+
+    #[inline(never)]
+    fn eval_let(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let LetForm { bindings, body } = analyze_let(&operand_forms)?;
+        // `let*` forms in fn bodies are checked once during analysis
+        if !self.scopes.iter().any(Scope::has_parameters) {
+            self.report_unused_let_bindings(&bindings, &body, &operand_forms);
+        }
+        let forward_declarations = bindings.resolve_forward_declarations();
+        let cells = self.declare_forward_cells(&forward_declarations);
+        self.enter_scope();
+        for (identifier, value_form) in bindings {
+            match self.evaluate_form(value_form) {
+                Ok(value) => match cells.get(identifier.as_str()) {
+                    Some(cell) => cell.update(value),
+                    None => self.insert_value_in_current_scope(identifier, value),
+                },
+                e @ Err(_) => {
                     self.leave_scope();
-                    result
+                    if !forward_declarations.is_empty() {
+                        self.leave_scope();
+                    }
+                    return e;
                 }
-                None => Err(err),
-                _ => unreachable!("`catch*` form yields callable or nothing via syntax analysis"),
-            },
+            }
+        }
+        let result = self.eval_do_inner(&body);
+        self.leave_scope();
+        if !forward_declarations.is_empty() {
+            self.leave_scope();
+        }
+        result
+    }
+
+    #[inline(never)]
+    fn eval_loop(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let LetForm { bindings, body } = analyze_let(&operand_forms)?;
+        // the bindings vector is never in tail position
+        check_recur_in_tail_position(&operand_forms, true, "loop*", &mut |operator| {
+            self.is_macro_invocation(operator)
+        })?;
+        self.enter_scope();
+        let mut bindings_keys = vec![];
+        for (name, value_form) in bindings.into_iter() {
+            let value = self.evaluate_form(value_form)?;
+            bindings_keys.push(name);
+            self.insert_value_in_current_scope(name, value)
+        }
+        let mut result = self.eval_do_inner(&body);
+        while let Ok(Value::Recur(next_bindings)) = result {
+            if next_bindings.len() != bindings_keys.len() {
+                self.leave_scope();
+                return Err(EvaluationError::WrongArity {
+                    expected: bindings_keys.len(),
+                    realized: next_bindings.len(),
+                });
+            }
+            for (key, value) in bindings_keys.iter().zip(next_bindings.iter()) {
+                self.insert_value_in_current_scope(key, value.clone());
+            }
+            result = self.eval_do_inner(&body);
+        }
+        self.leave_scope();
+        result
+    }
+
+    #[inline(never)]
+    fn eval_recur(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let mut result = PersistentVector::new();
+        for form in operand_forms.into_iter() {
+            let value = self.evaluate_form(form)?;
+            result.push_back_mut(value);
+        }
+        Ok(Value::Recur(result))
+    }
+
+    fn eval_if(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        if !(operand_forms.len() == 2 || operand_forms.len() == 3) {
+            return Err(EvaluationError::WrongArityRange {
+                min: 2,
+                max: 3,
+                realized: operand_forms.len(),
+            });
+        }
+        let mut forms = operand_forms.iter();
+        let predicate = match self.evaluate_form(forms.next().expect("validated len of `if` form"))
+        {
+            Ok(predicate) => predicate,
+            err => return err,
+        };
+        let branch = if predicate.is_truthy() {
+            forms.next()
+        } else {
+            forms.nth(1)
+        };
+        match branch {
+            Some(form) => self.evaluate_form(form),
+            None => Ok(Value::Nil),
+        }
+    }
+
+    fn eval_do_inner(&mut self, forms: &PersistentList<Value>) -> EvaluationResult<Value> {
+        let mut result = Ok(Value::Nil);
+        for form in forms {
+            result = self.evaluate_form(form);
+            if result.is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    fn eval_do(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        self.eval_do_inner(&operand_forms)
+    }
+
+    #[inline(never)]
+    fn eval_fn(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        if operand_forms.is_empty() {
+            return Err(EvaluationError::WrongArityAtLeast {
+                expected: 1,
+                realized: 0,
+            });
+        }
+        let params_form = operand_forms.first().unwrap();
+        let body = operand_forms.drop_first().expect("list is not empty");
+        check_recur_in_tail_position(&body, true, "fn*", &mut |operator| {
+            self.is_macro_invocation(operator)
+        })?;
+        match params_form {
+            Value::Vector(params) => analyze_fn(self, body, params),
+            other => Err(SyntaxError::LexicalBindingsMustBeVector(other.clone()).into()),
+        }
+    }
+
+    #[inline(never)]
+    fn eval_quote(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        if operand_forms.len() != 1 {
+            return Err(EvaluationError::WrongArity {
+                expected: 1,
+                realized: operand_forms.len(),
+            });
+        }
+        Ok(operand_forms.first().cloned().unwrap())
+    }
+
+    #[inline(never)]
+    fn eval_quasiquote(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        if operand_forms.len() != 1 {
+            return Err(EvaluationError::WrongArity {
+                expected: 1,
+                realized: operand_forms.len(),
+            });
+        }
+        let operand_form = operand_forms.first().unwrap();
+        let expansion = eval_quasiquote(operand_form)?;
+        self.evaluate_form(&expansion)
+    }
+
+    #[inline(never)]
+    fn eval_defmacro(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        match self.define_var(operand_forms, true)? {
+            (Value::Var(var), redefined) => match var_impl_into_inner(&var) {
+                Some(Value::Fn(f)) => {
+                    var.update(Value::Macro(f));
+                    let var = Value::Var(var);
+                    if redefined {
+                        self.var_changed(&var)?;
+                    }
+                    Ok(var)
+                }
+                Some(other) => {
+                    self.unintern_var(&var.identifier);
+                    Err(EvaluationError::WrongType {
+                        expected: "Fn",
+                        realized: other,
+                    })
+                }
+                None => {
+                    self.unintern_var(&var.identifier);
+                    Err(EvaluationError::WrongType {
+                        expected: "Fn",
+                        realized: Value::Var(var),
+                    })
+                }
+            },
+            _ => unreachable!("eval def only returns Value::Var"),
+        }
+    }
+
+    #[inline(never)]
+    fn eval_macroexpand(
+        &mut self,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        do_to_exactly_one_arg(operand_forms, |arg| match self.evaluate_form(arg)? {
+            Value::List(elems) => self.expand_macro_if_present(&elems),
+            other => Ok(other),
+        })
+    }
+
+    #[inline(never)]
+    fn eval_try(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let finally_body = match operand_forms.last() {
+            Some(Value::List(last_form)) => match last_form.first() {
+                Some(Value::Symbol(s, None)) if s == "finally*" => {
+                    Some(last_form.drop_first().unwrap_or_default())
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let finally_body = match finally_body {
+            Some(body) => body,
+            None => return self.eval_try_catch(operand_forms),
+        };
+        let forms_to_try = operand_forms
+            .iter()
+            .take(operand_forms.len() - 1)
+            .cloned()
+            .collect();
+        let result = self.eval_try_catch(forms_to_try);
+        // the `finally*` body always runs; if it throws, its exception replaces `result`
+        self.eval_do_inner(&finally_body)?;
+        result
+    }
+
+    fn eval_try_catch(&mut self, operand_forms: PersistentList<Value>) -> EvaluationResult<Value> {
+        let catch_form = match operand_forms.last() {
+            Some(Value::List(last_form)) => match last_form.first() {
+                Some(Value::Symbol(s, None)) if s == "catch*" => {
+                    // FIXME: deduplicate analysis of `catch*` here...
+                    if let Some(catch_form) = last_form.drop_first() {
+                        if let Some(exception_symbol) = catch_form.first() {
+                            match exception_symbol {
+                                s @ Value::Symbol(_, None) => {
+                                    if let Some(exception_body) = catch_form.drop_first() {
+                                        let mut exception_binding = PersistentVector::new();
+                                        exception_binding.push_back_mut(s.clone());
+                                        let body =
+                                            analyze_fn(self, exception_body, &exception_binding)?;
+                                        Some(body)
+                                    } else {
+                                        None
+                                    }
+                                }
+                                other => {
+                                    return Err(SyntaxError::LexicalBindingsMustHaveSymbolNames(
+                                        other.clone(),
+                                    )
+                                    .into());
+                                }
+                            }
+                        } else {
+                            None
+                        }
+                    } else {
+                        return Err(EvaluationError::WrongArity {
+                            expected: 2,
+                            realized: 0,
+                        });
+                    }
+                }
+                _ => None,
+            },
+            // FIXME: avoid clones here...
+            Some(f @ Value::Fn(..)) => Some(f.clone()),
+            Some(f @ Value::FnWithCaptures(..)) => Some(f.clone()),
+            _ => None,
+        };
+        let forms_to_eval = if catch_form.is_none() {
+            operand_forms
+        } else {
+            let mut forms_to_eval = vec![];
+            for (index, form) in operand_forms.iter().enumerate() {
+                if index == operand_forms.len() - 1 {
+                    break;
+                }
+                forms_to_eval.push(form.clone());
+            }
+            PersistentList::from_iter(forms_to_eval)
+        };
+        let apply_stack_pointer = self.apply_stack.len();
+        match self.eval_do_inner(&forms_to_eval) {
+            Ok(result) => Ok(result),
+            Err(err) if err.exit_status().is_some() => Err(err),
+            Err(err) => match catch_form {
+                Some(Value::Fn(FnImpl { body, level, .. })) => {
+                    self.failed_form.take();
+                    self.apply_stack.truncate(apply_stack_pointer);
+                    let exception = exception_from_system_err(err);
+                    self.scopes
+                        .push(Scope::with_parameters(level, vec![exception]));
+                    let result = self.eval_do_inner(&body);
+                    self.leave_scope();
+                    result
+                }
+                Some(Value::FnWithCaptures(FnWithCapturesImpl {
+                    f: FnImpl { body, level, .. },
+                    mut captures,
+                })) => {
+                    self.failed_form.take();
+                    self.apply_stack.truncate(apply_stack_pointer);
+                    // FIXME: here we pull values from scopes just to turn around and put them back in a child scope.
+                    // Can we skip this?
+                    update_captures(&mut captures, &self.scopes)?;
+                    self.extend_from_captures(&captures)?;
+                    let exception = exception_from_system_err(err);
+                    self.scopes
+                        .push(Scope::with_parameters(level, vec![exception]));
+                    let result = self.eval_do_inner(&body);
+                    self.leave_scope();
+                    self.leave_scope();
+                    result
+                }
+                None => Err(err),
+                _ => unreachable!("`catch*` form yields callable or nothing via syntax analysis"),
+            },
+        }
+    }
+
+    // whether `operator` names a macro, without expanding it
+    fn is_macro_invocation(&mut self, operator: &Value) -> bool {
+        match operator {
+            Value::Symbol(identifier, ns_opt) => {
+                if SpecialForm::of(operator).is_some() {
+                    return false;
+                }
+                self.load_deferred_source_for(identifier, ns_opt.as_ref())
+                    .is_ok()
+                    && matches!(
+                        self.resolve_symbol(identifier, ns_opt.as_ref()),
+                        Ok(Value::Macro(_))
+                    )
+            }
+            Value::Var(v) => matches!(self.var_value(v), Some(Value::Macro(_))),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn get_macro_expansion(
+        &mut self,
+        operator: &Value,
+        operands: &PersistentList<Value>,
+    ) -> Option<EvaluationResult<Value>> {
+        match operator {
+            Value::Symbol(identifier, ns_opt) => {
+                if let Err(err) = self.load_deferred_source_for(identifier, ns_opt.as_ref()) {
+                    return Some(Err(err));
+                }
+                if let Ok(Value::Macro(f)) = self.resolve_symbol(identifier, ns_opt.as_ref()) {
+                    Some(self.apply_macro(operator, &f, operands))
+                } else {
+                    None
+                }
+            }
+            Value::Var(v) => {
+                if let Some(Value::Macro(f)) = self.var_value(v) {
+                    Some(self.apply_macro(operator, &f, operands))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn eval_list(&mut self, forms: &PersistentList<Value>) -> EvaluationResult<Value> {
+        if forms.is_empty() {
+            return Ok(Value::List(PersistentList::new()));
+        }
+
+        let operator_form = forms.first().unwrap();
+        let operand_forms = forms.drop_first().unwrap_or_default();
+        // special forms can never name a macro so skip looking for one
+        let special_form = SpecialForm::of(operator_form);
+        if special_form.is_none() {
+            if let Some(expansion) = self.get_macro_expansion(operator_form, &operand_forms) {
+                return self.eval_expansion(expansion);
+            }
+        }
+        match special_form {
+            Some(special_form) => self.eval_special_form(special_form, forms, operand_forms),
+            None => self.apply_operator(forms, operand_forms),
+        }
+    }
+
+    #[inline(never)]
+    fn eval_expansion(&mut self, expansion: EvaluationResult<Value>) -> EvaluationResult<Value> {
+        match expansion? {
+            Value::List(forms) => self.eval_list(&forms),
+            other => self.evaluate_form(&other),
+        }
+    }
+
+    // Like the arms of `evaluate_form`, the two halves of `eval_list` are kept apart
+    // so that each level of nested evaluation only has the locals of the one it took.
+
+    #[inline(never)]
+    fn eval_special_form(
+        &mut self,
+        special_form: SpecialForm,
+        forms: &PersistentList<Value>,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        match special_form {
+            SpecialForm::Def => self.eval_def(operand_forms),
+            SpecialForm::Var => self.eval_var(operand_forms),
+            SpecialForm::Let => self.eval_let(operand_forms),
+            SpecialForm::Loop => self.eval_loop(operand_forms),
+            SpecialForm::Recur => self.eval_recur(operand_forms),
+            SpecialForm::If => self.eval_if(operand_forms),
+            SpecialForm::Do => self.eval_do(operand_forms),
+            SpecialForm::Fn => self.eval_fn(operand_forms),
+            SpecialForm::Quote => self.eval_quote(operand_forms),
+            SpecialForm::Quasiquote => self.eval_quasiquote(operand_forms),
+            SpecialForm::Defmacro => self.eval_defmacro(operand_forms),
+            SpecialForm::Macroexpand => self.eval_macroexpand(operand_forms),
+            SpecialForm::Try => self.eval_try(operand_forms),
+            _ => self.apply_operator(forms, operand_forms),
+        }
+    }
+
+    #[inline(never)]
+    fn apply_operator(
+        &mut self,
+        forms: &PersistentList<Value>,
+        operand_forms: PersistentList<Value>,
+    ) -> EvaluationResult<Value> {
+        let operator_form = forms.first().expect("list is not empty");
+        // errors on the recursive path of evaluation are matched rather than propagated
+        // with `?`, which takes noticeably more stack in unoptimized builds
+        let operator = match self.evaluate_form(operator_form) {
+            Ok(operator) => self.deref_operator(operator),
+            err => return err,
+        };
+        match &operator {
+            Value::Fn(f) => self.apply_fn(operator_form, f, operand_forms),
+            Value::FnWithCaptures(f) => {
+                self.apply_fn_with_captures(operator_form, f, operand_forms)
+            }
+            Value::Primitive(native_fn) => self.apply_primitive(*native_fn, forms),
+            Value::Keyword(..) => self.apply_keyword(&operator, operand_forms),
+            _ => Err(cannot_invoke(operator, forms)),
+        }
+    }
+
+    // invoking a var invokes the value it holds, e.g. `(#'f 1)`
+    pub(crate) fn deref_operator(&self, operator: Value) -> Value {
+        match operator {
+            Value::Var(var) => match self.var_value(&var) {
+                Some(value) => self.deref_operator(value),
+                None => Value::Var(var),
+            },
+            Value::WithMeta(operator, _) => self.deref_operator(*operator),
+            operator => operator,
+        }
+    }
+
+    /// Evaluate the `form` according to the semantics of the language.
+    pub fn evaluate(&mut self, form: &Value) -> EvaluationResult<Value> {
+        let result = self.evaluate_top_level(form);
+        match (result, &self.recovery_hook) {
+            (Err(err), Some(hook)) if err.exit_status().is_none() => {
+                match hook.clone().recover(&err, form) {
+                    Recovery::Propagate => Err(err),
+                    Recovery::Substitute(value) => Ok(value),
+                    Recovery::Retry(form) => self.evaluate_top_level(&form),
+                    Recovery::Throw(message, data) => {
+                        let cause = match err {
+                            EvaluationError::Exception(exc) => exc,
+                            err => ExceptionImpl::System(Box::new(err)),
+                        };
+                        let exc = exception_with_cause(&message, &data, &cause);
+                        Err(EvaluationError::Exception(exc))
+                    }
+                }
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn evaluate_top_level(&mut self, form: &Value) -> EvaluationResult<Value> {
+        let result = self.evaluate_form(form).and_then(reject_recur);
+        self.failed_form.take();
+        self.apply_stack.clear();
+        result
+    }
+
+    fn evaluate_form(&mut self, form: &Value) -> EvaluationResult<Value> {
+        match form {
+            Value::Nil
+            | Value::Bool(_)
+            | Value::Number(_)
+            | Value::String(_)
+            | Value::Keyword(..)
+            | Value::Fn(_)
+            | Value::Primitive(_)
+            | Value::Atom(_)
+            | Value::Bytes(_)
+            | Value::Uuid(_)
+            | Value::Instant(_)
+            | Value::WeakRef(_)
+            | Value::LruCache(_)
+            | Value::Reduced(_) => Ok(form.clone()),
+            Value::Symbol(id, ns_opt) => self.eval_symbol(id, ns_opt.as_ref()),
+            Value::Parameter(index, level) => self.eval_parameter(*index, *level),
+            Value::List(forms) => self.eval_list(forms),
+            Value::Vector(forms) => self.eval_vector(forms),
+            Value::Map(forms) => self.eval_map(forms),
+            Value::Set(forms) => self.eval_set(forms),
+            Value::Queue(forms) => self.eval_queue(forms),
+            // an unbound var evaluates to itself
+            Value::Var(v) => Ok(self.var_value(v).unwrap_or_else(|| form.clone())),
+            Value::FnWithCaptures(f) => self.eval_fn_with_captures(form, f),
+            Value::WithMeta(form, meta) => self.eval_with_meta(form, meta),
+            Value::Recur(_) | Value::Macro(_) | Value::Exception(_) => unreachable!(),
+        }
+    }
+
+    // The arms of `evaluate_form` which need their own locals are kept out of it so that
+    // its frame, which is on the stack once for every level of nested evaluation, stays small.
+
+    #[inline(never)]
+    fn eval_symbol(&mut self, id: &str, ns_opt: Option<&String>) -> EvaluationResult<Value> {
+        self.load_deferred_source_for(id, ns_opt)?;
+        self.resolve_symbol(id, ns_opt)
+    }
+
+    #[inline(never)]
+    fn eval_parameter(&mut self, index: usize, level: usize) -> EvaluationResult<Value> {
+        let value = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get_parameter(index, level));
+        match value {
+            Some(value) => Ok(value.clone()),
+            None => self.eval_symbol(&lambda_parameter_key(index, level), None),
+        }
+    }
+
+    // metadata on a collection literal, e.g. `^{:k v} [..]`, is evaluated and attached
+    // to the collection while that on a symbol or call is only read by special forms
+    #[inline(never)]
+    fn eval_with_meta(&mut self, form: &Value, meta: &Value) -> EvaluationResult<Value> {
+        let value = self.evaluate_form(form)?;
+        match form {
+            Value::Vector(..) | Value::Map(..) | Value::Set(..) | Value::Queue(..) => {
+                let meta = self.evaluate_form(meta)?;
+                value.with_meta(meta)
+            }
+            _ => Ok(value),
+        }
+    }
+
+    #[inline(never)]
+    fn eval_vector(&mut self, forms: &PersistentVector<Value>) -> EvaluationResult<Value> {
+        let mut result = PersistentVector::new();
+        for form in forms {
+            let value = self.evaluate_form(form)?;
+            result.push_back_mut(value);
+        }
+        Ok(Value::Vector(result))
+    }
+
+    #[inline(never)]
+    fn eval_map(&mut self, forms: &PersistentMap<Value, Value>) -> EvaluationResult<Value> {
+        let mut result = PersistentMap::new();
+        for (k, v) in forms {
+            let key = self.evaluate_form(k)?;
+            let value = self.evaluate_form(v)?;
+            result.insert_mut(key, value);
+        }
+        Ok(Value::Map(result))
+    }
+
+    #[inline(never)]
+    fn eval_set(&mut self, forms: &PersistentSet<Value>) -> EvaluationResult<Value> {
+        let mut result = PersistentSet::new();
+        for form in forms {
+            let value = self.evaluate_form(form)?;
+            result.insert_mut(value);
+        }
+        Ok(Value::Set(result))
+    }
+
+    #[inline(never)]
+    fn eval_queue(&mut self, forms: &PersistentQueue<Value>) -> EvaluationResult<Value> {
+        forms
+            .iter()
+            .map(|form| self.evaluate_form(form))
+            .collect::<EvaluationResult<_>>()
+            .map(Value::Queue)
+    }
+
+    #[inline(never)]
+    fn eval_fn_with_captures(
+        &mut self,
+        form: &Value,
+        FnWithCapturesImpl { f, captures }: &FnWithCapturesImpl,
+    ) -> EvaluationResult<Value> {
+        if captures.values().all(Option::is_some) {
+            return Ok(form.clone());
+        }
+        let mut captures = captures.clone();
+        update_captures(&mut captures, &self.scopes)?;
+        Ok(Value::FnWithCaptures(FnWithCapturesImpl {
+            f: f.clone(),
+            captures,
+        }))
+    }
+
+    /// Evaluate `form` in the global scope of the interpreter.
+    /// This method is exposed for the `eval` primitive which
+    /// has these semantics.
+    pub(crate) fn evaluate_in_global_scope(&mut self, form: &Value) -> EvaluationResult<Value> {
+        let mut child_scopes: Vec<_> = self.scopes.drain(1..).collect();
+        let result = self.evaluate_form(form);
+        self.scopes.append(&mut child_scopes);
+        result
+    }
+
+    /// Evaluate each form in `forms` in turn, yielding each result as it completes.
+    /// `policy` determines if evaluation continues past a form that fails.
+    pub fn evaluate_forms_iter<'a, 'f, I>(
+        &'a mut self,
+        forms: I,
+        policy: ErrorPolicy,
+    ) -> EvaluateForms<'a, I::IntoIter>
+    where
+        I: IntoIterator<Item = &'f Value>,
+    {
+        EvaluateForms {
+            interpreter: self,
+            forms: forms.into_iter(),
+            policy,
+            halted: false,
+        }
+    }
+
+    pub fn evaluate_from_source(&mut self, source: &str) -> EvaluationResult<Vec<Value>> {
+        let forms = self
+            .read(source)
+            .map_err(|err| EvaluationError::ReaderError(Box::new(err), source.to_string()))?;
+        self.evaluate_forms_iter(&forms, ErrorPolicy::Stop)
+            .collect()
+    }
+
+    /// Apply `f`, e.g. a fn returned by some script, to `args` as if by `(apply f args)`
+    /// so hosts can call back into sigil after evaluation has finished.
+    /// `f` may be any value sigil can invoke: a fn, a closure, a primitive, a var or a keyword.
+    pub fn call(&mut self, f: &Value, args: &[Value]) -> EvaluationResult<Value> {
+        // may be called during evaluation, e.g. from a primitive defined by the host
+        let apply_stack = std::mem::take(&mut self.apply_stack);
+        let failed_form = self.failed_form.take();
+        let result = core::apply_callable(self, f, args).and_then(reject_recur);
+        self.apply_stack = apply_stack;
+        self.failed_form = failed_form;
+        result
+    }
+
+    /// Expand `form` while it is a call to a macro without evaluating the expansion,
+    /// as if by `(macroexpand 'form)` in the current namespace. Forms nested within
+    /// the expansion are not expanded.
+    pub fn macroexpand(&mut self, form: &Value) -> EvaluationResult<Value> {
+        match form {
+            Value::List(elems) => self.expand_macro_if_present(elems),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Returns the names of the macros defined in each namespace, in sorted order.
+    /// Deferred sources are evaluated first so that every macro is listed.
+    pub fn macros(&mut self) -> EvaluationResult<BTreeMap<String, Vec<String>>> {
+        self.load_deferred_sources()?;
+        let mut macros = BTreeMap::new();
+        for (name, namespace) in &self.namespaces {
+            let mut names = namespace
+                .symbols()
+                .filter(|identifier| {
+                    matches!(
+                        namespace.get(identifier),
+                        Some(Value::Var(var)) if matches!(self.var_value(var), Some(Value::Macro(_)))
+                    )
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                names.sort();
+                macros.insert(name.clone(), names);
+            }
+        }
+        Ok(macros)
+    }
+
+    /// Returns a handle to the var `name` in `namespace` for hosts to call later with
+    /// `FnHandle::call`, always invoking the current value of the var.
+    pub fn fn_handle(&self, namespace: &str, name: &str) -> EvaluationResult<FnHandle> {
+        let var = self.resolve_var_in_namespace(name, namespace)?;
+        FnHandle::new(var)
+    }
+
+    /// Evaluate each form in the file at `path`, binding `*file*` to `path` while doing so.
+    pub fn evaluate_file(&mut self, path: impl AsRef<Path>) -> EvaluationResult<Vec<Value>> {
+        let path = path.as_ref();
+        let source =
+            fs::read_to_string(path).map_err(|err| EvaluationError::Interpreter(err.into()))?;
+        let file = path.display().to_string();
+        self.with_file(&file, |interpreter| {
+            let forms = interpreter.read_file_source(&file, &source)?;
+            // may be called during evaluation, e.g. from `load-file`
+            let apply_stack = std::mem::take(&mut interpreter.apply_stack);
+            let failed_form = interpreter.failed_form.take();
+            let result = forms
+                .iter()
+                .map(|form| {
+                    interpreter
+                        .evaluate_in_global_scope(form)
+                        .and_then(reject_recur)
+                })
+                .collect();
+            interpreter.apply_stack = apply_stack;
+            interpreter.failed_form = failed_form;
+            result
+        })
+    }
+
+    // runs `f` with `*file*` bound to `file`, restoring the previous binding afterwards
+    fn with_file<T>(
+        &mut self,
+        file: &str,
+        f: impl FnOnce(&mut Self) -> EvaluationResult<T>,
+    ) -> EvaluationResult<T> {
+        let previous = self.bind_file(Value::String(file.into()));
+        let result = f(self);
+        self.bind_file(previous);
+        result
+    }
+
+    // binds `*file*` to `file`, returning the previous binding
+    fn bind_file(&mut self, file: Value) -> Value {
+        self.owned_var(DEFAULT_NAMESPACE, FILE_SYMBOL);
+        let namespace = self
+            .namespaces
+            .get_mut(DEFAULT_NAMESPACE)
+            .expect("`core` namespace always exists");
+        let previous = match namespace.get(FILE_SYMBOL) {
+            Some(Value::Var(var)) => var_impl_into_inner(var).unwrap_or(Value::Nil),
+            _ => Value::Nil,
+        };
+        namespace
+            .intern(FILE_SYMBOL, &file)
+            .expect("`*file*` is a var");
+        previous
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        DeferredSource, ErrorPolicy, EvaluationError, Interpreter, InterpreterBuilder, SpecialForm,
+        MAX_PENDING_DIAGNOSTICS,
+    };
+    use crate::audit::AuditEvent;
+    use crate::dependencies::VarChange;
+    use crate::diagnostics::{Diagnostic, DiagnosticKind};
+    use crate::module::{FilesystemResolver, InMemoryResolver};
+    use crate::namespace::DEFAULT_NAME as DEFAULT_NAMESPACE;
+    use crate::reader::read;
+    use crate::recovery::Recovery;
+    use crate::testing::run_eval_test;
+    use crate::value::{
+        atom_with_value, exception, list_with_values, map_with_values, var_with_value,
+        vector_with_values, PersistentList, PersistentMap, PersistentVector,
+        Value::{self, *},
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_evaluate_forms_iter() {
+        let forms = read("1 (+ 1 :a) 3").unwrap();
+        let mut interpreter = Interpreter::default();
+        let results = interpreter
+            .evaluate_forms_iter(&forms, ErrorPolicy::Stop)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Ok(Number(1))));
+        assert!(results[1].is_err());
+
+        let results = interpreter
+            .evaluate_forms_iter(&forms, ErrorPolicy::Continue)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+        assert!(matches!(results[2], Ok(Number(3))));
+    }
+
+    #[test]
+    fn test_deferred_sources() {
+        let mut interpreter = Interpreter::default();
+        assert!(!interpreter.deferred_sources.is_empty());
+        // names are read from the sources, including `defmacro!` forms
+        for name in ["defmacro", "defn-memo", "memoize-lru", "transduce"] {
+            assert!(interpreter.deferred_names["core"].contains_key(name));
+        }
+        let result = interpreter
+            .evaluate_from_source("(def! inc 10) (let* [not 1] (dec not)) inc (not false)")
+            .unwrap();
+        assert_eq!(result[1], Number(0));
+        assert_eq!(result[2], Number(10));
+        assert_eq!(result[3], Bool(true));
+        // the index follows the sources remaining after loading some of them
+        let remaining = interpreter.deferred_sources.len();
+        let result = interpreter.evaluate_from_source("(transduce (transducer-map dec) + 0 [2 3])");
+        assert_eq!(result.unwrap()[0], Number(3));
+        assert_eq!(interpreter.deferred_sources.len(), remaining - 1);
+        assert!(interpreter
+            .deferred_names
+            .values()
+            .flat_map(|names| names.values())
+            .all(|&index| index < interpreter.deferred_sources.len()));
+
+        let interpreter = InterpreterBuilder::default().with_eager_core().build();
+        assert!(interpreter.deferred_sources.is_empty());
+
+        // names are those of top-level definitions wherever they are written
+        let source = DeferredSource {
+            namespace: "core",
+            source: "(def! ^:private a 1) (defn b [] \"(def! x\") (do (def! c 3))\n  (defmacro! d (fn* [] 4))",
+        };
+        assert_eq!(source.names().unwrap(), vec!["a", "b", "d"]);
+        let mut interpreter = Interpreter::default();
+        assert!(interpreter
+            .defer_source(DeferredSource {
+                namespace: "core",
+                source: "(defn e [",
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut parent = Interpreter::default();
+        parent
+            .evaluate_from_source("(def! a 1) (def! b 2)")
+            .unwrap();
+        let mut child = parent.fork();
+        let result = child
+            .evaluate_from_source("(def! a 10) (def! c 3) [a b c]")
+            .unwrap();
+        assert_eq!(
+            result[2],
+            vector_with_values(vec![Number(10), Number(2), Number(3)])
+        );
+
+        let result = parent.evaluate_from_source("(def! b 20) [a b]").unwrap();
+        assert_eq!(result[1], vector_with_values(vec![Number(1), Number(20)]));
+        assert!(parent.evaluate_from_source("c").is_err());
+
+        let result = child.evaluate_from_source("b").unwrap();
+        assert_eq!(result[0], Number(2));
+
+        // fns analyzed before the fork see redefinitions made after it
+        parent
+            .evaluate_from_source("(def! g (fn* [] 1)) (def! f (fn* [] (g)))")
+            .unwrap();
+        let mut child = parent.fork();
+        let result = child
+            .evaluate_from_source("(def! g (fn* [] 2)) (f)")
+            .unwrap();
+        assert_eq!(result[1], Number(2));
+        let result = parent.evaluate_from_source("(f)").unwrap();
+        assert_eq!(result[0], Number(1));
+        // a fn analyzed after the fork by the parent sees its own redefinitions
+        let result = parent
+            .evaluate_from_source("(def! h (fn* [] (g))) (def! g (fn* [] 3)) (h)")
+            .unwrap();
+        assert_eq!(result[2], Number(3));
+        let result = child.evaluate_from_source("[(f) (g)]").unwrap();
+        assert_eq!(result[0], vector_with_values(vec![Number(2), Number(2)]));
+
+        // vars are shared until either interpreter changes them
+        let child = parent.fork();
+        let var = |interpreter: &Interpreter, name: &str| match interpreter
+            .resolve_var_in_namespace(name, DEFAULT_NAMESPACE)
+        {
+            Ok(Value::Var(var)) => var,
+            other => panic!("expected a var, got {other:?}"),
+        };
+        assert!(var(&parent, "+").is_same(&var(&child, "+")));
+        assert!(var(&parent, "g").is_same(&var(&child, "g")));
+        parent.evaluate_from_source("(def! g (fn* [] 4))").unwrap();
+        assert!(!var(&parent, "g").is_same(&var(&child, "g")));
+        assert!(var(&parent, "+").is_same(&var(&child, "+")));
+    }
+
+    #[test]
+    fn test_fork_shares_atoms() {
+        // the value held by a var is shared, so an atom defined before forking
+        // is the same atom in both interpreters
+        let mut parent = Interpreter::default();
+        parent
+            .evaluate_from_source(
+                "(def! counter (atom 0)) (def! count! (fn* [] (swap! counter inc)))",
+            )
+            .unwrap();
+        let mut child = parent.fork();
+        let result = child.evaluate_from_source("(count!) (count!)").unwrap();
+        assert_eq!(result[1], Number(2));
+        let result = parent.evaluate_from_source("(count!)").unwrap();
+        assert_eq!(result[0], Number(3));
+        // unless either redefines the var to hold another atom
+        child
+            .evaluate_from_source("(def! counter (atom 10))")
+            .unwrap();
+        let result = child.evaluate_from_source("(count!)").unwrap();
+        assert_eq!(result[0], Number(11));
+        let result = parent.evaluate_from_source("@counter").unwrap();
+        assert_eq!(result[0], Number(3));
+
+        // and a `file-atom` is persisted by whichever interpreter swaps it
+        let dir = std::env::temp_dir().join(format!("sigil-fork-atoms-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("state.edn");
+        parent
+            .evaluate_from_source(&format!(
+                "(def! state (file-atom {:?} 0))",
+                file.display().to_string()
+            ))
+            .unwrap();
+        let mut child = parent.fork();
+        child.evaluate_from_source("(swap! state inc)").unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "1");
+        let result = parent.evaluate_from_source("(swap! state inc)").unwrap();
+        assert_eq!(result[0], Number(2));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "2");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_clone_isolated() {
+        let mut base = InterpreterBuilder::default().with_eager_core().build();
+        base.evaluate_from_source("(def! a 1)").unwrap();
+        let mut first = base.clone_isolated();
+        let mut second = base.clone_isolated();
+
+        let result = first
+            .evaluate_from_source("(def! a 2) (def! b 3) [a b ((comp inc inc) 0)]")
+            .unwrap();
+        assert_eq!(
+            result[2],
+            vector_with_values(vec![Number(2), Number(3), Number(2)])
+        );
+        let result = second.evaluate_from_source("a").unwrap();
+        assert_eq!(result[0], Number(1));
+        assert!(second.evaluate_from_source("b").is_err());
+        let result = base.evaluate_from_source("a").unwrap();
+        assert_eq!(result[0], Number(1));
+
+        // deferred sources are loaded by each copy rather than by `base`
+        let mut base = Interpreter::default();
+        let remaining = base.deferred_sources.len();
+        let mut copy = base.clone_isolated();
+        copy.evaluate_from_source("((comp inc inc) 0)").unwrap();
+        assert!(copy.deferred_sources.len() < remaining);
+        assert_eq!(base.deferred_sources.len(), remaining);
+        base.evaluate_from_source("((comp inc inc) 0)").unwrap();
+    }
+
+    #[test]
+    fn test_require() {
+        let resolver: InMemoryResolver = [
+            (
+                "greeting",
+                "(defn hello [n] (str \"hi \" n)) (def! loads (atom 0))",
+            ),
+            ("counter", "(swap! greeting/loads inc) (def! value 1)"),
+            ("broken", "(def! a (+ 1 :a))"),
+        ]
+        .into_iter()
+        .collect();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(resolver)
+            .build();
+        let result = interpreter
+            .evaluate_from_source(
+                "(require 'greeting) (require 'counter) (require \"counter\") [(greeting/hello \"there\") (deref greeting/loads) counter/value]",
+            )
+            .unwrap();
+        assert_eq!(
+            result[3],
+            vector_with_values(vec![String("hi there".into()), Number(1), Number(1)])
+        );
+        assert!(interpreter.evaluate_from_source("hello").is_err());
+        assert!(interpreter
+            .evaluate_from_source("(require 'missing)")
+            .is_err());
+        assert!(interpreter
+            .evaluate_from_source("(require 'broken)")
+            .is_err());
+        assert!(interpreter
+            .evaluate_from_source("(require 'broken)")
+            .is_err());
+
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(FilesystemResolver::new("lib").with_root("vendor"))
+            .build();
+        let err = interpreter
+            .evaluate_from_source("(require \"a.b-c\")")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "interpreter error: namespace a.b-c not found, searched: lib/a/b_c.sigil, vendor/a/b_c.sigil"
+        );
+
+        let resolver: InMemoryResolver = [
+            ("located", "(def! file *file*)"),
+            ("unreadable", "(def! a 1)\n(def! b"),
+        ]
+        .into_iter()
+        .collect();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(resolver)
+            .build();
+        let result = interpreter
+            .evaluate_from_source("(require 'located) [located/file *file*]")
+            .unwrap();
+        assert_eq!(
+            result[1],
+            vector_with_values(vec![String("located".into()), Nil])
+        );
+        match interpreter.evaluate_from_source("(require 'unreadable)") {
+            Err(EvaluationError::FileReaderError(file, line, _)) => {
+                assert_eq!((file.as_str(), line), ("unreadable", 2))
+            }
+            other => panic!("expected a reader error but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_namespace_hooks() {
+        let resolver: InMemoryResolver = [
+            (
+                "pool",
+                "(def! size 2)
+                 (ns-hooks {:on-load (fn* [] (swap! events conj [:load size]))
+                            :on-unload (fn* [] (swap! events conj [:unload size]))})",
+            ),
+            ("broken", "(ns-hooks {:on-load (fn* [] (throw :broken))})"),
+            ("stuck", "(ns-hooks {:on-unload (fn* [] (throw :stuck))})"),
+        ]
+        .into_iter()
+        .collect();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(resolver)
+            .build();
+        let result = interpreter
+            .evaluate_from_source(
+                "(def! events (atom []))
+                 (require 'pool)
+                 (require 'pool)
+                 (remove-ns! 'pool)
+                 @events",
+            )
+            .unwrap();
+        let event = |name: &str| vector_with_values([Keyword(name.to_string(), None), Number(2)]);
+        assert_eq!(
+            result[4],
+            vector_with_values([event("load"), event("unload")])
+        );
+
+        assert!(interpreter
+            .evaluate_from_source("(require 'broken)")
+            .is_err());
+        assert!(interpreter
+            .evaluate_from_source("(remove-ns! 'broken)")
+            .is_err());
+        interpreter
+            .evaluate_from_source("(require 'stuck)")
+            .unwrap();
+        assert!(interpreter
+            .evaluate_from_source("(remove-ns! 'stuck)")
+            .is_err());
+        assert_eq!(
+            interpreter
+                .evaluate_from_source("(try* (ns-hooks 1) (catch* e :invalid))")
+                .unwrap(),
+            vec![Keyword("invalid".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_unload_namespace() {
+        let resolver: InMemoryResolver = [(
+            "scratch",
+            "(def! data [1 2 3]) (defn total [] (count data)) (def! unused 0)",
+        )]
+        .into_iter()
+        .collect();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(resolver)
+            .build();
+        interpreter
+            .evaluate_from_source(
+                "(require 'scratch) (alias 's 'scratch) (def! keep scratch/total) (def! w (weak-ref #'scratch/unused))",
+            )
+            .unwrap();
+        assert!(interpreter.evaluate_from_source("(remove-ns! 's)").is_err());
+        let result = interpreter
+            .evaluate_from_source("(remove-ns! 'scratch) [(keep) @w (ns-aliases)]")
+            .unwrap();
+        assert_eq!(
+            result[1],
+            vector_with_values([Number(3), Nil, map_with_values([])])
+        );
+        assert_eq!(
+            interpreter.take_diagnostics(),
+            vec![Diagnostic {
+                kind: DiagnosticKind::RetainedVar {
+                    namespace: "scratch".to_string(),
+                    name: "data".to_string(),
+                },
+                form: Symbol("data".to_string(), Some("scratch".to_string())),
+            }]
+        );
+        assert!(interpreter.evaluate_from_source("scratch/total").is_err());
+        assert!(interpreter.unload_namespace("scratch").is_err());
+
+        let result = interpreter
+            .evaluate_from_source("(require 'scratch) (scratch/total)")
+            .unwrap();
+        assert_eq!(result[1], Number(3));
+        let err = interpreter.unload_namespace(DEFAULT_NAMESPACE).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "interpreter error: namespace core is in use and cannot be removed"
+        );
+    }
+
+    #[test]
+    fn test_compile_cache() {
+        let dir = std::env::temp_dir().join(format!("sigil-compile-cache-{}", std::process::id()));
+        let cache_dir = dir.join("cache");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("cached.sigil");
+        std::fs::write(&file, "(def! cached [1 :two \"three\"]) cached").unwrap();
+
+        for _ in 0..2 {
+            let mut interpreter = InterpreterBuilder::default()
+                .with_compile_cache(&cache_dir)
+                .build();
+            let result = interpreter.evaluate_file(&file).unwrap();
+            assert_eq!(
+                result[1],
+                vector_with_values(vec![
+                    Number(1),
+                    Keyword("two".to_string(), None),
+                    String("three".into())
+                ])
+            );
+            assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+        }
+
+        // a changed file is read again
+        std::fs::write(&file, "(def! cached 2) cached").unwrap();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_compile_cache(&cache_dir)
+            .build();
+        assert_eq!(interpreter.evaluate_file(&file).unwrap()[1], Number(2));
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_atom() {
+        let dir = std::env::temp_dir().join(format!("sigil-file-atom-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("state.edn");
+        let source = format!(
+            "(def! state (file-atom {:?} {{:count 0}}))",
+            file.display().to_string()
+        );
+
+        let mut interpreter = Interpreter::default();
+        interpreter.evaluate_from_source(&source).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "{:count 0}");
+        interpreter
+            .evaluate_from_source("(swap! state update :count inc) (swap! state assoc :name \"x\")")
+            .unwrap();
+        interpreter
+            .evaluate_from_source("(compare-and-set! state nil 1)")
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "{:count 1, :name \"x\"}"
+        );
+
+        // a new interpreter restores the persisted value rather than the initial one
+        let mut interpreter = Interpreter::default();
+        let result = interpreter
+            .evaluate_from_source(&format!("{source} (reset! state (:count @state)) @state"))
+            .unwrap();
+        assert_eq!(result[2], Number(1));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "1");
+
+        std::fs::write(&file, "1 2").unwrap();
+        assert!(interpreter.evaluate_from_source(&source).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_streams() {
+        let dir = std::env::temp_dir().join(format!("sigil-streams-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = format!("{:?}", dir.join("log.txt").display().to_string());
+
+        let mut interpreter = Interpreter::default();
+        let source = format!(
+            "(with-open [w (writer {path})] (write-line w \"a\") (write-line w 2))
+             (with-open [w (writer {path} {{:append true}})] (write-line w :c))
+             (with-open [r (reader {path})] [(read-lines r 2) (read-lines r 2) (read-lines r 2)])
+             (with-open [r (reader {path})] [(read-line r) (count (line-seq r))])
+             (def! r (reader {path}))
+             (close! r)
+             (try* (read-line r) (catch* e (ex-message e)))"
+        );
+        let result = interpreter.evaluate_from_source(&source).unwrap();
+        assert_eq!(
+            result[2],
+            vector_with_values([
+                list_with_values([String("a".into()), String("2".into())]),
+                list_with_values([String(":c".into())]),
+                Nil,
+            ])
+        );
+        assert_eq!(
+            result[3],
+            vector_with_values([String("a".into()), Number(2)])
+        );
+        assert_eq!(
+            result[6],
+            String(
+                format!(
+                    "interpreter error: stream for {} is closed",
+                    dir.join("log.txt").display()
+                )
+                .into()
+            )
+        );
+        assert!(interpreter
+            .evaluate_from_source(&format!("(with-open [r (reader {path})] (write-line r 1))"))
+            .is_err());
+
+        // `line-seq` is not limited to the chunk `read-lines` reads by default
+        let lines = (0..1500)
+            .map(|i| format!("{i}\n"))
+            .collect::<std::string::String>();
+        std::fs::write(dir.join("log.txt"), lines).unwrap();
+        let result = interpreter
+            .evaluate_from_source(&format!(
+                "(with-open [r (reader {path})] [(count (line-seq r)) (line-seq r)])
+                 (with-open [r (reader {path})] (count (read-lines r)))"
+            ))
+            .unwrap();
+        assert_eq!(result[0], vector_with_values([Number(1500), Nil]));
+        assert_eq!(result[1], Number(1024));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_evaluate_file() {
+        let dir = std::env::temp_dir().join(format!("sigil-evaluate-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inner = dir.join("inner.sigil");
+        let outer = dir.join("outer.sigil");
+        let unreadable = dir.join("unreadable.sigil");
+        std::fs::write(&inner, "(def! inner-file *file*)").unwrap();
+        std::fs::write(
+            &outer,
+            format!(
+                "#!/usr/bin/env sigil\n(load-file {:?})\n[*file* inner-file]",
+                inner.display().to_string()
+            ),
+        )
+        .unwrap();
+        std::fs::write(&unreadable, "1\n2\n(3").unwrap();
+
+        let mut interpreter = Interpreter::default();
+        let result = interpreter.evaluate_file(&outer).unwrap();
+        assert_eq!(
+            result[1],
+            vector_with_values(vec![
+                String(outer.display().to_string().into()),
+                String(inner.display().to_string().into())
+            ])
+        );
+        assert_eq!(interpreter.evaluate_from_source("*file*").unwrap()[0], Nil);
+        match interpreter.evaluate_file(&unreadable) {
+            Err(EvaluationError::FileReaderError(file, line, _)) => {
+                assert_eq!((file, line), (unreadable.display().to_string(), 3))
+            }
+            other => panic!("expected a reader error but got {:?}", other),
+        }
+        assert_eq!(interpreter.evaluate_from_source("*file*").unwrap()[0], Nil);
+        assert!(interpreter
+            .evaluate_file(dir.join("missing.sigil"))
+            .is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_call() {
+        let mut interpreter = Interpreter::default();
+        let results = interpreter
+            .evaluate_from_source(
+                "(fn* [a b] (+ a b)) (let* [n 10] (fn* [x] (+ x n))) + (def! counter (atom 0)) (fn* [& xs] (swap! counter + (count xs)))",
+            )
+            .unwrap();
+        assert_eq!(
+            interpreter
+                .call(&results[0], &[Number(1), Number(2)])
+                .unwrap(),
+            Number(3)
+        );
+        assert_eq!(
+            interpreter.call(&results[1], &[Number(1)]).unwrap(),
+            Number(11)
+        );
+        assert_eq!(
+            interpreter
+                .call(&results[2], &[Number(1), Number(2), Number(3)])
+                .unwrap(),
+            Number(6)
+        );
+        // callbacks can be called repeatedly and keep any state they close over
+        for _ in 0..3 {
+            interpreter.call(&results[4], &[Nil, Nil]).unwrap();
+        }
+        assert_eq!(
+            interpreter.evaluate_from_source("@counter").unwrap(),
+            vec![Number(6)]
+        );
+        let key = Keyword("a".to_string(), None);
+        let map = map_with_values(vec![(key.clone(), Number(1))]);
+        assert_eq!(interpreter.call(&key, &[map]).unwrap(), Number(1));
+        assert!(matches!(
+            interpreter.call(&results[0], &[Number(1)]),
+            Err(EvaluationError::WrongArityForParams { .. })
+        ));
+        assert!(interpreter.call(&Number(1), &[]).is_err());
+        assert!(interpreter.apply_stack.is_empty());
+    }
+
+    #[test]
+    fn test_reader_extension() {
+        let mut interpreter = Interpreter::default();
+        interpreter.register_reader_extension("upper", |form| match form {
+            String(s) => Ok(String(s.to_uppercase().into())),
+            _ => Err("expected a string".to_string()),
+        });
+        let results = interpreter
+            .evaluate_from_source(
+                "(str #upper \"abc\" \"d\") (read-string \"#upper \\\"e\\\"\") (read-string \"#upper 1\" {:readers {'upper inc}})",
+            )
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![String("ABCd".into()), String("E".into()), Number(2)]
+        );
+        assert!(matches!(
+            interpreter.evaluate_from_source("#upper 1"),
+            Err(EvaluationError::ReaderError(..))
+        ));
+        // forks read with the same extensions
+        let mut fork = interpreter.fork();
+        assert_eq!(
+            fork.evaluate_from_source("#upper \"f\"").unwrap(),
+            vec![String("F".into())]
+        );
+    }
+
+    #[test]
+    fn test_record_result() {
+        let mut interpreter = Interpreter::default();
+        for source in ["1", "2", "(+ *1 *2)", "(/ 1 0)", "[*1 *2 *3]"] {
+            let form = read(source).unwrap().remove(0);
+            let result = interpreter.evaluate(&form);
+            interpreter.record_result(&result);
+        }
+        let results = interpreter
+            .evaluate_from_source("*1 (ex-message *e)")
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                vector_with_values(vec![Number(3), Number(2), Number(1)]),
+                String(EvaluationError::DivideByZero(1).to_string().into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arity_errors_name_params() {
+        let mut interpreter = Interpreter::default();
+        let cases = vec![
+            (
+                "(defn f [^long x y] (+ x y)) (f 1)",
+                "could not apply `f`: form invoked with incorrect arity: expected [x y], got 1 arg",
+            ),
+            (
+                "((fn* [a & more] a))",
+                "form invoked with incorrect arity: expected [a & more], got 0 args",
+            ),
+            (
+                "((fn* [] 1) 1 2)",
+                "form invoked with incorrect arity: expected [], got 2 args",
+            ),
+            (
+                "(subs \"abc\")",
+                "could not apply `subs`: form invoked with incorrect arity: provided 1 arguments but expected between 2 and 3 arguments",
+            ),
+            // a primitive applied by another fn is named rather than the fn applying it
+            (
+                "(map subs [\"abc\"])",
+                "could not apply `subs`: form invoked with incorrect arity: provided 1 arguments but expected between 2 and 3 arguments",
+            ),
+            (
+                "(apply subs [\"abc\"])",
+                "could not apply `subs`: form invoked with incorrect arity: provided 1 arguments but expected between 2 and 3 arguments",
+            ),
+        ];
+        for (source, expected) in cases {
+            let err = interpreter.evaluate_from_source(source).unwrap_err();
+            assert_eq!(err.to_string(), expected);
         }
     }
 
-    pub(crate) fn get_macro_expansion(
-        &mut self,
-        operator: &Value,
-        operands: &PersistentList<Value>,
-    ) -> Option<EvaluationResult<Value>> {
-        match operator {
-            Value::Symbol(identifier, ns_opt) => {
-                if let Ok(Value::Macro(f)) = self.resolve_symbol(identifier, ns_opt.as_ref()) {
-                    Some(self.apply_macro(&f, operands))
-                } else {
-                    None
-                }
-            }
-            Value::Var(v) => {
-                if let Some(Value::Macro(f)) = var_impl_into_inner(v) {
-                    Some(self.apply_macro(&f, operands))
-                } else {
-                    None
-                }
-            }
-            _ => None,
+    #[test]
+    fn test_call_context() {
+        fn context(interpreter: &mut Interpreter, _: &[Value]) -> super::EvaluationResult<Value> {
+            let context = interpreter.call_context().unwrap();
+            Ok(vector_with_values([
+                context.form,
+                String(context.namespace.into()),
+                context.file.map_or(Nil, |file| String(file.into())),
+            ]))
         }
+        let mut interpreter = InterpreterBuilder::default()
+            .with_bindings([("core", "context", Primitive(context))])
+            .build();
+        assert!(interpreter.call_context().is_none());
+        let result = interpreter
+            .evaluate_from_source("(context 1 (+ 1 1))")
+            .unwrap();
+        assert_eq!(
+            result[0],
+            vector_with_values([
+                read("(context 1 (+ 1 1))").unwrap().remove(0),
+                String("core".into()),
+                Nil,
+            ])
+        );
+
+        let dir = std::env::temp_dir().join(format!("sigil-context-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("script.sigil");
+        std::fs::write(&path, "(nth (context) 2)").unwrap();
+        let result = interpreter.evaluate_file(&path).unwrap();
+        assert_eq!(result[0], String(path.display().to_string().into()));
+        std::fs::remove_dir_all(dir).unwrap();
+        assert!(interpreter.apply_stack.is_empty());
     }
 
-    fn eval_list(&mut self, forms: &PersistentList<Value>) -> EvaluationResult<Value> {
-        if forms.is_empty() {
-            return Ok(Value::List(PersistentList::new()));
+    #[test]
+    fn test_cannot_invoke_errors_show_form() {
+        let mut interpreter = Interpreter::default();
+        let cases = vec![
+            (
+                "(1 2 3)",
+                "cannot invoke the supplied value `1` in `(1 2 3)`; to write a list of values rather than a call, quote it with `'` or use a vector",
+            ),
+            (
+                "({:a 1} :a)",
+                "cannot invoke the supplied value `{:a 1}` in `({:a 1} :a)`; maps and sets cannot be invoked, use `(get coll k)` to look up a key",
+            ),
+            (
+                "([1 2] 0)",
+                "cannot invoke the supplied value `[1 2]` in `([1 2] 0)`; vectors cannot be invoked, use `(nth v index)` to get an element",
+            ),
+            (
+                "(\"abcdefghijklmnopqrstuvwxyz\" \"abcdefghijklmnopqrstuvwxyz\" \"abcdefghijklmnopqrstuvwxyz\")",
+                "cannot invoke the supplied value `abcdefghijklmnopqrstuvwxyz` in `(abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstuvwxyz abcdefghi...`; to write a list of values rather than a call, quote it with `'` or use a vector",
+            ),
+        ];
+        for (source, expected) in cases {
+            let err = interpreter.evaluate_from_source(source).unwrap_err();
+            assert_eq!(err.to_string(), expected);
         }
+    }
 
-        let operator_form = forms.first().unwrap();
-        let operand_forms = forms.drop_first().unwrap_or_default();
-        if let Some(expansion) = self.get_macro_expansion(operator_form, &operand_forms) {
-            match expansion? {
-                Value::List(forms) => return self.eval_list(&forms),
-                other => return self.evaluate_form(&other),
-            }
-        }
-        match operator_form {
-            Value::Symbol(s, None) if s == "def!" => self.eval_def(operand_forms),
-            Value::Symbol(s, None) if s == "var" => self.eval_var(operand_forms),
-            Value::Symbol(s, None) if s == "let*" => self.eval_let(operand_forms),
-            Value::Symbol(s, None) if s == "loop*" => self.eval_loop(operand_forms),
-            Value::Symbol(s, None) if s == "recur" => self.eval_recur(operand_forms),
-            Value::Symbol(s, None) if s == "if" => self.eval_if(operand_forms),
-            Value::Symbol(s, None) if s == "do" => self.eval_do(operand_forms),
-            Value::Symbol(s, None) if s == "fn*" => self.eval_fn(operand_forms),
-            Value::Symbol(s, None) if s == "quote" => self.eval_quote(operand_forms),
-            Value::Symbol(s, None) if s == "quasiquote" => self.eval_quasiquote(operand_forms),
-            Value::Symbol(s, None) if s == "defmacro!" => self.eval_defmacro(operand_forms),
-            Value::Symbol(s, None) if s == "macroexpand" => self.eval_macroexpand(operand_forms),
-            Value::Symbol(s, None) if s == "try*" => self.eval_try(operand_forms),
-            operator_form => match self.evaluate_form(operator_form)? {
-                Value::Fn(f) => self.apply_fn(&f, operand_forms),
-                Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
-                    self.extend_from_captures(&captures)?;
-                    let result = self.apply_fn(&f, operand_forms);
-                    self.leave_scope();
-                    result
-                }
-                Value::Primitive(native_fn) => {
-                    self.apply_stack.push(operator_form.clone());
-                    match self.apply_primitive(native_fn, operand_forms) {
-                        result @ Ok(..) => {
-                            self.apply_stack.pop().unwrap();
-                            result
-                        }
-                        err @ Err(..) => {
-                            if self.failed_form.is_none() {
-                                self.failed_form = Some(self.apply_stack.len() - 1);
-                            }
-                            err
-                        }
-                    }
-                }
-                v => Err(EvaluationError::CannotInvoke(v)),
-            },
+    #[test]
+    fn test_macroexpand_from_host() {
+        let mut interpreter = Interpreter::default();
+        let source = "
+            (def! expanded (atom 0))
+            (defmacro! unless (fn* [test & body] (swap! expanded inc) (list 'if test nil (cons 'do body))))
+        ";
+        interpreter.evaluate_from_source(source).unwrap();
+        let form = read("(unless false (prn :side-effect))").unwrap().remove(0);
+        let expansion = interpreter.macroexpand(&form).unwrap();
+        assert_eq!(
+            expansion,
+            read("(if false nil (do (prn :side-effect)))")
+                .unwrap()
+                .remove(0)
+        );
+        let result = interpreter.evaluate_from_source("@expanded").unwrap();
+        assert_eq!(result, vec![Number(1)]);
+        assert_eq!(interpreter.macroexpand(&Number(1)).unwrap(), Number(1));
+
+        let macros = interpreter.macros().unwrap();
+        let core_macros = &macros[DEFAULT_NAMESPACE];
+        assert!(core_macros.contains(&"unless".to_string()));
+        assert!(core_macros.contains(&"cond".to_string()));
+        assert!(!core_macros.contains(&"inc".to_string()));
+    }
+
+    #[test]
+    fn test_special_form_names() {
+        for form in SpecialForm::ALL {
+            assert_eq!(SpecialForm::from_name(form.name()), Some(form));
         }
+        assert_eq!(SpecialForm::from_name("def"), None);
+        assert_eq!(
+            SpecialForm::of(&Value::Symbol("if".to_string(), None)),
+            Some(SpecialForm::If)
+        );
+        assert_eq!(
+            SpecialForm::of(&Value::Symbol("if".to_string(), Some("core".to_string()))),
+            None
+        );
     }
 
-    /// Evaluate the `form` according to the semantics of the language.
-    pub fn evaluate(&mut self, form: &Value) -> EvaluationResult<Value> {
-        let result = self.evaluate_form(form);
-        self.failed_form.take();
-        self.apply_stack.clear();
-        result
+    #[test]
+    fn test_type_checks() {
+        let source = "
+            (defn add [^long a ^long b] (+ a b))
+            (defn ^string describe [^keyword k] (str k))
+            (defn total [^long n acc] (if (= n 0) acc (recur (dec n) (+ n acc))))
+            (def! ^long limit 10)
+        ";
+        let mut unchecked = Interpreter::default();
+        unchecked.evaluate_from_source(source).unwrap();
+        let result = unchecked.evaluate_from_source("(add 1 \"a\")");
+        assert!(result.is_err());
+        let result = unchecked
+            .evaluate_from_source("(def! ^long other \"a\") other")
+            .unwrap();
+        assert_eq!(result.last(), Some(&String("a".into())));
+
+        let mut interpreter = InterpreterBuilder::default().with_type_checks().build();
+        interpreter.evaluate_from_source(source).unwrap();
+        let cases = vec![
+            ("(add 1 2)", Ok(Number(3))),
+            ("(describe :a)", Ok(String(":a".into()))),
+            ("(total 3 0)", Ok(Number(6))),
+            ("limit", Ok(Number(10))),
+            (
+                "(try* (add 1 \"2\") (catch* e :caught))",
+                Ok(Keyword("caught".to_string(), None)),
+            ),
+            (
+                "(add 1 \"2\")",
+                Err("`b` is hinted as `long` but found value `2`"),
+            ),
+            (
+                "(describe \"a\")",
+                Err("`k` is hinted as `keyword` but found value `a`"),
+            ),
+            (
+                "(def! ^long other :a)",
+                Err("`other` is hinted as `long` but found value `:a`"),
+            ),
+            ("((fn* [^Object x] x) 1)", Ok(Number(1))),
+        ];
+        for (source, expected) in cases {
+            let result = interpreter.evaluate_from_source(source);
+            match expected {
+                Ok(value) => assert_eq!(result.unwrap().last(), Some(&value), "{}", source),
+                Err(message) => assert_eq!(result.unwrap_err().to_string(), message),
+            }
+        }
+        // a def failing its check does not define the var
+        assert!(interpreter.evaluate_from_source("other").is_err());
     }
 
-    fn evaluate_form(&mut self, form: &Value) -> EvaluationResult<Value> {
-        match form {
-            Value::Nil => Ok(Value::Nil),
-            Value::Bool(b) => Ok(Value::Bool(*b)),
-            Value::Number(n) => Ok(Value::Number(*n)),
-            Value::String(s) => Ok(Value::String(s.to_string())),
-            Value::Keyword(id, ns_opt) => Ok(Value::Keyword(
-                id.to_string(),
-                ns_opt.as_ref().map(String::from),
-            )),
-            Value::Symbol(id, ns_opt) => self.resolve_symbol(id, ns_opt.as_ref()),
-            Value::List(forms) => self.eval_list(forms),
-            Value::Vector(forms) => {
-                let mut result = PersistentVector::new();
-                for form in forms {
-                    let value = self.evaluate_form(form)?;
-                    result.push_back_mut(value);
+    #[test]
+    fn test_macro_expansion_limit() {
+        let mut interpreter = Interpreter::default();
+        let err = interpreter
+            .evaluate_from_source("(defmacro! forever (fn* [] (list 'forever))) (forever)")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expanding macro `forever` exceeded the limit of 256 nested expansions"
+        );
+        // the interpreter is usable afterwards and the error can be caught
+        let result = interpreter
+            .evaluate_from_source("(try* (forever) (catch* e :caught))")
+            .unwrap();
+        assert_eq!(result, vec![Keyword("caught".to_string(), None)]);
+        let result = interpreter
+            .evaluate_from_source("(cond false 1 false 2 :else 3)")
+            .unwrap();
+        assert_eq!(result, vec![Number(3)]);
+
+        let mut interpreter = InterpreterBuilder::default()
+            .with_macro_expansion_limit(2)
+            .build();
+        let source = "
+            (defmacro! nest (fn* [n] (if (= n 0) :done (list 'nest (dec n)))))
+            (nest 1)
+        ";
+        let result = interpreter.evaluate_from_source(source).unwrap();
+        assert_eq!(result.last(), Some(&Keyword("done".to_string(), None)));
+        let err = interpreter.evaluate_from_source("(nest 2)").unwrap_err();
+        assert!(matches!(
+            err,
+            EvaluationError::MacroExpansionLimit { limit: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_var_change_hook() {
+        let changes = Rc::new(RefCell::new(vec![]));
+        let mut interpreter = InterpreterBuilder::default()
+            .with_var_change_hook({
+                let changes = changes.clone();
+                move |change| changes.borrow_mut().push(change.clone())
+            })
+            .build();
+        let source = "
+            (defmacro! twice (fn* [x] (list '* 2 x)))
+            (defn f [x] (twice x))
+            (defn g [x] (inc (f x)))
+            (def! h (g 1))
+            (def! expansions (atom 0))
+            (def! counted (do (swap! expansions inc) (twice 1)))
+            (def! ^{:n (swap! expansions inc)} k (fn* [x] (twice x)))
+            (def! base 10)
+            (defn with-base [x] (+ base x))
+            [(f 2) (with-base 1)]";
+        let results = interpreter.evaluate_from_source(source).unwrap();
+        assert_eq!(
+            results.last().unwrap(),
+            &vector_with_values(vec![Number(4), Number(11)])
+        );
+        // no var existed before
+        assert!(changes.borrow().is_empty());
+
+        let source = "
+            (defmacro! twice (fn* [x] (list '+ x x x)))
+            (def! base 20)
+            [(f 2) (g 2) (k 2) (with-base 1) h @expansions (:n (meta (var k)))]";
+        let results = interpreter.evaluate_from_source(source).unwrap();
+        // `f` and `k` expanded the old `twice` so their fns are analyzed again with the new
+        // one, without evaluating the rest of their definitions, while `counted` is not a fn
+        // so is not evaluated again
+        assert_eq!(
+            results.last().unwrap(),
+            &vector_with_values(vec![
+                Number(6),
+                Number(7),
+                Number(6),
+                Number(21),
+                Number(3),
+                Number(2),
+                Number(2)
+            ])
+        );
+        let change = |name: &str, dependents: &[&str]| VarChange {
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            name: name.to_string(),
+            dependents: dependents
+                .iter()
+                .map(|name| (DEFAULT_NAMESPACE.to_string(), name.to_string()))
+                .collect(),
+        };
+        assert_eq!(
+            *changes.borrow(),
+            vec![
+                // only references the analyzer resolved count, so not those of `h` or
+                // `counted` made when evaluating their values
+                change("twice", &["f", "k", "g"]),
+                change("f", &["g"]),
+                change("k", &[]),
+                change("base", &["with-base"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recovery_hook() {
+        let mut interpreter = InterpreterBuilder::default()
+            .with_recovery_hook(|err, form| match form {
+                Value::List(elems) if elems.first() == Some(&Symbol("/".to_string(), None)) => {
+                    Recovery::Substitute(Keyword("undefined".to_string(), None))
                 }
-                Ok(Value::Vector(result))
-            }
-            Value::Map(forms) => {
-                let mut result = PersistentMap::new();
-                for (k, v) in forms {
-                    let key = self.evaluate_form(k)?;
-                    let value = self.evaluate_form(v)?;
-                    result.insert_mut(key, value);
+                Value::List(elems) if elems.first() == Some(&Symbol("inc".to_string(), None)) => {
+                    Recovery::Retry(read("(inc 0)").unwrap().remove(0))
                 }
-                Ok(Value::Map(result))
-            }
-            Value::Set(forms) => {
-                let mut result = PersistentSet::new();
-                for form in forms {
-                    let value = self.evaluate_form(form)?;
-                    result.insert_mut(value);
+                Value::List(elems) if elems.first() == Some(&Symbol("get".to_string(), None)) => {
+                    Recovery::Retry(
+                        read("(get nil :missing :default :extra)")
+                            .unwrap()
+                            .remove(0),
+                    )
                 }
-                Ok(Value::Set(result))
+                Value::Symbol(..) => {
+                    Recovery::Throw(err.to_string(), Keyword("unbound".to_string(), None))
+                }
+                _ => Recovery::Propagate,
+            })
+            .build();
+        let results = interpreter
+            .evaluate_from_source("(/ 1 0) (inc :a) (+ 1 2)")
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![Keyword("undefined".to_string(), None), Number(1), Number(3)]
+        );
+        // errors from a retry are not recovered
+        assert!(interpreter.evaluate_from_source("(get)").is_err());
+        assert!(interpreter.evaluate_from_source("(+ 1 :a)").is_err());
+        match interpreter.evaluate_from_source("does-not-exist") {
+            Err(EvaluationError::Exception(exc)) => {
+                let exc = Value::Exception(exc);
+                let data = interpreter.evaluate(&list_with_values(vec![
+                    Symbol("ex-data".to_string(), None),
+                    list_with_values(vec![Symbol("quote".to_string(), None), exc]),
+                ]));
+                assert_eq!(data.unwrap(), Keyword("unbound".to_string(), None));
             }
-            Value::Var(v) => match var_impl_into_inner(v) {
-                Some(value) => Ok(value),
-                None => Ok(Value::Var(v.clone())),
+            other => panic!("expected an exception but got {:?}", other),
+        }
+        // exiting is never recovered
+        let mut interpreter = InterpreterBuilder::default()
+            .with_recovery_hook(|_, _| Recovery::Substitute(Nil))
+            .build();
+        assert_eq!(
+            interpreter
+                .evaluate_from_source("(sys/exit 3)")
+                .unwrap_err()
+                .exit_status(),
+            Some(3)
+        );
+        assert_eq!(
+            interpreter.evaluate_from_source("(/ 1 0)").unwrap(),
+            vec![Nil]
+        );
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let resolver: InMemoryResolver =
+            [("greeting", "(def! hello \"hi\")")].into_iter().collect();
+        let hooked = Rc::new(RefCell::new(0));
+        let hook_count = hooked.clone();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_module_resolver(resolver)
+            .with_audit_hook(move |_| *hook_count.borrow_mut() += 1)
+            .build();
+        assert!(interpreter.audit_log().is_empty());
+        interpreter
+            .evaluate_from_source("(def! a 1) (defn f [] (print \"\")) (f) (require 'greeting)")
+            .unwrap();
+        let define = |namespace: &str, name: &str| AuditEvent::Define {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        };
+        let switch = |from: &str, to: &str| AuditEvent::NamespaceSwitch {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        let expected = vec![
+            define("core", "a"),
+            define("core", "f"),
+            AuditEvent::Io {
+                name: "print",
+                args: vec![String("".into())],
             },
-            f @ Value::Fn(_) => Ok(f.clone()),
-            Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
-                let mut captures = captures.clone();
-                update_captures(&mut captures, &self.scopes)?;
-                Ok(Value::FnWithCaptures(FnWithCapturesImpl {
-                    f: f.clone(),
-                    captures,
-                }))
+            switch("core", "greeting"),
+            define("greeting", "hello"),
+            switch("greeting", "core"),
+        ];
+        assert_eq!(interpreter.audit_log(), expected.as_slice());
+        assert_eq!(*hooked.borrow(), expected.len());
+
+        let result = interpreter.evaluate_from_source("(audit-log)").unwrap();
+        match &result[0] {
+            Vector(events) => {
+                assert_eq!(events.len(), expected.len());
+                assert_eq!(events[0], expected[0].to_value());
             }
-            f @ Value::Primitive(_) => Ok(f.clone()),
-            Value::Recur(_) => unreachable!(),
-            a @ Value::Atom(_) => Ok(a.clone()),
-            Value::Macro(_) => unreachable!(),
-            Value::Exception(_) => unreachable!(),
+            other => panic!("expected a vector of events but found {}", other),
         }
+
+        assert_eq!(interpreter.take_audit_log(), expected);
+        assert!(interpreter.audit_log().is_empty());
+
+        let mut interpreter = Interpreter::default();
+        interpreter.evaluate_from_source("(def! a 1)").unwrap();
+        assert!(interpreter.audit_log().is_empty());
+        assert_eq!(
+            interpreter.evaluate_from_source("(audit-log)").unwrap()[0],
+            Nil
+        );
     }
 
-    /// Evaluate `form` in the global scope of the interpreter.
-    /// This method is exposed for the `eval` primitive which
-    /// has these semantics.
-    pub(crate) fn evaluate_in_global_scope(&mut self, form: &Value) -> EvaluationResult<Value> {
-        let mut child_scopes: Vec<_> = self.scopes.drain(1..).collect();
-        let result = self.evaluate_form(form);
-        self.scopes.append(&mut child_scopes);
-        result
+    #[test]
+    fn test_define() {
+        let mut interpreter = InterpreterBuilder::default()
+            .with_bindings(vec![
+                ("config", "port", Number(8080)),
+                ("core", "debug?", Bool(true)),
+            ])
+            .build();
+        interpreter
+            .define("config", "host", String("localhost".into()))
+            .unwrap();
+        interpreter.define("core", "inc", Number(1)).unwrap();
+        assert!(matches!(
+            interpreter.define("config", "if", Nil),
+            Err(EvaluationError::CannotRedefine(name)) if name == "if"
+        ));
+        let result = interpreter
+            .evaluate_from_source("[config/port config/host debug? inc (dec 1)]")
+            .unwrap();
+        assert_eq!(
+            result[0],
+            vector_with_values(vec![
+                Number(8080),
+                String("localhost".into()),
+                Bool(true),
+                Number(1),
+                Number(0),
+            ])
+        );
+
+        let result = InterpreterBuilder::default()
+            .with_bindings([("config", "port", Number(8080)), ("config", "fn*", Nil)])
+            .try_build();
+        assert!(matches!(
+            result,
+            Err(EvaluationError::CannotRedefine(name)) if name == "fn*"
+        ));
     }
 
-    pub fn evaluate_from_source(&mut self, source: &str) -> EvaluationResult<Vec<Value>> {
-        read(source)
-            .map_err(|err| EvaluationError::ReaderError(err, source.to_string()))?
-            .iter()
-            .map(|form| self.evaluate(form))
-            .collect()
+    #[test]
+    fn test_constant_bindings_are_not_captured() {
+        let mut interpreter = Interpreter::default();
+        let result = interpreter
+            .evaluate_from_source(
+                "(def! f (fn* [] (let* [x 1 s \"s\"] (fn* [] [x s])))) (f) ((f)) ((fn* [y] (let* [x y] (fn* [] x))) 2)",
+            )
+            .unwrap();
+        assert!(matches!(result[1], Fn(..)));
+        assert_eq!(
+            result[2],
+            vector_with_values(vec![Number(1), String("s".into())])
+        );
+        assert!(matches!(result[3], FnWithCaptures(..)));
+
+        let result = interpreter
+            .evaluate_from_source("((fn* [] (loop* [i 0] (if (< i 3) (recur (+ i 1)) i))))")
+            .unwrap();
+        assert_eq!(result[0], Number(3));
+
+        // names are left as written in quoted data and where they are defined
+        let result = interpreter
+            .evaluate_from_source(
+                "(def! f (fn* [] (let* [n 3] (quote (n n))))) (f)
+                 ((fn* [] (let* [x :a] `(x ~x))))
+                 ((fn* [] (let* [x 1] (def! x 5) x)))",
+            )
+            .unwrap();
+        let symbol = |name: &str| Symbol(name.to_string(), None);
+        assert_eq!(result[1], list_with_values([symbol("n"), symbol("n")]));
+        assert_eq!(
+            result[2],
+            list_with_values([symbol("x"), Keyword("a".to_string(), None)])
+        );
+        assert_eq!(result[3], Number(1));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::namespace::DEFAULT_NAME as DEFAULT_NAMESPACE;
-    use crate::reader::read;
-    use crate::testing::run_eval_test;
-    use crate::value::{
-        atom_with_value, exception, list_with_values, map_with_values, var_with_value,
-        vector_with_values, PersistentList, PersistentMap, PersistentVector,
-        Value::{self, *},
-    };
+    #[test]
+    fn test_protected_names() {
+        let test_cases = vec![
+            (
+                "(try* (def! if 3) (catch* e :error))",
+                Keyword("error".to_string(), None),
+            ),
+            (
+                "(try* (def! fn*) (catch* e :error))",
+                Keyword("error".to_string(), None),
+            ),
+            (
+                "(try* (def! + -) (catch* e :error))",
+                Keyword("error".to_string(), None),
+            ),
+            ("(try* (def! + -) (catch* e nil)) (+ 1 1)", Number(2)),
+            ("(def! inc dec) (inc 1)", Number(0)),
+        ];
+        run_eval_test(&test_cases);
+
+        let mut interpreter = InterpreterBuilder::default()
+            .with_primitive_redefinition()
+            .build();
+        let result = interpreter
+            .evaluate_from_source("(def! + -) (+ 3 1)")
+            .unwrap();
+        assert_eq!(result[1], Number(2));
+        assert!(interpreter.evaluate_from_source("(def! if 3)").is_err());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let resolver: InMemoryResolver = [("shadowing", "(def! inc dec)")].into_iter().collect();
+        let mut interpreter = InterpreterBuilder::default()
+            .with_eager_core()
+            .with_module_resolver(resolver)
+            .build();
+        assert!(interpreter.diagnostics().is_empty());
+
+        interpreter
+            .evaluate_from_source("(let* [a 1 b 2 _c 3] a) (let* [a 1 b 2 _c 3] a)")
+            .unwrap();
+        let kinds = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![DiagnosticKind::UnusedBinding("b".to_string())]);
+
+        interpreter
+            .evaluate_from_source(
+                "(def! f (fn* [x] (let* [y 1 z y] x))) (f 1) (f 2) (def! g (fn* [a] a)) (def! h (fn* [] (g 1 2)))",
+            )
+            .unwrap();
+        let kinds = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                DiagnosticKind::UnusedBinding("z".to_string()),
+                DiagnosticKind::SuspiciousArity {
+                    name: "g".to_string(),
+                    realized: 2
+                },
+            ]
+        );
+
+        // forms in diagnostics name parameters as written
+        interpreter
+            .evaluate_from_source("(def! k (fn* [x & more] (g x more)))")
+            .unwrap();
+        let forms = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.form.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(forms, vec!["(#'core/g x more)".to_string()]);
+
+        interpreter
+            .evaluate_from_source("(require 'shadowing)")
+            .unwrap();
+        let kinds = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![DiagnosticKind::ShadowedVar {
+                namespace: "shadowing".to_string(),
+                name: "inc".to_string()
+            }]
+        );
+
+        interpreter
+            .evaluate_from_source(
+                "(def! ^{:deprecated \"use `str`\"} old-str str) (def! ^{:deprecated true} old 1) (def! m (fn* [] [(old-str old) old]))",
+            )
+            .unwrap();
+        let diagnostics = interpreter
+            .take_diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "warning: var `core/old-str` is deprecated: use `str` in `old-str`".to_string(),
+                "warning: var `core/old` is deprecated in `old`".to_string(),
+            ]
+        );
+
+        // pending warnings are bounded until taken
+        for index in 0..MAX_PENDING_DIAGNOSTICS + 1 {
+            let kind = DiagnosticKind::UnusedBinding(index.to_string());
+            interpreter.report(kind.clone(), &Nil);
+            interpreter.report(kind, &Nil);
+        }
+        assert_eq!(interpreter.diagnostics().len(), MAX_PENDING_DIAGNOSTICS);
+        interpreter.take_diagnostics();
+        interpreter.report(DiagnosticKind::UnusedBinding("0".to_string()), &Nil);
+        assert_eq!(interpreter.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn test_var_redefinition_in_fn_body() {
+        let test_cases = vec![
+            (
+                "(def! g (fn* [] 1)) (def! f (fn* [] (g))) (def! g (fn* [] 2)) (f)",
+                Number(2),
+            ),
+            ("(def! a 1) (def! f (fn* [] a)) (def! a 3) (f)", Number(3)),
+            (
+                "(declare h) (def! f (fn* [] (h))) (def! h (fn* [] :later)) (f)",
+                Keyword("later".to_string(), None),
+            ),
+        ];
+        run_eval_test(&test_cases);
+    }
 
     #[test]
     fn test_basic_self_evaluating() {
@@ -1162,13 +4716,13 @@ mod test {
             ("false", Bool(false)),
             ("1337", Number(1337)),
             ("-1337", Number(-1337)),
-            ("\"hi\"", String("hi".to_string())),
-            (r#""""#, String("".to_string())),
-            ("\"abc\"", String("abc".to_string())),
-            ("\"abc   def\"", String("abc   def".to_string())),
-            ("\"abc\\ndef\\nghi\"", String("abc\ndef\nghi".to_string())),
-            ("\"abc\\def\\ghi\"", String("abc\\def\\ghi".to_string())),
-            ("\" \\\\n \"", String(" \\n ".to_string())),
+            ("\"hi\"", String("hi".into())),
+            (r#""""#, String("".into())),
+            ("\"abc\"", String("abc".into())),
+            ("\"abc   def\"", String("abc   def".into())),
+            ("\"abc\\ndef\\nghi\"", String("abc\ndef\nghi".into())),
+            ("\"abc\\def\\ghi\"", String("abc\\def\\ghi".into())),
+            ("\" \\\\n \"", String(" \\n ".into())),
             (":hi", Keyword("hi".to_string(), None)),
             (
                 ":foo/hi",
@@ -1214,7 +4768,7 @@ mod test {
             ),
             (
                 "{\"a\" (+ 7 8)}",
-                map_with_values(vec![(String("a".to_string()), Number(15))]),
+                map_with_values(vec![(String("a".into()), Number(15))]),
             ),
         ];
         run_eval_test(&test_cases);
@@ -1253,6 +4807,61 @@ mod test {
                 "(def! SOME-NUM 4)",
                 var_with_value(Number(4), DEFAULT_NAMESPACE, "SOME-NUM"),
             ),
+            ("(def! ^{:doc \"three\"} a 3) a", Number(3)),
+            (
+                "(def! ^{:doc \"three\"} a 3) (meta #'a)",
+                map_with_values([(Keyword("doc".to_string(), None), String("three".into()))]),
+            ),
+            ("(def! ^:private a 3) (:private (meta #'a))", Bool(true)),
+            (
+                "(def! ^{:doc (str \"th\" \"ree\")} a 3) (:doc (meta #'a))",
+                String("three".into()),
+            ),
+            ("(def! ^:private a 3) (def! a 4) (meta #'a)", Nil),
+            ("(def! a 3) (meta #'a)", Nil),
+            ("(meta 3)", Nil),
+            ("(with-meta [1] {:a 1})", vector_with_values([Number(1)])),
+            (
+                "(meta (with-meta [1] {:a 1}))",
+                map_with_values([(Keyword("a".to_string(), None), Number(1))]),
+            ),
+            ("(meta (with-meta (with-meta [1] {:a 1}) nil))", Nil),
+            ("^{:a 1} [1]", vector_with_values([Number(1)])),
+            (
+                "(meta ^{:a (+ 1 1)} [1 2])",
+                map_with_values([(Keyword("a".to_string(), None), Number(2))]),
+            ),
+            (
+                "(meta '^:foo x)",
+                map_with_values([(Keyword("foo".to_string(), None), Bool(true))]),
+            ),
+            ("'^:foo x", Symbol("x".to_string(), None)),
+            ("(= '^:foo [x (y)] '[x (y)])", Bool(true)),
+            ("(count ^:foo [1 2])", Number(2)),
+            (
+                "(meta (conj ^:foo [1] 2))",
+                map_with_values([(Keyword("foo".to_string(), None), Bool(true))]),
+            ),
+            (
+                "(meta (nth `(def! ^:foo x) 1))",
+                map_with_values([(Keyword("foo".to_string(), None), Bool(true))]),
+            ),
+            ("(def! ^:foo v [1]) (meta v)", Nil),
+            ("(let* [^:private x 1] x)", Number(1)),
+            ("(def! f (fn* [x] (+ x 1))) (#'f 1)", Number(2)),
+            ("(def! f (fn* [x] (+ x 1))) ((var f) 1)", Number(2)),
+            ("(def! f (fn* [x] (+ x 1))) (def! g #'f) (g 1)", Number(2)),
+            ("(def! f (fn* [x] (+ x 1))) (apply #'f [1])", Number(2)),
+            (
+                "(def! f (fn* [x] (+ x 1))) (map #'f [1 2])",
+                list_with_values([Number(2), Number(3)]),
+            ),
+            ("(def! f (fn* [x] (+ x 1))) ((first [#'f]) 1)", Number(2)),
+            ("(#'+ 1 2)", Number(3)),
+            (
+                "(def! u) (try* (#'u 1) (catch* e :invalid))",
+                Keyword("invalid".to_string(), None),
+            ),
         ];
         run_eval_test(&test_cases);
     }
@@ -1305,6 +4914,15 @@ mod test {
             ("(let* [y (let* [x 12] (fn* [] (fn* [] x)))] ((y)))", Number(12)),
             ("(let* [y (let* [x 12] ((fn* [x] (fn* [] (inc x))) x))] (y))", Number(13)),
             ("(let* [y (let* [y 12] ((fn* [y] (fn* [] (inc y))) y))] (y))", Number(13)),
+            // forward references are only usable once the binding is initialized
+            ("(let* [f (fn* [] g) g (fn* [] 2)] ((f)))", Number(2)),
+            ("(let* [f (fn* [] 1) x [f]] (= x [f]))", Bool(true)),
+            ("(let* [f (fn* [] 1) g f] (g))", Number(1)),
+            ("(try* (let* [x [f] f (fn* [] 1)] x) (catch* e :uninitialized))", Keyword("uninitialized".to_string(), None)),
+            ("(try* (let* [x f f (fn* [] 1)] x) (catch* e :uninitialized))", Keyword("uninitialized".to_string(), None)),
+            ("(try* (let* [f (fn* [] (g)) x (f) g (fn* [] 2)] x) (catch* e :uninitialized))", Keyword("uninitialized".to_string(), None)),
+            ("(let* [f (fn* [] (g)) g (fn* [] 2) x (f)] x)", Number(2)),
+            ("(let* [ev? (fn* [n] (if (= n 0) true (od? (dec n)))) od? (fn* [n] (if (= n 0) false (ev? (dec n))))] [(ev? 10) (od? 7)])", vector_with_values(vec![Bool(true), Bool(true)])),
         ];
         run_eval_test(&test_cases);
     }
@@ -1348,6 +4966,11 @@ mod test {
             ("((fn* [] (+ 4 3)) )", Number(7)),
             ("((fn* [f x] (f x)) (fn* [a] (+ 1 a)) 7)", Number(8)),
             ("((fn* [a] (+ a 1) 25) 23)", Number(25)),
+            // arguments to a closure are evaluated without its captures in scope
+            (
+                "(def! wrap (fn* [f] (fn* [x] (f x)))) (def! g (wrap (fn* [n] (if (< n 1) 0 (+ 1 (g (- n 1))))))) (g 3)",
+                Number(3),
+            ),
             ("((fn* [a] (let* [b 2] (+ a b))) 23)", Number(25)),
             ("((fn* [a] (let* [a 2] (+ a a))) 23)", Number(4)),
             (
@@ -1357,6 +4980,19 @@ mod test {
             ("((fn* [a] ((fn* [b] (+ b 1)) a)) 1)", Number(2)),
             ("((fn* [a] ((fn* [a] (+ a 1)) a)) 1)", Number(2)),
             ("((fn* [] ((fn* [] ((fn* [] 13))))))", Number(13)),
+            // parameters are resolved to their ordinal, unless quoted
+            (
+                "((fn* [a b] [(quote b) (first '(a)) b]) 1 2)",
+                vector_with_values([
+                    Symbol("b".to_string(), None),
+                    Symbol("a".to_string(), None),
+                    Number(2),
+                ]),
+            ),
+            (
+                "((fn* [a] ((fn* [b] (try* (throw b) (catch* e [a b]))) 2)) 1)",
+                vector_with_values([Number(1), Number(2)]),
+            ),
             (
                 "(def! factorial (fn* [n] (if (< n 2) 1 (* n (factorial (- n 1)))))) (factorial 8)",
                 Number(40320),
@@ -1404,6 +5040,15 @@ mod test {
             // test capturing let* bindings
             ("(def f (fn* [x] (let* [x x] (if (list? (first x)) (f (first x)) (fn* [] x))))) (first ((eval (f '(3)))))", Number(3)),
             ("(def f (fn* [x] (let* [x '((fn* [] 4))] (if (list? (first x)) (f (first x)) (fn* [] x))))) ((first ((eval (f '((fn* [] 3)))))))", Number(4)),
+            // test validation of bindings
+            ("(try* (fn* [a a] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (fn* [a & a] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (fn* [& a b] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (fn* [a &] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (fn* [a & b & c] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (let* [a 1 a 2] a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* ((fn* [] (let* [a 1 a 2] a))) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(let* [a 1] (let* [a 2] a))", Number(2)),
         ];
         run_eval_test(&test_cases);
     }
@@ -1430,6 +5075,22 @@ mod test {
                 "(def! f (fn* [i] (loop* [n i] (if (< n 400) (recur (+ 1 n)) n)))) (f 0)",
                 Number(400),
             ),
+            ("(loop* [i 0] (cond (< i 3) (recur (inc i)) :else i))", Number(3)),
+            ("(loop* [i 0] (let* [j (inc i)] (do (if (< j 3) (recur j) j))))", Number(3)),
+            ("(loop* [i 0] '(+ 1 (recur i)))", list_with_values([Symbol("+".to_string(), None), Number(1), list_with_values([Symbol("recur".to_string(), None), Symbol("i".to_string(), None)])])),
+            ("(try* (loop* [i 0] (+ 1 (recur i))) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (loop* [i 0] (if (recur i) 1 2)) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (loop* [i (recur 1)] i) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (fn* [i] (inc (recur i))) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(def! f (fn* [i] (if (< i 10000) (recur (inc i)) i))) (f 0)", Number(10000)),
+            ("(def! f (fn* [n acc] (if (< n 1) acc (recur (dec n) (* acc n))))) (f 5 1)", Number(120)),
+            ("(def! f (fn* [n & xs] (if (< n 1) xs (recur (dec n) (cons n xs))))) (f 3)", list_with_values([Number(1), Number(2), Number(3)])),
+            ("((fn* [i] (loop* [j i] (if (< j 3) (recur (inc j)) j))) 0)", Number(3)),
+            ("(loop* [i 0] ((fn* [j] (if (< j 3) (recur (inc j)) j)) i))", Number(3)),
+            ("(try* ((fn* [i] (if (< i 3) (recur) i)) 0) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            // the error names the form the `recur` rebinds
+            ("(try* (fn* [i] (inc (recur i))) (catch* e (ex-message e)))", String("syntax error: `recur` must be in tail position of the enclosing `fn*` but found `(recur i)`".into())),
+            ("(try* (fn* [i] (loop* [j i] (inc (recur j)))) (catch* e (ex-message e)))", String("syntax error: `recur` must be in tail position of the enclosing `loop*` but found `(recur j)`".into())),
         ];
         run_eval_test(&test_cases);
     }
@@ -1450,6 +5111,12 @@ mod test {
                 "(def! a (atom 5)) (def! inc (fn* [x] (+ x 1))) (swap! a inc)",
                 Number(6),
             ),
+            ("(def! a (atom 5)) (compare-and-set! a 5 6)", Bool(true)),
+            ("(def! a (atom 5)) (compare-and-set! a 5 6) @a", Number(6)),
+            ("(def! a (atom 5)) (compare-and-set! a 4 6)", Bool(false)),
+            ("(def! a (atom 5)) (compare-and-set! a 4 6) @a", Number(5)),
+            ("(def! a (atom [1])) (compare-and-set! a [1] nil) @a", Nil),
+            ("(try* (compare-and-set! 5 5 6) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
             ("(def! a (atom 5)) (swap! a + 1 2 3 4 5)", Number(20)),
             (
                 "(def! a (atom 5)) (swap! a + 1 2 3 4 5) (deref a)",
@@ -1874,6 +5541,21 @@ mod test {
         run_eval_test(&test_cases);
     }
 
+    #[test]
+    fn test_quasiquote_expansion() {
+        let expand = |source: &str| {
+            let form = read(source).unwrap().remove(0);
+            super::eval_quasiquote(&form).unwrap().to_string()
+        };
+        assert_eq!(expand("(a (b [c {:d e}]))"), "(quote (a (b [c {:d e}])))");
+        assert_eq!(expand("[a b]"), "(quote [a b])");
+        assert_eq!(
+            expand("(a b ~c (d) ~@e f)"),
+            "(core/concat (core/list (quote a) (quote b) c (quote (d))) e (core/list (quote f)))"
+        );
+        assert_eq!(expand("[~a b]"), "(core/vec (core/list a (quote b)))");
+    }
+
     #[test]
     fn test_basic_macros() {
         let test_cases = vec![
@@ -1952,7 +5634,7 @@ mod test {
             "test",
             &map_with_values(vec![(
                 Keyword("cause".to_string(), None),
-                String("no memory".to_string()),
+                String("no memory".into()),
             )]),
         );
         let test_cases = vec![
@@ -1972,13 +5654,40 @@ mod test {
                 exc.clone(),
             ),
             ("(try* 123 (catch* e 0))", Number(123)),
+            ("(try* 123 (finally* 0))", Number(123)),
+            ("(try* (finally* 0))", Nil),
+            ("(try* (throw :a) (catch* e (ex-data e)) (finally* 0))", Keyword("a".to_string(), None)),
+            (
+                "(def! a (atom 0)) (try* (throw :a) (catch* e (swap! a + 1)) (finally* (swap! a + 10))) (deref a)",
+                Number(11),
+            ),
+            (
+                "(def! a (atom 0)) (try* (try* (throw :a) (finally* (reset! a 1))) (catch* e [(ex-data e) (deref a)]))",
+                vector_with_values(vec![Keyword("a".to_string(), None), Number(1)]),
+            ),
+            (
+                "(try* (try* (throw :a) (catch* e (throw :b)) (finally* 0)) (catch* e (ex-data e)))",
+                Keyword("b".to_string(), None),
+            ),
+            (
+                "(try* (try* 1 (finally* (throw :c))) (catch* e (ex-data e)))",
+                Keyword("c".to_string(), None),
+            ),
+            (
+                "(try* (try* (throw :a) (finally* (throw :c))) (catch* e (ex-data e)))",
+                Keyword("c".to_string(), None),
+            ),
+            (
+                "(def! a (atom 0)) (def! f (fn* [x] (try* (+ x :b) (catch* e x) (finally* (reset! a x))))) [(f 3) (deref a)]",
+                vector_with_values(vec![Number(3), Number(3)]),
+            ),
             (
                 "(try* (ex-info \"test\" {:cause \"no memory\"}) (catch* e 0))",
                 exc,
             ),
             (
                 "(try* (throw (ex-info \"test\" {:cause \"no memory\"})) (catch* e (str e)))",
-                String("test, {:cause \"no memory\"}".to_string()),
+                String("test, {:cause \"no memory\"}".into()),
             ),
             (
                 "(try* (throw (ex-info \"test\" {:cause \"no memory\"})) (catch* e 999))",
@@ -1997,7 +5706,7 @@ mod test {
                     &map_with_values(
                         [(
                             Keyword("cause".to_string(), None),
-                            String("no memory".to_string()),
+                            String("no memory".into()),
                         )]
                         .iter()
                         .cloned(),
@@ -2029,11 +5738,11 @@ mod test {
             ),
             (
                 "(try* (do (try* \"t1\" (catch* e \"c1\")) (throw \"e1\")) (catch* e \"c2\"))",
-                String("c2".to_string()),
+                String("c2".into()),
             ),
             (
                 "(try* (try* (throw \"e1\") (catch* e (throw \"e2\"))) (catch* e \"c2\"))",
-                String("c2".to_string()),
+                String("c2".into()),
             ),
             (
                 "(def! f (fn* [a] ((fn* [] (try* (throw (ex-info \"test\" {:cause 22})) (catch* e (prn e) a)))))) (f 2222)",
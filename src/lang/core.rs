@@ -1,48 +1,108 @@
-use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter, InterpreterError};
-use crate::namespace::Namespace;
-use crate::reader::read;
+use crate::audit::AuditEvent;
+use crate::hierarchy::Hierarchy;
+use crate::interpreter::{
+    truncated_form, DeferredSource, EvaluationError, EvaluationResult, Interpreter,
+    InterpreterError, NamespaceHooks, SpecialForm,
+};
+use crate::namespace::{Namespace, DEFAULT_NAME};
+use crate::reader::{is_symbolic, read, read_with_tag_reader};
+use crate::streams::Stream;
 use crate::value::{
-    atom_impl_into_inner, atom_with_value, exception, list_with_values, map_with_values,
-    set_with_values, var_impl_into_inner, vector_with_values, FnWithCapturesImpl, NativeFn,
-    PersistentList, PersistentSet, PersistentVector, Value,
+    atom_impl_into_inner, atom_with_value, bytes_with_values, char_string, exception,
+    exception_with_cause, list_with_values, map_with_values, set_with_values, var_impl_into_inner,
+    vector_with_values, FnWithCapturesImpl, LruCacheImpl, NativeFn, PersistentList, PersistentMap,
+    PersistentQueue, PersistentSet, PersistentVector, PrintMode, Value, WeakRefImpl,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use itertools::Itertools;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
-use std::io::{BufRead, Write as IOWrite};
+use std::io::{BufRead, BufReader, BufWriter, Write as IOWrite};
+use std::mem::discriminant;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::{fs, io};
+use std::{fs, io, iter};
 
-const SOURCE: &str = include_str!("./core.sigil");
+// sigil sources for this namespace, each evaluated the first time one of its names is referenced
+const SOURCES: &[DeferredSource] = &[
+    DeferredSource {
+        namespace: DEFAULT_NAME,
+        source: include_str!("./core/macros.sigil"),
+    },
+    DeferredSource {
+        namespace: DEFAULT_NAME,
+        source: include_str!("./core/fns.sigil"),
+    },
+    DeferredSource {
+        namespace: DEFAULT_NAME,
+        source: include_str!("./core/transducers.sigil"),
+    },
+];
+const PRINT_ALIASES_SYMBOL: &str = "*print-aliases*";
+const PRINT_NAMESPACE_MAPS_SYMBOL: &str = "*print-namespace-maps*";
 const BINDINGS: &[(&str, NativeFn)] = &[
     ("+", plus),
     ("-", subtract),
     ("*", multiply),
     ("/", divide),
+    ("min", min),
+    ("max", max),
+    ("sum", sum),
+    ("clamp", clamp),
     ("pr", pr),
     ("prn", prn),
     ("pr-str", pr_str),
     ("print", print_),
     ("println", println),
     ("print-str", print_str),
+    ("add-print-method!", add_print_method),
+    ("derive!", derive),
+    ("underive!", underive),
+    ("isa?", isa),
+    ("parents", parents),
+    ("ancestors", ancestors),
+    ("descendants", descendants),
     ("list", list),
     ("list?", is_list),
     ("empty?", is_empty),
+    ("empty", empty),
     ("count", count),
     ("<", less),
     ("<=", less_eq),
     (">", greater),
     (">=", greater_eq),
     ("=", equal),
+    ("==", numeric_equal),
     ("read-string", read_string),
+    ("intern-data", intern_data),
+    ("template-parts", template_parts),
     ("spit", spit),
     ("slurp", slurp),
+    ("load-file", load_file),
+    ("spit-bytes", spit_bytes),
+    ("slurp-bytes", slurp_bytes),
+    ("reader", reader),
+    ("writer", writer),
+    ("read-line", read_line),
+    ("line-seq", line_seq),
+    ("read-lines", read_lines),
+    ("write-line", write_line),
     ("eval", eval),
     ("str", to_str),
+    ("subs", subs),
+    ("parse-long", parse_long),
+    ("number->string", number_to_string),
     ("atom", to_atom),
     ("atom?", is_atom),
+    ("file-atom", file_atom),
+    ("weak-ref", weak_ref),
+    ("weak-ref?", is_weak_ref),
     ("deref", deref),
     ("reset!", reset_atom),
     ("swap!", swap_atom),
+    ("compare-and-set!", compare_and_set_atom),
     ("cons", cons),
     ("concat", concat),
     ("vec", vec),
@@ -50,41 +110,108 @@ const BINDINGS: &[(&str, NativeFn)] = &[
     ("first", first),
     ("rest", rest),
     ("ex-info", ex_info),
+    ("ex-message", ex_message),
+    ("ex-data", ex_data),
+    ("ex-cause", ex_cause),
+    ("close!", close),
+    ("require", require),
+    ("alias", alias),
+    ("remove-ns!", remove_ns),
+    ("ns-hooks", ns_hooks),
+    ("ns-aliases", ns_aliases),
+    ("completions", completions),
+    ("audit-log", audit_log),
+    ("resolve-symbol", resolve_symbol),
     ("throw", throw),
     ("apply", apply),
     ("map", map),
+    ("mapv", mapv),
+    ("reduce", reduce),
+    ("reduce-kv", reduce_kv),
+    ("reduced", to_reduced),
+    ("reduced?", is_reduced),
+    ("filterv", filterv),
+    ("filter", filter),
+    ("remove", remove),
     ("nil?", is_nil),
+    ("some?", is_some),
+    ("boolean", to_boolean),
+    ("not", not),
     ("true?", is_true),
     ("false?", is_false),
     ("symbol?", is_symbol),
     ("symbol", to_symbol),
     ("keyword", to_keyword),
+    ("name", to_name),
+    ("namespace", to_namespace),
     ("keyword?", is_keyword),
     ("vector", to_vector),
     ("vector?", is_vector),
+    ("byte-array", byte_array),
+    ("bytes?", is_bytes),
+    ("uuid?", is_uuid),
+    ("inst?", is_inst),
+    ("aget", aget),
+    ("aset", aset),
+    ("bytes->string", bytes_to_string),
+    ("string->bytes", string_to_bytes),
     ("sequential?", is_sequential),
+    ("coll?", is_coll),
+    ("seqable?", is_seqable),
     ("hash-map", to_map),
     ("map?", is_map),
     ("set", to_set),
     ("set?", is_set),
+    ("queue", queue),
+    ("queue?", is_queue),
     ("assoc", assoc),
     ("dissoc", dissoc),
+    ("update", update),
+    ("select-keys", select_keys),
+    ("rename-keys", rename_keys),
+    ("deep-merge", deep_merge),
+    ("deep-merge-with", deep_merge_with),
+    ("lru-cache", lru_cache),
+    ("lru-lookup", lru_lookup),
+    ("lru-put", lru_put),
+    ("subvec", subvec),
+    ("take", take),
+    ("drop", drop),
+    ("take-last", take_last),
+    ("drop-last", drop_last),
+    ("nthrest", nthrest),
+    ("slice", slice),
+    ("peek", peek),
+    ("pop", pop),
     ("get", get),
     ("contains?", does_contain),
     ("keys", to_keys),
+    ("key", to_key),
+    ("val", to_val),
+    ("map-entry?", is_map_entry),
     ("vals", to_vals),
     ("last", last),
     ("string?", is_string),
     ("number?", is_number),
     ("fn?", is_fn),
     ("conj", conj),
+    ("into", into),
     ("macro?", is_macro),
     ("time-ms", time_in_millis),
+    ("nano-time", nano_time),
+    ("monotonic-ms", monotonic_millis),
     ("seq", to_seq),
     ("readline", readline),
     ("meta", to_meta),
     ("with-meta", with_meta),
     ("zero?", is_zero),
+    ("diff", diff),
+    ("hash", hash),
+    ("count-nodes", count_nodes),
+    ("measure", measure),
+    ("walk", walk),
+    ("postwalk", postwalk),
+    ("prewalk", prewalk),
 ];
 
 // loads the namespace represented by this Rust module into `interpreter`
@@ -94,13 +221,20 @@ pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
         let value = Value::Primitive(*f);
         namespace.intern(k, &value).expect("can intern");
     }
+    for name in [PRINT_ALIASES_SYMBOL, PRINT_NAMESPACE_MAPS_SYMBOL] {
+        namespace
+            .intern(name, &Value::Bool(false))
+            .expect("can intern");
+    }
 
     // TODO: remove once we can determine namespace from source
     interpreter.set_namespace(&namespace);
 
     interpreter.load_namespace(namespace)?;
 
-    interpreter.evaluate_from_source(SOURCE).expect("is valid");
+    for source in SOURCES {
+        interpreter.defer_source(*source)?;
+    }
 
     Ok(())
 }
@@ -174,6 +308,19 @@ fn multiply(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         .map(Value::Number)
 }
 
+// Euclidean division, so the remainder is never negative and `(/ -7 2)` is `-4`, as
+// with floor division, while `(/ 7 -2)` is `-3` rather than the `-4` of floor division.
+// Dividing by zero, or `i64::MIN` by `-1`, raises an error with `:arithmetic-error` as
+// the `:type` of its `ex-data`.
+fn checked_divide(dividend: i64, divisor: i64) -> EvaluationResult<i64> {
+    if divisor == 0 {
+        return Err(EvaluationError::DivideByZero(dividend));
+    }
+    dividend
+        .checked_div_euclid(divisor)
+        .ok_or(EvaluationError::Overflow(dividend, divisor))
+}
+
 fn divide(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     match args.len() {
         0 => Err(EvaluationError::WrongArity {
@@ -181,10 +328,7 @@ fn divide(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
             realized: 0,
         }),
         1 => match &args[0] {
-            Value::Number(first) => 1_i64
-                .checked_div_euclid(*first)
-                .ok_or_else(|| EvaluationError::Overflow(1, *first))
-                .map(Value::Number),
+            Value::Number(first) => checked_divide(1, *first).map(Value::Number),
             other => Err(EvaluationError::WrongType {
                 expected: "Number",
                 realized: other.clone(),
@@ -197,9 +341,7 @@ fn divide(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
                 Value::Number(first) => rest_values
                     .iter()
                     .try_fold(*first, |acc, x| match x {
-                        Value::Number(next) => acc
-                            .checked_div_euclid(*next)
-                            .ok_or_else(|| EvaluationError::Overflow(acc, *next)),
+                        Value::Number(next) => checked_divide(acc, *next),
                         other => Err(EvaluationError::WrongType {
                             expected: "Number",
                             realized: other.clone(),
@@ -215,45 +357,428 @@ fn divide(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
 }
 
-fn pr(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    let result = args.iter().map(|arg| arg.to_readable_string()).join(" ");
+// Write `value` into `buffer` according to `mode`, deferring to any
+// registered print methods for `value` or the values it contains.
+fn print_value(
+    interpreter: &mut Interpreter,
+    value: &Value,
+    options: &PrintOptions,
+    buffer: &mut String,
+) -> EvaluationResult<()> {
+    if interpreter.print_methods.is_empty() && options.is_plain() {
+        push_value(value, options.mode, buffer);
+        return Ok(());
+    }
+    for (predicate, printer) in interpreter.print_methods.clone() {
+        if !apply_callable(interpreter, &predicate, std::slice::from_ref(value))?.is_truthy() {
+            continue;
+        }
+        return match apply_callable(interpreter, &printer, std::slice::from_ref(value))? {
+            Value::String(s) => {
+                buffer.push_str(&s);
+                Ok(())
+            }
+            other => Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other,
+            }),
+        };
+    }
+    match value {
+        Value::List(elems) => {
+            print_values(interpreter, elems.iter(), options, "(", " ", ")", buffer)
+        }
+        Value::Vector(elems) => {
+            print_values(interpreter, elems.iter(), options, "[", " ", "]", buffer)
+        }
+        Value::Set(elems) => {
+            print_values(interpreter, elems.iter(), options, "#{", " ", "}", buffer)
+        }
+        Value::Queue(elems) => print_values(
+            interpreter,
+            elems.iter(),
+            options,
+            "#queue [",
+            " ",
+            "]",
+            buffer,
+        ),
+        Value::Map(elems) => {
+            let namespace = options.namespace_of_keys(elems);
+            if let Some(namespace) = namespace {
+                match options.aliases.get(namespace) {
+                    Some(alias) => write!(buffer, "#::{}", alias),
+                    None => write!(buffer, "#:{}", namespace),
+                }
+                .expect("can write to string");
+            }
+            buffer.push('{');
+            for (index, (k, v)) in elems.iter().enumerate() {
+                if index > 0 {
+                    buffer.push_str(", ");
+                }
+                match k {
+                    Value::Keyword(id, _) if namespace.is_some() => {
+                        let k = Value::Keyword(id.clone(), None);
+                        print_value(interpreter, &k, options, buffer)?;
+                    }
+                    k => print_value(interpreter, k, options, buffer)?,
+                }
+                buffer.push(' ');
+                print_value(interpreter, v, options, buffer)?;
+            }
+            buffer.push('}');
+            Ok(())
+        }
+        Value::Atom(v) => {
+            let inner = v.borrow().clone();
+            buffer.push_str("(atom ");
+            print_value(interpreter, &inner, options, buffer)?;
+            buffer.push(')');
+            Ok(())
+        }
+        Value::Keyword(id, Some(ns)) if options.aliases.contains_key(ns) => {
+            write!(buffer, "::{}/{}", options.aliases[ns], id).expect("can write to string");
+            Ok(())
+        }
+        other => {
+            push_value(other, options.mode, buffer);
+            Ok(())
+        }
+    }
+}
+
+// writes `value` straight into `buffer` so that displayed strings are not copied first
+fn push_value(value: &Value, mode: PrintMode, buffer: &mut String) {
+    match mode {
+        PrintMode::Display => write!(buffer, "{}", value).expect("can write to string"),
+        PrintMode::Readable => buffer.push_str(&value.to_readable_string()),
+    }
+}
+
+fn print_values<'a>(
+    interpreter: &mut Interpreter,
+    values: impl Iterator<Item = &'a Value>,
+    options: &PrintOptions,
+    open: &str,
+    separator: &str,
+    close: &str,
+    buffer: &mut String,
+) -> EvaluationResult<()> {
+    buffer.push_str(open);
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            buffer.push_str(separator);
+        }
+        print_value(interpreter, value, options, buffer)?;
+    }
+    buffer.push_str(close);
+    Ok(())
+}
+
+// how to print values in `mode`, including the behavior controlled by
+// the `*print-aliases*` and `*print-namespace-maps*` vars
+struct PrintOptions {
+    mode: PrintMode,
+    // namespace name to the alias printed in its place, when `*print-aliases*` is set,
+    // holding only aliases `read-string` resolves back to the namespace
+    aliases: HashMap<String, String>,
+    // print maps whose keys are all keywords in one namespace as e.g. `#:ns{:a 1}`
+    namespace_maps: bool,
+}
+
+impl PrintOptions {
+    fn new(interpreter: &Interpreter, mode: PrintMode) -> Self {
+        let is_set = |name: &str| match interpreter.resolve_symbol_to_var(name, None) {
+            Ok(Value::Var(var)) => interpreter
+                .var_value(&var)
+                .is_some_and(|value| value.is_truthy()),
+            _ => false,
+        };
+        let aliases = if is_set(PRINT_ALIASES_SYMBOL) {
+            // of several aliases for a namespace the shortest, then the least, is collected last
+            interpreter
+                .current_namespace_aliases()
+                .iter()
+                .filter(|(alias, _)| is_readable_alias(alias))
+                .sorted_by(|(a, _), (b, _)| (b.len(), b).cmp(&(a.len(), a)))
+                .map(|(alias, namespace)| (namespace.clone(), alias.clone()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Self {
+            mode,
+            aliases,
+            namespace_maps: is_set(PRINT_NAMESPACE_MAPS_SYMBOL),
+        }
+    }
+
+    fn is_plain(&self) -> bool {
+        self.aliases.is_empty() && !self.namespace_maps
+    }
+
+    // the namespace shared by every key of `map` if printing namespace maps
+    fn namespace_of_keys<'a>(&self, map: &'a PersistentMap<Value, Value>) -> Option<&'a String> {
+        if !self.namespace_maps {
+            return None;
+        }
+        let mut namespace = None;
+        for key in map.keys() {
+            match key {
+                Value::Keyword(_, Some(ns)) if namespace.is_none_or(|n| n == ns) => {
+                    namespace = Some(ns)
+                }
+                _ => return None,
+            }
+        }
+        namespace
+    }
+}
+
+// whether the reader reads `::alias/name` as a keyword namespaced by `alias`
+fn is_readable_alias(alias: &str) -> bool {
+    !alias.is_empty()
+        && alias
+            .chars()
+            .all(|ch| is_symbolic(ch) && ch != ':' && ch != '/')
+}
+
+fn print_args(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    mode: PrintMode,
+) -> EvaluationResult<String> {
+    let options = PrintOptions::new(interpreter, mode);
+    let mut result = String::new();
+    print_values(interpreter, args.iter(), &options, "", " ", "", &mut result)?;
+    Ok(result)
+}
+
+fn audit_io(interpreter: &mut Interpreter, name: &'static str, args: &[Value]) {
+    interpreter.audit(|| AuditEvent::Io {
+        name,
+        args: args.to_vec(),
+    });
+}
+
+fn expect_number(value: &Value) -> EvaluationResult<i64> {
+    match value {
+        Value::Number(n) => Ok(*n),
+        other => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn min(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.is_empty() {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 1,
+            realized: 0,
+        });
+    }
+    args.iter()
+        .try_fold(i64::MAX, |acc, x| Ok(acc.min(expect_number(x)?)))
+        .map(Value::Number)
+}
+
+fn max(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.is_empty() {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 1,
+            realized: 0,
+        });
+    }
+    args.iter()
+        .try_fold(i64::MIN, |acc, x| Ok(acc.max(expect_number(x)?)))
+        .map(Value::Number)
+}
+
+// `(sum coll)` adds the numbers in any seqable `coll`
+fn sum(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    SeqIter::new(&args[0])?
+        .try_fold(0_i64, |acc, x| {
+            let n = expect_number(&x)?;
+            acc.checked_add(n).ok_or(EvaluationError::Overflow(acc, n))
+        })
+        .map(Value::Number)
+}
+
+// `(clamp x lower upper)` bounds `x` to be within `lower` and `upper`, inclusive
+fn clamp(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    let x = expect_number(&args[0])?;
+    let lower = expect_number(&args[1])?;
+    let upper = expect_number(&args[2])?;
+    if lower > upper {
+        return Err(EvaluationError::InvalidRange(lower, upper));
+    }
+    Ok(Value::Number(x.clamp(lower, upper)))
+}
+
+fn pr(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "pr", args);
+    let result = print_args(interpreter, args, PrintMode::Readable)?;
     print!("{}", result);
     io::stdout().flush().unwrap();
     Ok(Value::Nil)
 }
 
-fn prn(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    let result = args.iter().map(|arg| arg.to_readable_string()).join(" ");
+fn prn(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "prn", args);
+    let result = print_args(interpreter, args, PrintMode::Readable)?;
     println!("{}", result);
     Ok(Value::Nil)
 }
 
-fn pr_str(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    let result = args.iter().map(|arg| arg.to_readable_string()).join(" ");
-    Ok(Value::String(result))
+fn pr_str(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let result = print_args(interpreter, args, PrintMode::Readable)?;
+    Ok(Value::String(result.into()))
 }
 
-fn print_(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    print!("{}", args.iter().format(" "));
+fn print_(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "print", args);
+    let result = print_args(interpreter, args, PrintMode::Display)?;
+    print!("{}", result);
     io::stdout().flush().unwrap();
     Ok(Value::Nil)
 }
 
-fn println(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    println!("{}", args.iter().format(" "));
+fn println(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "println", args);
+    let result = print_args(interpreter, args, PrintMode::Display)?;
+    println!("{}", result);
     Ok(Value::Nil)
 }
 
-fn print_str(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    let mut result = String::new();
-    write!(&mut result, "{}", args.iter().format(" ")).expect("can write to string");
-    Ok(Value::String(result))
+fn print_str(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let result = print_args(interpreter, args, PrintMode::Display)?;
+    Ok(Value::String(result.into()))
+}
+
+fn add_print_method(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let (predicate, printer) = (&args[0], &args[1]);
+    // registering a printer for the same predicate again replaces the earlier one
+    match interpreter
+        .print_methods
+        .iter_mut()
+        .find(|(existing, _)| existing == predicate)
+    {
+        Some((_, existing)) => *existing = printer.clone(),
+        None => interpreter
+            .print_methods
+            .push((predicate.clone(), printer.clone())),
+    }
+    Ok(Value::Nil)
+}
+
+fn expect_tag(value: &Value) -> EvaluationResult<&Value> {
+    match value {
+        Value::Keyword(..) | Value::Symbol(..) => Ok(value),
+        other => Err(EvaluationError::WrongType {
+            expected: "Keyword, Symbol",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn expect_tags(args: &[Value]) -> EvaluationResult<(Value, Value)> {
+    match args {
+        [child, parent] => Ok((expect_tag(child)?.clone(), expect_tag(parent)?.clone())),
+        _ => Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+// `(derive! child parent)` records that the tag `child` is a `parent`
+fn derive(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let (child, parent) = expect_tags(args)?;
+    if !interpreter.hierarchy.derive(child.clone(), parent.clone()) {
+        return Err(EvaluationError::CyclicDerivation(
+            Box::new(child),
+            Box::new(parent),
+        ));
+    }
+    Ok(Value::Nil)
+}
+
+fn underive(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let (child, parent) = expect_tags(args)?;
+    interpreter.hierarchy.underive(&child, &parent);
+    Ok(Value::Nil)
+}
+
+// `(isa? child parent)` where vectors match element-wise, e.g. `(isa? [::square ::red] [::shape ::color])`
+fn isa(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Bool(interpreter.hierarchy.isa(&args[0], &args[1])))
+}
+
+// applies one of the `Hierarchy` queries to the single tag in `args`, returning nil for no tags
+fn query_hierarchy(
+    interpreter: &Interpreter,
+    args: &[Value],
+    query: fn(&Hierarchy, &Value) -> PersistentSet<Value>,
+) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let tags = query(&interpreter.hierarchy, &args[0]);
+    if tags.is_empty() {
+        return Ok(Value::Nil);
+    }
+    Ok(Value::Set(tags))
+}
+
+fn parents(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    query_hierarchy(interpreter, args, Hierarchy::parents)
+}
+
+fn ancestors(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    query_hierarchy(interpreter, args, Hierarchy::ancestors)
+}
+
+fn descendants(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    query_hierarchy(interpreter, args, Hierarchy::descendants)
 }
 
 fn list(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     Ok(list_with_values(args.iter().cloned()))
 }
 
+fn queue(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    Ok(Value::Queue(args.iter().cloned().collect()))
+}
+
 fn is_list(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
@@ -281,13 +806,31 @@ fn is_empty(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         Value::Vector(elems) => Ok(Value::Bool(elems.is_empty())),
         Value::Map(elems) => Ok(Value::Bool(elems.is_empty())),
         Value::Set(elems) => Ok(Value::Bool(elems.is_empty())),
+        Value::Queue(elems) => Ok(Value::Bool(elems.is_empty())),
         other => Err(EvaluationError::WrongType {
-            expected: "Nil, String, List, Vector, Map, Set",
+            expected: "Nil, String, List, Vector, Map, Set, Queue",
             realized: other.clone(),
         }),
     }
 }
 
+fn empty(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::List(..) => Ok(Value::List(PersistentList::new())),
+        Value::Vector(..) => Ok(Value::Vector(PersistentVector::new())),
+        Value::Map(..) => Ok(Value::Map(PersistentMap::new())),
+        Value::Set(..) => Ok(Value::Set(PersistentSet::new())),
+        Value::Queue(..) => Ok(Value::Queue(PersistentQueue::new())),
+        _ => Ok(Value::Nil),
+    }
+}
+
 fn count(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
@@ -297,13 +840,15 @@ fn count(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
     match &args[0] {
         Value::Nil => Ok(Value::Number(0)),
-        Value::String(s) => Ok(Value::Number(s.len() as i64)),
+        Value::String(s) => Ok(Value::Number(s.chars().count() as i64)),
         Value::List(elems) => Ok(Value::Number(elems.len() as i64)),
         Value::Vector(elems) => Ok(Value::Number(elems.len() as i64)),
         Value::Map(elems) => Ok(Value::Number(elems.size() as i64)),
         Value::Set(elems) => Ok(Value::Number(elems.size() as i64)),
+        Value::Queue(elems) => Ok(Value::Number(elems.len() as i64)),
+        Value::Bytes(bytes) => Ok(Value::Number(bytes.len() as i64)),
         other => Err(EvaluationError::WrongType {
-            expected: "Nil, String, List, Vector, Map, Set",
+            expected: "Nil, String, List, Vector, Map, Set, Queue, Bytes",
             realized: other.clone(),
         }),
     }
@@ -339,6 +884,7 @@ comparator!(less, <);
 comparator!(less_eq, <=);
 comparator!(greater, >);
 comparator!(greater_eq, >=);
+comparator!(numeric_equal, ==);
 
 fn equal(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 2 {
@@ -350,33 +896,240 @@ fn equal(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     Ok(Value::Bool(args[0] == args[1]))
 }
 
-fn read_string(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(read-string s)` or `(read-string s opts)` where `opts` is a map supporting
+// `:all` to return a list of every form, `:eof` for the value returned when `s`
+// holds no forms, `:readers` mapping tags to fns reading tagged literals and
+// `:intern` to share repeated data as `intern-data` does
+fn read_string(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let opts = match args.len() {
+        1 => PersistentMap::new(),
+        2 => match &args[1] {
+            Value::Map(opts) => opts.clone(),
+            Value::Nil => PersistentMap::new(),
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "Map, Nil",
+                    realized: other.clone(),
+                })
+            }
+        },
+        _ => {
+            return Err(EvaluationError::WrongArity {
+                expected: 2,
+                realized: args.len(),
+            })
+        }
+    };
+    let s = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
+        }
+    };
+    let option = |name: &str| opts.get(&Value::Keyword(name.to_string(), None));
+    let readers = match option("readers") {
+        Some(Value::Map(readers)) => readers.clone(),
+        None | Some(Value::Nil) => PersistentMap::new(),
+        Some(other) => {
+            return Err(EvaluationError::WrongType {
+                expected: "Map",
+                realized: other.clone(),
+            })
+        }
+    };
+
+    // an error raised by a reader fn stops reading and is surfaced in place of the reader's error
+    let mut reader_error = None;
+    let options = interpreter.current_read_options();
+    let mut tag_reader = |tag: &str, form: Value| {
+        let reader = readers.get(&Value::Symbol(tag.to_string(), None))?;
+        match apply_callable(interpreter, reader, std::slice::from_ref(&form)) {
+            Ok(value) => Some(Ok(value)),
+            Err(err) => {
+                reader_error = Some(err);
+                Some(Err(form))
+            }
+        }
+    };
+    let result = read_with_tag_reader(s, &options, &mut tag_reader);
+    if let Some(err) = reader_error {
+        return Err(err);
+    }
+    let mut forms = result.map_err(|err| {
+        let context = err.context(s);
+        EvaluationError::ReaderError(Box::new(err), context.to_string())
+    })?;
+    if option("intern").is_some_and(Value::is_truthy) {
+        let mut interner = DataInterner::default();
+        forms = forms.iter().map(|form| interner.intern(form)).collect();
+    }
+
+    if option("all").is_some_and(Value::is_truthy) {
+        return Ok(list_with_values(forms));
+    }
+    match forms.pop() {
+        Some(form) => Ok(form),
+        None => Ok(option("eof").cloned().unwrap_or(Value::Nil)),
+    }
+}
+
+// collections with at most this many elements, each a scalar, are shared by `intern-data`
+const INTERNED_COLLECTION_LIMIT: usize = 8;
+
+// `(intern-data v)` rebuilds `v` so that equal strings, and equal small collections of
+// scalars like `[x y]` pairs, share one instance, e.g. to cut the memory held by data read
+// from large files. Keywords and symbols store their names inline so are not shared.
+fn intern_data(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::String(s) => {
-            let mut forms = read(s).map_err(|err| {
-                let context = err.context(s);
-                EvaluationError::ReaderError(err, context.to_string())
+    Ok(DataInterner::default().intern(&args[0]))
+}
+
+#[derive(Default)]
+struct DataInterner {
+    // only holds strings and collections of scalars, whose hashes never change
+    shared: HashSet<Value>,
+}
+
+impl DataInterner {
+    fn intern(&mut self, value: &Value) -> Value {
+        let mut intern = |elem: &Value| self.intern(elem);
+        let rebuilt = match value {
+            Value::String(_) => value.clone(),
+            Value::List(elems) => list_with_values(elems.iter().map(&mut intern)),
+            Value::Vector(elems) => vector_with_values(elems.iter().map(&mut intern)),
+            Value::Map(elems) => map_with_values(elems.iter().map(|(k, v)| (intern(k), intern(v)))),
+            Value::Set(elems) => set_with_values(elems.iter().map(&mut intern)),
+            Value::Queue(elems) => Value::Queue(elems.iter().map(&mut intern).collect()),
+            _ => return value.clone(),
+        };
+        if !is_shareable(&rebuilt) {
+            return rebuilt;
+        }
+        // lists and vectors with equal elements are equal, so also match the kind of collection
+        match self.shared.get(&rebuilt) {
+            Some(existing) if discriminant(existing) == discriminant(&rebuilt) => existing.clone(),
+            _ => {
+                self.shared.insert(rebuilt.clone());
+                rebuilt
+            }
+        }
+    }
+}
+
+fn is_shareable(value: &Value) -> bool {
+    let is_scalar = |value: &Value| {
+        matches!(
+            value,
+            Value::Nil
+                | Value::Bool(_)
+                | Value::Number(_)
+                | Value::String(_)
+                | Value::Keyword(..)
+                | Value::Symbol(..)
+        )
+    };
+    let is_small = |len: usize| len <= INTERNED_COLLECTION_LIMIT;
+    match value {
+        Value::String(_) => true,
+        Value::List(elems) => is_small(elems.len()) && elems.iter().all(is_scalar),
+        Value::Vector(elems) => is_small(elems.len()) && elems.iter().all(is_scalar),
+        Value::Map(elems) => {
+            is_small(elems.size()) && elems.iter().all(|(k, v)| is_scalar(k) && is_scalar(v))
+        }
+        Value::Set(elems) => is_small(elems.size()) && elems.iter().all(is_scalar),
+        Value::Queue(elems) => is_small(elems.len()) && elems.iter().all(is_scalar),
+        _ => false,
+    }
+}
+
+// the index just past the `}` closing a `~{` that ends just before `start`, skipping braces in strings
+fn template_expression_end(template: &str, start: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut chars = template[start..].char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// `(template-parts "Hello ~{name}!")` splits a template into its literal strings and the form
+// read from each `~{...}`, e.g. `("Hello " name "!")`; `~~` is a literal `~`. Used by `<<`.
+fn template_parts(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let template = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
+        }
+    };
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut index = 0;
+    while let Some(ch) = template[index..].chars().next() {
+        let rest = &template[index..];
+        if rest.starts_with("~~") {
+            literal.push('~');
+            index += 2;
+        } else if rest.starts_with("~{") {
+            let start = index + 2;
+            let end = template_expression_end(template, start)
+                .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), "a template"))?;
+            let source = &template[start..end - 1];
+            let mut forms = interpreter.read(source).map_err(|err| {
+                let context = err.context(source);
+                EvaluationError::ReaderError(Box::new(err), context.to_string())
             })?;
-            if forms.is_empty() {
-                Ok(Value::Nil)
-            } else {
-                Ok(forms.pop().unwrap())
+            if forms.len() != 1 {
+                return Err(EvaluationError::CannotDecode(args[0].clone(), "a template"));
             }
+            if !literal.is_empty() {
+                parts.push(Value::String(std::mem::take(&mut literal).into()));
+            }
+            parts.extend(forms.pop());
+            index = end;
+        } else {
+            literal.push(ch);
+            index += ch.len_utf8();
         }
-        other => Err(EvaluationError::WrongType {
-            expected: "String",
-            realized: other.clone(),
-        }),
     }
+    if !literal.is_empty() {
+        parts.push(Value::String(literal.into()));
+    }
+    Ok(list_with_values(parts))
 }
 
-fn spit(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn spit(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "spit", args);
     if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
             expected: 2,
@@ -387,7 +1140,7 @@ fn spit(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         Value::String(path) => {
             let mut contents = String::new();
             let _ = write!(&mut contents, "{}", &args[1]);
-            let _ = fs::write(path, contents).map_err(|err| -> InterpreterError { err.into() })?;
+            fs::write(&**path, contents).map_err(|err| -> InterpreterError { err.into() })?;
             Ok(Value::Nil)
         }
         other => Err(EvaluationError::WrongType {
@@ -397,208 +1150,254 @@ fn spit(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
 }
 
-fn slurp(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// the number of lines `read-lines` reads at a time unless another is requested
+const READ_LINES_CHUNK_SIZE: usize = 1024;
+
+// `(reader path)` opens the file at `path` to read a line at a time with `read-line`,
+// `read-lines` or `line-seq`, returning a handle to close with `close!`, e.g. as a binding of `with-open`
+fn reader(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "reader", args);
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::String(path) => {
-            let contents =
-                fs::read_to_string(path).map_err(|err| -> InterpreterError { err.into() })?;
-            Ok(Value::String(contents))
+    let path = match &args[0] {
+        Value::String(path) => path,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
         }
-        other => Err(EvaluationError::WrongType {
-            expected: "String",
-            realized: other.clone(),
+    };
+    let file = fs::File::open(&**path).map_err(|err| -> InterpreterError { err.into() })?;
+    let stream = Stream::Reader(BufReader::new(file));
+    Ok(stream_handle(interpreter, path, stream))
+}
+
+// `(writer path)` or `(writer path {:append true})` opens the file at `path` to write to
+// with `write-line`, truncating it unless appending, and returns a handle like `reader`
+fn writer(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "writer", args);
+    let (path, append) = match args {
+        [Value::String(path)] => (path, false),
+        [Value::String(path), Value::Map(opts)] => {
+            let append = opts
+                .get(&Value::Keyword("append".to_string(), None))
+                .is_some_and(Value::is_truthy);
+            (path, append)
+        }
+        [Value::String(_), other] | [other, ..] if args.len() <= 2 => {
+            return Err(EvaluationError::WrongType {
+                expected: "String, Map",
+                realized: other.clone(),
+            })
+        }
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: args.len(),
+            })
+        }
+    };
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(append)
+        .write(true)
+        .truncate(!append)
+        .open(&**path)
+        .map_err(|err| -> InterpreterError { err.into() })?;
+    let stream = Stream::Writer(BufWriter::new(file));
+    Ok(stream_handle(interpreter, path, stream))
+}
+
+fn stream_handle(interpreter: &mut Interpreter, path: &str, stream: Stream) -> Value {
+    let id = interpreter.streams.open(stream);
+    let keyword = |name: &str| Value::Keyword(name.to_string(), None);
+    map_with_values([
+        (keyword("stream"), Value::Number(id)),
+        (keyword("path"), Value::String(path.into())),
+        (keyword("close"), Value::Primitive(close_stream)),
+    ])
+}
+
+// the stream behind a handle returned by `reader` or `writer`
+fn open_stream<'a>(
+    interpreter: &'a mut Interpreter,
+    handle: &Value,
+) -> EvaluationResult<&'a mut Stream> {
+    let field = |name: &str| handle.get_path(&[name]);
+    match (field("stream"), field("path")) {
+        (Some(Value::Number(id)), Some(Value::String(path))) => {
+            interpreter.streams.get_mut(*id).ok_or_else(|| {
+                EvaluationError::Interpreter(InterpreterError::ClosedStream(path.to_string()))
+            })
+        }
+        _ => Err(EvaluationError::WrongType {
+            expected: "Map with :stream",
+            realized: handle.clone(),
         }),
     }
 }
 
-fn eval(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn open_reader<'a>(
+    interpreter: &'a mut Interpreter,
+    handle: &Value,
+) -> EvaluationResult<&'a mut BufReader<fs::File>> {
+    match open_stream(interpreter, handle)? {
+        Stream::Reader(reader) => Ok(reader),
+        Stream::Writer(_) => Err(EvaluationError::WrongType {
+            expected: "reader",
+            realized: handle.clone(),
+        }),
+    }
+}
+
+// the `:close` fn of stream handles
+fn close_stream(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-
-    interpreter.evaluate_in_global_scope(&args[0])
+    if let Some(Value::Number(id)) = args[0].get_path(&["stream"]) {
+        if let Some(Stream::Writer(mut writer)) = interpreter.streams.close(*id) {
+            writer
+                .flush()
+                .map_err(|err| -> InterpreterError { err.into() })?;
+        }
+    }
+    Ok(Value::Nil)
 }
 
-fn to_str(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() == 1 && matches!(&args[0], Value::Nil) {
-        return Ok(Value::String("".to_string()));
+// the next line of `reader` without its line terminator, or `None` at the end of the file
+fn next_line(reader: &mut BufReader<fs::File>) -> EvaluationResult<Option<String>> {
+    let mut line = String::new();
+    let read = reader
+        .read_line(&mut line)
+        .map_err(|err| -> InterpreterError { err.into() })?;
+    if read == 0 {
+        return Ok(None);
     }
-    let mut result = String::new();
-    for arg in args {
-        match arg {
-            Value::String(s) => {
-                write!(result, "{}", s).expect("can write to string");
-            }
-            _ => write!(result, "{}", arg.to_readable_string()).expect("can write to string"),
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
         }
     }
-    Ok(Value::String(result))
+    Ok(Some(line))
 }
 
-fn to_atom(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(read-line rdr)` is the next line of a `reader`, or `nil` once every line has been read
+fn read_line(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    Ok(atom_with_value(args[0].clone()))
+    let reader = open_reader(interpreter, &args[0])?;
+    Ok(next_line(reader)?
+        .map(|line| Value::String(line.into()))
+        .unwrap_or(Value::Nil))
 }
 
-fn is_atom(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(line-seq rdr)` lists every remaining line of a `reader`, or is `nil` if none remain
+fn line_seq(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    match args[0] {
-        Value::Atom(_) => Ok(Value::Bool(true)),
-        _ => Ok(Value::Bool(false)),
+    let reader = open_reader(interpreter, &args[0])?;
+    let mut lines = vec![];
+    while let Some(line) = next_line(reader)? {
+        lines.push(Value::String(line.into()));
     }
+    if lines.is_empty() {
+        return Ok(Value::Nil);
+    }
+    Ok(list_with_values(lines))
 }
 
-fn deref(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 1 {
-        return Err(EvaluationError::WrongArity {
-            expected: 1,
-            realized: args.len(),
-        });
+// `(read-lines rdr)` or `(read-lines rdr n)` lists the next `n` lines of a `reader`, 1024
+// unless given, or is `nil` once every line has been read. Calling it until `nil`
+// processes a file of any size a chunk at a time rather than holding all of it.
+fn read_lines(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let chunk_size = match args {
+        [_] => READ_LINES_CHUNK_SIZE,
+        [_, n] => count_arg(n)?.max(1),
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: args.len(),
+            })
+        }
+    };
+    let reader = open_reader(interpreter, &args[0])?;
+    let mut lines = vec![];
+    while lines.len() < chunk_size {
+        match next_line(reader)? {
+            Some(line) => lines.push(Value::String(line.into())),
+            None => break,
+        }
     }
-    match &args[0] {
-        Value::Atom(inner) => Ok(atom_impl_into_inner(inner)),
-        Value::Var(var) => var_impl_into_inner(var)
-            .ok_or_else(|| EvaluationError::CannotDerefUnboundVar(Value::Var(var.clone()))),
-        other => Err(EvaluationError::WrongType {
-            expected: "Atom, Var",
-            realized: other.clone(),
-        }),
+    if lines.is_empty() {
+        return Ok(Value::Nil);
     }
+    Ok(list_with_values(lines))
 }
 
-fn reset_atom(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(write-line w x)` writes `x`, printed as by `str`, and a newline with a `writer`
+fn write_line(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
             expected: 2,
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::Atom(inner) => {
-            let value = args[1].clone();
-            *inner.borrow_mut() = value.clone();
-            Ok(value)
+    let writer = match open_stream(interpreter, &args[0])? {
+        Stream::Writer(writer) => writer,
+        Stream::Reader(_) => {
+            return Err(EvaluationError::WrongType {
+                expected: "writer",
+                realized: args[0].clone(),
+            })
         }
-        other => Err(EvaluationError::WrongType {
-            expected: "Atom",
-            realized: other.clone(),
-        }),
-    }
+    };
+    writeln!(writer, "{}", args[1]).map_err(|err| -> InterpreterError { err.into() })?;
+    Ok(Value::Nil)
 }
 
-fn swap_atom(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() < 2 {
+fn slurp(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "slurp", args);
+    if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
-            expected: 2,
+            expected: 1,
             realized: args.len(),
         });
     }
     match &args[0] {
-        Value::Atom(cell) => match &args[1] {
-            Value::Fn(f) => {
-                let mut inner = cell.borrow_mut();
-                let original_value = inner.clone();
-                let mut fn_args = vec![original_value];
-                fn_args.extend_from_slice(&args[2..]);
-                let new_value = interpreter.apply_fn_inner(f, &fn_args, fn_args.len())?;
-                *inner = new_value.clone();
-                Ok(new_value)
-            }
-            Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
-                interpreter.extend_from_captures(captures)?;
-                let mut inner = cell.borrow_mut();
-                let original_value = inner.clone();
-                let mut fn_args = vec![original_value];
-                fn_args.extend_from_slice(&args[2..]);
-                let new_value = interpreter.apply_fn_inner(f, &fn_args, fn_args.len());
-                interpreter.leave_scope();
-
-                let new_value = new_value?;
-                *inner = new_value.clone();
-                Ok(new_value)
-            }
-            Value::Primitive(native_fn) => {
-                let mut inner = cell.borrow_mut();
-                let original_value = inner.clone();
-                let mut fn_args = vec![original_value];
-                fn_args.extend_from_slice(&args[2..]);
-                let new_value = native_fn(interpreter, &fn_args)?;
-                *inner = new_value.clone();
-                Ok(new_value)
-            }
-            other => Err(EvaluationError::WrongType {
-                expected: "Fn, FnWithCaptures, Primitive",
-                realized: other.clone(),
-            }),
-        },
-        other => Err(EvaluationError::WrongType {
-            expected: "Atom",
-            realized: other.clone(),
-        }),
-    }
-}
-
-fn cons(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 2 {
-        return Err(EvaluationError::WrongArity {
-            expected: 2,
-            realized: args.len(),
-        });
-    }
-    match &args[1] {
-        Value::List(seq) => Ok(Value::List(seq.push_front(args[0].clone()))),
-        Value::Vector(seq) => {
-            let mut inner = PersistentList::new();
-            for elem in seq.iter().rev() {
-                inner.push_front_mut(elem.clone());
-            }
-            inner.push_front_mut(args[0].clone());
-            Ok(Value::List(inner))
+        Value::String(path) => {
+            let contents =
+                fs::read_to_string(&**path).map_err(|err| -> InterpreterError { err.into() })?;
+            Ok(Value::String(contents.into()))
         }
         other => Err(EvaluationError::WrongType {
-            expected: "List, Vector",
+            expected: "String",
             realized: other.clone(),
         }),
     }
 }
 
-fn concat(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    let mut elems = vec![];
-    for arg in args {
-        match arg {
-            Value::List(seq) => elems.extend(seq.iter().cloned()),
-            Value::Vector(seq) => elems.extend(seq.iter().cloned()),
-            other => {
-                return Err(EvaluationError::WrongType {
-                    expected: "List, Vector",
-                    realized: other.clone(),
-                });
-            }
-        }
-    }
-    Ok(list_with_values(elems))
-}
-
-fn vec(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn load_file(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "load-file", args);
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
@@ -606,51 +1405,43 @@ fn vec(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         });
     }
     match &args[0] {
-        Value::List(elems) => Ok(vector_with_values(elems.iter().cloned())),
-        Value::Vector(elems) => Ok(vector_with_values(elems.iter().cloned())),
-        Value::Nil => Ok(vector_with_values([].iter().cloned())),
+        Value::String(path) => {
+            interpreter.evaluate_file(&**path)?;
+            Ok(Value::Nil)
+        }
         other => Err(EvaluationError::WrongType {
-            expected: "List, Vector, Nil",
+            expected: "String",
             realized: other.clone(),
         }),
     }
 }
 
-fn nth(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn spit_bytes(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "spit-bytes", args);
     if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
             expected: 2,
             realized: args.len(),
         });
     }
-    match &args[1] {
-        Value::Number(index) if *index >= 0 => {
-            let index = *index as usize;
-            match &args[0] {
-                Value::List(seq) => seq
-                    .iter()
-                    .nth(index)
-                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, seq.len()))
-                    .map(|elem| elem.clone()),
-                Value::Vector(seq) => seq
-                    .iter()
-                    .nth(index)
-                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, seq.len()))
-                    .map(|elem| elem.clone()),
-                other => Err(EvaluationError::WrongType {
-                    expected: "List, Vector",
-                    realized: other.clone(),
-                }),
-            }
+    match (&args[0], &args[1]) {
+        (Value::String(path), Value::Bytes(bytes)) => {
+            fs::write(&**path, bytes).map_err(|err| -> InterpreterError { err.into() })?;
+            Ok(Value::Nil)
         }
-        other => Err(EvaluationError::WrongType {
-            expected: "Number",
+        (Value::String(_), other) => Err(EvaluationError::WrongType {
+            expected: "Bytes",
+            realized: other.clone(),
+        }),
+        (other, _) => Err(EvaluationError::WrongType {
+            expected: "String",
             realized: other.clone(),
         }),
     }
 }
 
-fn first(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn slurp_bytes(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "slurp-bytes", args);
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
@@ -658,67 +1449,117 @@ fn first(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         });
     }
     match &args[0] {
-        Value::List(elems) => {
-            if let Some(first) = elems.first() {
-                Ok(first.clone())
-            } else {
-                Ok(Value::Nil)
-            }
-        }
-        Value::Vector(elems) => {
-            if let Some(first) = elems.first() {
-                Ok(first.clone())
-            } else {
-                Ok(Value::Nil)
-            }
+        Value::String(path) => {
+            let contents = fs::read(&**path).map_err(|err| -> InterpreterError { err.into() })?;
+            Ok(Value::Bytes(contents.into()))
         }
-        Value::Nil => Ok(Value::Nil),
         other => Err(EvaluationError::WrongType {
-            expected: "List, Vector, Nil",
+            expected: "String",
             realized: other.clone(),
         }),
     }
 }
 
-fn rest(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn eval(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::List(elems) => {
-            if let Some(rest) = elems.drop_first() {
-                Ok(Value::List(rest))
-            } else {
-                Ok(Value::List(PersistentList::new()))
-            }
+
+    interpreter.evaluate_in_global_scope(&args[0])
+}
+
+// `(subs s start)` or `(subs s start end)` is the part of `s` from the char at `start` up to,
+// but excluding, the char at `end`, defaulting to the end of `s`; indices count chars, not bytes
+fn subs(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let (s, start, end) = match args {
+        [Value::String(s), start] => (s, start, None),
+        [Value::String(s), start, end] => (s, start, Some(end)),
+        [other, _] | [other, _, _] => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
         }
-        Value::Vector(elems) => {
-            let mut result = PersistentList::new();
-            for elem in elems.iter().skip(1).rev() {
-                result.push_front_mut(elem.clone())
-            }
-            Ok(Value::List(result))
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 2,
+                max: 3,
+                realized: args.len(),
+            })
         }
-        Value::Nil => Ok(Value::List(PersistentList::new())),
+    };
+    let len = s.chars().count();
+    let index = |n: &Value| match n {
+        Value::Number(n) if *n >= 0 && *n as usize <= len => Ok(*n as usize),
+        Value::Number(n) if *n >= 0 => Err(EvaluationError::IndexOutOfBounds(*n as usize, len)),
         other => Err(EvaluationError::WrongType {
-            expected: "List, Vector, Nil",
+            expected: "Number",
             realized: other.clone(),
         }),
+    };
+    let start = index(start)?;
+    let end = end.map(index).transpose()?.unwrap_or(len);
+    if start > end {
+        return Err(EvaluationError::InvalidRange(start as i64, end as i64));
     }
+    let offset = |index: usize| {
+        s.char_indices()
+            .nth(index)
+            .map(|(offset, _)| offset)
+            .unwrap_or(s.len())
+    };
+    Ok(Value::String(s[offset(start)..offset(end)].into()))
 }
 
-fn ex_info(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 2 {
-        return Err(EvaluationError::WrongArity {
-            expected: 2,
+fn to_str(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [Value::Nil] => return Ok(Value::String("".into())),
+        // shares the existing string rather than copying it
+        [s @ Value::String(_)] => return Ok(s.clone()),
+        _ => {}
+    }
+    let options = PrintOptions::new(interpreter, PrintMode::Readable);
+    let mut result = String::new();
+    for arg in args {
+        match arg {
+            Value::String(s) => result.push_str(s),
+            // only nested in a collection are these printed as tagged literals
+            Value::Uuid(_) | Value::Instant(_) => push_value(arg, PrintMode::Display, &mut result),
+            _ => print_value(interpreter, arg, &options, &mut result)?,
+        }
+    }
+    Ok(Value::String(result.into()))
+}
+
+fn radix_from_args(args: &[Value]) -> EvaluationResult<u32> {
+    match args.get(1) {
+        None => Ok(10),
+        Some(Value::Number(radix)) if (2..=36).contains(radix) => Ok(*radix as u32),
+        Some(Value::Number(radix)) => Err(EvaluationError::InvalidRadix(*radix)),
+        Some(other) => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// `(parse-long s)` or `(parse-long s radix)`, returning `nil` if `s` is not a number
+fn parse_long(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(1..=2).contains(&args.len()) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
             realized: args.len(),
         });
     }
+    let radix = radix_from_args(args)?;
     match &args[0] {
-        Value::String(msg) => Ok(Value::Exception(exception(msg, &args[1]))),
+        Value::String(s) => Ok(i64::from_str_radix(s, radix)
+            .map(Value::Number)
+            .unwrap_or(Value::Nil)),
         other => Err(EvaluationError::WrongType {
             expected: "String",
             realized: other.clone(),
@@ -726,170 +1567,1651 @@ fn ex_info(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
 }
 
-fn throw(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(number->string n)` or `(number->string n radix)`
+fn number_to_string(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(1..=2).contains(&args.len()) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let radix = radix_from_args(args)?;
+    let n = match &args[0] {
+        Value::Number(n) => *n,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Number",
+                realized: other.clone(),
+            })
+        }
+    };
+    let mut magnitude = n.unsigned_abs();
+    let mut digits = vec![];
+    loop {
+        let digit = (magnitude % radix as u64) as u32;
+        digits.push(std::char::from_digit(digit, radix).expect("digit is within radix"));
+        magnitude /= radix as u64;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        digits.push('-');
+    }
+    Ok(Value::String(
+        digits.iter().rev().collect::<String>().into(),
+    ))
+}
+
+fn to_atom(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
-    let exception =
-        match &args[0] {
-            n @ Value::Nil => exception("", n),
-            b @ Value::Bool(_) => exception("", b),
-            n @ Value::Number(_) => exception("", n),
-            s @ Value::String(_) => exception("", s),
-            k @ Value::Keyword(..) => exception("", k),
-            s @ Value::Symbol(..) => exception("", s),
-            coll @ Value::List(_) => exception("", coll),
-            coll @ Value::Vector(_) => exception("", coll),
-            coll @ Value::Map(_) => exception("", coll),
-            coll @ Value::Set(_) => exception("", coll),
-            Value::Exception(e) => e.clone(),
-            other => return Err(EvaluationError::WrongType {
-                expected:
-                    "Nil, Bool, Number, String, Keyword, Symbol, List, Vector, Map, Set, Exception",
-                realized: other.clone(),
-            }),
-        };
-    Err(EvaluationError::Exception(exception))
+    Ok(atom_with_value(args[0].clone()))
 }
 
-fn apply(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() < 2 {
+fn is_atom(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
-            expected: 2,
+            expected: 1,
             realized: args.len(),
         });
     }
-    let (last, prefix) = args.split_last().expect("has enough elements");
-    let (first, middle) = prefix.split_first().expect("has enough elements");
-    let fn_args = match last {
-        Value::List(elems) => {
-            let mut fn_args = Vec::with_capacity(middle.len() + elems.len());
-            for elem in middle.iter().chain(elems.iter()) {
-                fn_args.push(elem.clone())
-            }
-            fn_args
-        }
-        Value::Vector(elems) => {
-            let mut fn_args = Vec::with_capacity(middle.len() + elems.len());
-            for elem in middle.iter().chain(elems.iter()) {
-                fn_args.push(elem.clone())
-            }
-            fn_args
-        }
+    match args[0] {
+        Value::Atom(_) => Ok(Value::Bool(true)),
+        _ => Ok(Value::Bool(false)),
+    }
+}
+
+// `(weak-ref target)` refers to the atom or var `target` without keeping it alive;
+// deref'ing the result gives `target`, or `nil` once nothing else refers to it
+fn weak_ref(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    WeakRefImpl::new(&args[0])
+        .map(Value::WeakRef)
+        .ok_or_else(|| EvaluationError::WrongType {
+            expected: "Atom, Var",
+            realized: args[0].clone(),
+        })
+}
+
+// `(file-atom path)` or `(file-atom path init)` returns an atom holding the value
+// last persisted to `path`, or `init` (default `nil`) if there is no such file. Its value
+// is written to `path` in readable form each time it is changed.
+fn file_atom(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "file-atom", args);
+    if !(1..=2).contains(&args.len()) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let path = match &args[0] {
+        Value::String(path) => PathBuf::from(&**path),
         other => {
             return Err(EvaluationError::WrongType {
-                expected: "List, Vector",
+                expected: "String",
                 realized: other.clone(),
             })
         }
     };
-    match first {
-        Value::Fn(f) => interpreter.apply_fn_inner(f, &fn_args, fn_args.len()),
-        Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
-            interpreter.extend_from_captures(captures)?;
-            let result = interpreter.apply_fn_inner(f, &fn_args, fn_args.len());
-            interpreter.leave_scope();
-            result
+    let value = match fs::read_to_string(&path) {
+        Ok(contents) => {
+            let mut forms = read(&contents).map_err(|err| {
+                let context = err.context(&contents);
+                EvaluationError::ReaderError(Box::new(err), context.to_string())
+            })?;
+            if forms.len() != 1 {
+                return Err(EvaluationError::WrongArity {
+                    expected: 1,
+                    realized: forms.len(),
+                });
+            }
+            forms.remove(0)
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let value = args.get(1).cloned().unwrap_or(Value::Nil);
+            write_atom_file(&path, &value)?;
+            value
         }
-        Value::Primitive(native_fn) => native_fn(interpreter, &fn_args),
+        Err(err) => return Err(InterpreterError::from(err).into()),
+    };
+    let atom = Rc::new(RefCell::new(value));
+    interpreter.file_atoms.push((Rc::downgrade(&atom), path));
+    Ok(Value::Atom(atom))
+}
+
+// replaces the file so that it is never left partially written
+fn write_atom_file(path: &Path, value: &Value) -> EvaluationResult<()> {
+    let mut staging = path.as_os_str().to_owned();
+    staging.push(".tmp");
+    fs::write(&staging, value.to_readable_string())
+        .and_then(|_| fs::rename(&staging, path))
+        .map_err(|err| InterpreterError::from(err).into())
+}
+
+// writes the value of `atom` to its file if it was made by `file-atom`
+fn persist_atom(interpreter: &mut Interpreter, atom: &Rc<RefCell<Value>>) -> EvaluationResult<()> {
+    interpreter
+        .file_atoms
+        .retain(|(weak, _)| weak.strong_count() > 0);
+    let path = interpreter
+        .file_atoms
+        .iter()
+        .find(|(weak, _)| weak.as_ptr() == Rc::as_ptr(atom))
+        .map(|(_, path)| path.clone());
+    match path {
+        Some(path) => write_atom_file(&path, &atom.borrow()),
+        None => Ok(()),
+    }
+}
+
+fn deref(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Atom(inner) => Ok(atom_impl_into_inner(inner)),
+        Value::Var(var) => var_impl_into_inner(var)
+            .ok_or_else(|| EvaluationError::CannotDerefUnboundVar(Value::Var(var.clone()))),
+        Value::WeakRef(weak) => Ok(weak.upgrade().unwrap_or(Value::Nil)),
+        Value::Reduced(inner) => Ok(*inner.clone()),
         other => Err(EvaluationError::WrongType {
-            expected: "Fn, FnWithCaptures, Primitive",
+            expected: "Atom, Var, WeakRef, Reduced",
             realized: other.clone(),
         }),
     }
 }
 
-fn map(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn reset_atom(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
             expected: 2,
             realized: args.len(),
         });
     }
-    let fn_args: Vec<_> = match &args[1] {
-        Value::Nil => return Ok(Value::List(PersistentList::new())),
-        Value::List(elems) => elems.iter().collect(),
-        Value::Vector(elems) => elems.iter().collect(),
-        other => {
-            return Err(EvaluationError::WrongType {
-                expected: "Nil, List, Vector",
-                realized: other.clone(),
-            })
+    match &args[0] {
+        Value::Atom(inner) => {
+            let value = args[1].clone();
+            *inner.borrow_mut() = value.clone();
+            persist_atom(interpreter, inner)?;
+            Ok(value)
         }
-    };
-    let mut result = Vec::with_capacity(fn_args.len());
+        other => Err(EvaluationError::WrongType {
+            expected: "Atom",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// `(compare-and-set! a expected new)` sets `a` to `new` only if its current value equals `expected`
+fn compare_and_set_atom(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
     match &args[0] {
-        Value::Fn(f) => {
-            for arg in fn_args {
-                let mapped_arg = interpreter.apply_fn_inner(f, [arg], 1)?;
-                result.push(mapped_arg);
+        Value::Atom(inner) => {
+            if *inner.borrow() != args[1] {
+                return Ok(Value::Bool(false));
             }
+            *inner.borrow_mut() = args[2].clone();
+            persist_atom(interpreter, inner)?;
+            Ok(Value::Bool(true))
         }
-        Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
-            interpreter.extend_from_captures(captures)?;
-            for arg in fn_args {
-                let mapped_arg = interpreter.apply_fn_inner(f, [arg], 1)?;
-                result.push(mapped_arg);
-            }
-            interpreter.leave_scope();
+        other => Err(EvaluationError::WrongType {
+            expected: "Atom",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn swap_atom(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() < 2 {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Atom(cell) => {
+            let new_value = match &args[1] {
+                Value::Fn(f) => {
+                    let mut inner = cell.borrow_mut();
+                    let original_value = inner.clone();
+                    let mut fn_args = vec![original_value];
+                    fn_args.extend_from_slice(&args[2..]);
+                    let new_value = interpreter.apply_fn_inner(f, &fn_args, fn_args.len())?;
+                    *inner = new_value.clone();
+                    Ok(new_value)
+                }
+                Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
+                    interpreter.extend_from_captures(captures)?;
+                    let mut inner = cell.borrow_mut();
+                    let original_value = inner.clone();
+                    let mut fn_args = vec![original_value];
+                    fn_args.extend_from_slice(&args[2..]);
+                    let new_value = interpreter.apply_fn_inner(f, &fn_args, fn_args.len());
+                    interpreter.leave_scope();
+
+                    let new_value = new_value?;
+                    *inner = new_value.clone();
+                    Ok(new_value)
+                }
+                Value::Primitive(native_fn) => {
+                    let mut inner = cell.borrow_mut();
+                    let original_value = inner.clone();
+                    let mut fn_args = vec![original_value];
+                    fn_args.extend_from_slice(&args[2..]);
+                    let new_value = interpreter.apply_primitive_to_args(*native_fn, &fn_args)?;
+                    *inner = new_value.clone();
+                    Ok(new_value)
+                }
+                other => Err(EvaluationError::WrongType {
+                    expected: "Fn, FnWithCaptures, Primitive",
+                    realized: other.clone(),
+                }),
+            }?;
+            persist_atom(interpreter, cell)?;
+            Ok(new_value)
         }
-        Value::Primitive(native_fn) => {
-            for arg in fn_args {
-                let mapped_arg = native_fn(interpreter, &[arg.clone()])?;
-                result.push(mapped_arg);
+        other => Err(EvaluationError::WrongType {
+            expected: "Atom",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn cons(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match args[1].without_meta() {
+        Value::List(seq) => Ok(Value::List(seq.push_front(args[0].clone()))),
+        Value::Vector(seq) => {
+            let mut inner = PersistentList::new();
+            for elem in seq.iter().rev() {
+                inner.push_front_mut(elem.clone());
             }
+            inner.push_front_mut(args[0].clone());
+            Ok(Value::List(inner))
         }
-        other => {
-            return Err(EvaluationError::WrongType {
-                expected: "Fn, FnWithCaptures, Primitive",
-                realized: other.clone(),
-            });
-        }
-    };
-    Ok(Value::List(result.into_iter().collect()))
+        Value::Queue(seq) => Ok(list_with_values(
+            iter::once(args[0].clone()).chain(seq.iter().cloned()),
+        )),
+        other => Err(EvaluationError::WrongType {
+            expected: "List, Vector, Queue",
+            realized: other.clone(),
+        }),
+    }
 }
 
-macro_rules! is_type {
-    ($name:ident, $($target_type:pat) ,*) => {
-         fn $name(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-            if args.len() != 1 {
-                return Err(EvaluationError::WrongArity {
-                    expected: 1,
-                    realized: args.len(),
+fn concat(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let mut elems = vec![];
+    for arg in args {
+        match arg {
+            Value::List(seq) => elems.extend(seq.iter().cloned()),
+            Value::Vector(seq) => elems.extend(seq.iter().cloned()),
+            Value::Queue(seq) => elems.extend(seq.iter().cloned()),
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "List, Vector, Queue",
+                    realized: other.clone(),
                 });
             }
+        }
+    }
+    Ok(list_with_values(elems))
+}
+
+fn vec(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::List(elems) => Ok(vector_with_values(elems.iter().cloned())),
+        Value::Vector(elems) => Ok(vector_with_values(elems.iter().cloned())),
+        Value::Queue(elems) => Ok(vector_with_values(elems.iter().cloned())),
+        Value::Nil => Ok(vector_with_values([].iter().cloned())),
+        other => Err(EvaluationError::WrongType {
+            expected: "List, Vector, Queue, Nil",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn nth(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match &args[1] {
+        Value::Number(index) if *index >= 0 => {
+            let index = *index as usize;
             match &args[0] {
-                $($target_type) |* => Ok(Value::Bool(true)),
-                _ => Ok(Value::Bool(false)),
+                Value::List(seq) => seq
+                    .iter()
+                    .nth(index)
+                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, seq.len()))
+                    .map(|elem| elem.clone()),
+                Value::Vector(seq) => seq
+                    .iter()
+                    .nth(index)
+                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, seq.len()))
+                    .map(|elem| elem.clone()),
+                Value::Queue(seq) => seq
+                    .iter()
+                    .nth(index)
+                    .cloned()
+                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, seq.len())),
+                Value::String(s) => s
+                    .chars()
+                    .nth(index)
+                    .map(char_string)
+                    .ok_or_else(|| EvaluationError::IndexOutOfBounds(index, s.chars().count())),
+                other => Err(EvaluationError::WrongType {
+                    expected: "List, Vector, Queue, String",
+                    realized: other.clone(),
+                }),
             }
         }
-    };
+        other => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
 }
 
-is_type!(is_nil, Value::Nil);
-is_type!(is_true, Value::Bool(true));
-is_type!(is_false, Value::Bool(false));
-is_type!(is_symbol, Value::Symbol(..));
-is_type!(is_keyword, Value::Keyword(..));
-is_type!(is_vector, Value::Vector(..));
-is_type!(is_sequential, Value::List(..), Value::Vector(..));
-is_type!(is_map, Value::Map(..));
-is_type!(is_set, Value::Set(..));
-is_type!(is_string, Value::String(..));
-is_type!(is_number, Value::Number(..));
-is_type!(
-    is_fn,
-    Value::Fn(..),
-    Value::FnWithCaptures(..),
-    Value::Primitive(..),
-    Value::Macro(..)
-);
-is_type!(is_macro, Value::Macro(..));
+fn first(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::List(elems) => {
+            if let Some(first) = elems.first() {
+                Ok(first.clone())
+            } else {
+                Ok(Value::Nil)
+            }
+        }
+        Value::Vector(elems) => {
+            if let Some(first) = elems.first() {
+                Ok(first.clone())
+            } else {
+                Ok(Value::Nil)
+            }
+        }
+        Value::Queue(elems) => Ok(elems.peek().cloned().unwrap_or(Value::Nil)),
+        Value::Nil => Ok(Value::Nil),
+        other => Err(EvaluationError::WrongType {
+            expected: "List, Vector, Queue, Nil",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn rest(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::List(elems) => {
+            if let Some(rest) = elems.drop_first() {
+                Ok(Value::List(rest))
+            } else {
+                Ok(Value::List(PersistentList::new()))
+            }
+        }
+        Value::Vector(elems) => {
+            let mut result = PersistentList::new();
+            for elem in elems.iter().skip(1).rev() {
+                result.push_front_mut(elem.clone())
+            }
+            Ok(Value::List(result))
+        }
+        Value::Queue(elems) => Ok(list_with_values(elems.iter().skip(1).cloned())),
+        Value::Nil => Ok(Value::List(PersistentList::new())),
+        other => Err(EvaluationError::WrongType {
+            expected: "List, Vector, Queue, Nil",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn ex_info(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 2 || args.len() == 3) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 2,
+            max: 3,
+            realized: args.len(),
+        });
+    }
+    let msg = match &args[0] {
+        Value::String(msg) => msg,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
+        }
+    };
+    match args.get(2) {
+        None | Some(Value::Nil) => Ok(Value::Exception(exception(msg, &args[1]))),
+        Some(Value::Exception(cause)) => {
+            Ok(Value::Exception(exception_with_cause(msg, &args[1], cause)))
+        }
+        Some(other) => Err(EvaluationError::WrongType {
+            expected: "Exception, Nil",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn ex_message(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Exception(e) => Ok(Value::String(e.message().into())),
+        _ => Ok(Value::Nil),
+    }
+}
+
+fn ex_data(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Exception(e) => Ok(e.data()),
+        _ => Ok(Value::Nil),
+    }
+}
+
+fn ex_cause(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Exception(e) => Ok(e.cause().map(Value::Exception).unwrap_or(Value::Nil)),
+        _ => Ok(Value::Nil),
+    }
+}
+
+// resources are maps holding a fn under `:close` which is called with the resource
+fn close(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let resource = &args[0];
+    let close_fn = match resource {
+        Value::Nil => return Ok(Value::Nil),
+        Value::Map(map) => map.get(&Value::Keyword("close".to_string(), None)),
+        _ => None,
+    };
+    match close_fn {
+        Some(f) => apply_callable(interpreter, f, std::slice::from_ref(resource)),
+        None => Err(EvaluationError::WrongType {
+            expected: "Nil, Map with :close",
+            realized: resource.clone(),
+        }),
+    }
+}
+
+// the events recorded so far as a vector of maps or `nil` if not auditing
+fn audit_log(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    if !interpreter.is_auditing() {
+        return Ok(Value::Nil);
+    }
+    Ok(vector_with_values(
+        interpreter.audit_log().iter().map(AuditEvent::to_value),
+    ))
+}
+
+fn completions(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(prefix) => Ok(list_with_values(
+            interpreter
+                .completions(prefix)
+                .into_iter()
+                .map(|symbol| Value::Symbol(symbol, None)),
+        )),
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// describes what `symbol` resolves to as a map with the keys `:name`, `:namespace`,
+// `:kind` and, for fns and macros, `:arity` and `:variadic`; `nil` if it does not resolve
+fn resolve_symbol(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let (identifier, ns_opt) = match &args[0] {
+        Value::Symbol(identifier, ns_opt) => (identifier, ns_opt.as_ref()),
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Symbol",
+                realized: other.clone(),
+            })
+        }
+    };
+    let keyword = |name: &str| Value::Keyword(name.to_string(), None);
+    let name = (keyword("name"), Value::String(identifier.as_str().into()));
+    if ns_opt.is_none() && SpecialForm::from_name(identifier).is_some() {
+        return Ok(map_with_values([
+            name,
+            (keyword("kind"), keyword("special-form")),
+        ]));
+    }
+    interpreter.load_deferred_source_for(identifier, ns_opt)?;
+    let var = match interpreter.resolve_symbol_to_var(identifier, ns_opt) {
+        Ok(Value::Var(var)) => var,
+        _ => return Ok(Value::Nil),
+    };
+    let mut entries = vec![
+        name,
+        (keyword("namespace"), Value::String(var.namespace().into())),
+    ];
+    let (kind, f) = match var_impl_into_inner(&var) {
+        None => ("unbound", None),
+        Some(Value::Fn(f)) | Some(Value::FnWithCaptures(FnWithCapturesImpl { f, .. })) => {
+            ("fn", Some(f))
+        }
+        Some(Value::Macro(f)) => ("macro", Some(f)),
+        Some(Value::Primitive(_)) => ("primitive", None),
+        Some(_) => ("value", None),
+    };
+    entries.push((keyword("kind"), keyword(kind)));
+    if let Some(f) = f {
+        entries.push((keyword("arity"), Value::Number(f.arity as i64)));
+        entries.push((keyword("variadic"), Value::Bool(f.variadic)));
+    }
+    Ok(map_with_values(entries))
+}
+
+fn require(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let namespace: &str = match &args[0] {
+        Value::Symbol(namespace, None) => namespace,
+        Value::String(namespace) => namespace,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Symbol, String",
+                realized: other.clone(),
+            })
+        }
+    };
+    interpreter.require(namespace)?;
+    Ok(Value::Nil)
+}
+
+// `(remove-ns! 'some.namespace)` drops the namespace and its vars, see `Interpreter::unload_namespace`
+fn remove_ns(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let namespace: &str = match &args[0] {
+        Value::Symbol(namespace, None) => namespace,
+        Value::String(namespace) => namespace,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Symbol, String",
+                realized: other.clone(),
+            })
+        }
+    };
+    interpreter.unload_namespace(namespace)?;
+    Ok(Value::Nil)
+}
+
+// `(ns-hooks {:on-load f :on-unload g})` has `f` called once `require` has loaded the current
+// namespace and `g` before `remove-ns!` removes it, replacing any hooks declared before
+fn ns_hooks(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let hooks = match &args[0] {
+        Value::Map(hooks) => hooks,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Map",
+                realized: other.clone(),
+            })
+        }
+    };
+    let hook = |name: &str| {
+        hooks
+            .get(&Value::Keyword(name.to_string(), None))
+            .filter(|hook| !matches!(hook, Value::Nil))
+            .cloned()
+    };
+    interpreter.set_namespace_hooks(NamespaceHooks {
+        on_load: hook("on-load"),
+        on_unload: hook("on-unload"),
+    });
+    Ok(Value::Nil)
+}
+
+// `(alias 'short 'some.namespace)` lets `short/name` refer to `some.namespace/name`
+// in the current namespace and, with `*print-aliases*` set, prints keywords as `::short/name`
+// which `read-string` reads back as `:some.namespace/name`
+fn alias(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [Value::Symbol(alias, None), Value::Symbol(namespace, None)] => {
+            interpreter.add_alias(alias, namespace)?;
+            Ok(Value::Nil)
+        }
+        [Value::Symbol(_, None), other] | [other, _] => Err(EvaluationError::WrongType {
+            expected: "Symbol",
+            realized: other.clone(),
+        }),
+        _ => Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+// `(ns-aliases)` maps each alias of the current namespace to the namespace it stands for
+fn ns_aliases(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    Ok(map_with_values(
+        interpreter
+            .current_namespace_aliases()
+            .iter()
+            .map(|(alias, namespace)| {
+                (
+                    Value::Symbol(alias.clone(), None),
+                    Value::Symbol(namespace.clone(), None),
+                )
+            }),
+    ))
+}
+
+fn throw(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let exception =
+        match &args[0] {
+            n @ Value::Nil => exception("", n),
+            b @ Value::Bool(_) => exception("", b),
+            n @ Value::Number(_) => exception("", n),
+            s @ Value::String(_) => exception("", s),
+            k @ Value::Keyword(..) => exception("", k),
+            s @ Value::Symbol(..) => exception("", s),
+            coll @ Value::List(_) => exception("", coll),
+            coll @ Value::Vector(_) => exception("", coll),
+            coll @ Value::Map(_) => exception("", coll),
+            coll @ Value::Set(_) => exception("", coll),
+            Value::Exception(e) => e.clone(),
+            other => return Err(EvaluationError::WrongType {
+                expected:
+                    "Nil, Bool, Number, String, Keyword, Symbol, List, Vector, Map, Set, Exception",
+                realized: other.clone(),
+            }),
+        };
+    Err(EvaluationError::Exception(exception))
+}
+
+// iterates the elements of any seqable value: strings yield each character
+// as a string and maps yield each entry as a `[k v]` vector
+pub(crate) struct SeqIter<'a>(Box<dyn Iterator<Item = Value> + 'a>);
+
+impl<'a> SeqIter<'a> {
+    pub(crate) fn new(value: &'a Value) -> EvaluationResult<Self> {
+        let iter: Box<dyn Iterator<Item = Value> + 'a> = match value {
+            Value::Nil => Box::new(std::iter::empty()),
+            Value::String(s) => Box::new(s.chars().map(char_string)),
+            Value::List(coll) => Box::new(coll.iter().cloned()),
+            Value::Vector(coll) => Box::new(coll.iter().cloned()),
+            Value::Map(coll) => Box::new(
+                coll.iter()
+                    .map(|(k, v)| vector_with_values([k.clone(), v.clone()])),
+            ),
+            Value::Set(coll) => Box::new(coll.iter().cloned()),
+            Value::Queue(coll) => Box::new(coll.iter().cloned()),
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "Nil, String, List, Vector, Map, Set, Queue",
+                    realized: other.clone(),
+                })
+            }
+        };
+        Ok(Self(iter))
+    }
+}
+
+impl Iterator for SeqIter<'_> {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        self.0.next()
+    }
+}
+
+fn apply(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() < 2 {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let (last, prefix) = args.split_last().expect("has enough elements");
+    let (first, middle) = prefix.split_first().expect("has enough elements");
+    let fn_args: Vec<_> = middle.iter().cloned().chain(SeqIter::new(last)?).collect();
+    apply_callable(interpreter, first, &fn_args)
+}
+
+// invoke any callable `f` with the already evaluated `args`
+pub(crate) fn apply_callable(
+    interpreter: &mut Interpreter,
+    f: &Value,
+    args: &[Value],
+) -> EvaluationResult<Value> {
+    match f {
+        Value::Fn(f) => interpreter.apply_fn_inner(f, args, args.len()),
+        Value::FnWithCaptures(FnWithCapturesImpl { f, captures }) => {
+            interpreter.extend_from_captures(captures)?;
+            let result = interpreter.apply_fn_inner(f, args, args.len());
+            interpreter.leave_scope();
+            result
+        }
+        Value::Primitive(native_fn) => interpreter.apply_primitive_to_args(*native_fn, args),
+        other => apply_other_callable(interpreter, other, args),
+    }
+}
+
+// kept apart from `apply_callable`, which is on the recursive path of `apply`, `map` etc.
+#[inline(never)]
+fn apply_other_callable(
+    interpreter: &mut Interpreter,
+    f: &Value,
+    args: &[Value],
+) -> EvaluationResult<Value> {
+    match f {
+        var @ Value::Var(..) => match interpreter.deref_operator(var.clone()) {
+            Value::Var(..) => Err(EvaluationError::CannotInvoke {
+                value: Box::new(var.clone()),
+                form: truncated_form(&list_with_values(
+                    iter::once(var.clone()).chain(args.iter().cloned()),
+                )),
+            }),
+            f => apply_callable(interpreter, &f, args),
+        },
+        keyword @ Value::Keyword(..) => match args {
+            [coll] => Ok(coll.lookup(keyword).unwrap_or(Value::Nil)),
+            [coll, not_found] => Ok(coll.lookup(keyword).unwrap_or_else(|| not_found.clone())),
+            _ => Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: args.len(),
+            }),
+        },
+        other => Err(EvaluationError::WrongType {
+            expected: "Fn, FnWithCaptures, Primitive, Keyword, Var",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// the elements at each position of `colls`, up to the end of the shortest
+fn zip_seqs(colls: &[Value]) -> EvaluationResult<Vec<Vec<Value>>> {
+    let mut seqs = colls
+        .iter()
+        .map(SeqIter::new)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut result = vec![];
+    loop {
+        let mut elems = Vec::with_capacity(seqs.len());
+        for seq in &mut seqs {
+            match seq.next() {
+                Some(elem) => elems.push(elem),
+                None => return Ok(result),
+            }
+        }
+        result.push(elems);
+    }
+}
+
+// `(map f coll & colls)` applies `f` to the first elements of each coll, then the
+// second and so on until any coll is exhausted
+// calls the fn `name` defined by the sigil source of `core`, loading that source if needed
+fn call_core_fn(
+    interpreter: &mut Interpreter,
+    name: &str,
+    args: &[Value],
+) -> EvaluationResult<Value> {
+    let namespace = Some(DEFAULT_NAME.to_string());
+    interpreter.load_deferred_source_for(name, namespace.as_ref())?;
+    let f = match interpreter.resolve_symbol_to_var(name, namespace.as_ref())? {
+        Value::Var(var) => var_impl_into_inner(&var).unwrap_or(Value::Nil),
+        other => other,
+    };
+    apply_callable(interpreter, &f, args)
+}
+
+// `(map f)` returns a transducer, see `transduce`
+fn map(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args.len() {
+        0 => {
+            return Err(EvaluationError::WrongArityAtLeast {
+                expected: 1,
+                realized: 0,
+            })
+        }
+        1 => return call_core_fn(interpreter, "transducer-map", args),
+        _ => {}
+    }
+    let mut result = vec![];
+    for elems in zip_seqs(&args[1..])? {
+        let mapped_elem = apply_callable(interpreter, &args[0], &elems)?;
+        result.push(mapped_elem);
+    }
+    Ok(Value::List(result.into_iter().collect()))
+}
+
+fn mapv(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() < 2 {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let mut result = PersistentVector::new();
+    for elems in zip_seqs(&args[1..])? {
+        let mapped_elem = apply_callable(interpreter, &args[0], &elems)?;
+        result.push_back_mut(mapped_elem);
+    }
+    Ok(Value::Vector(result))
+}
+
+// `(reduce f coll)` or `(reduce f init coll)` folds `(f acc elem)` over the elements of `coll`,
+// starting from `init` or else the first element; without `init`, an empty `coll` gives `(f)`.
+// The fold stops early with `x` once `f` returns `(reduced x)`.
+fn reduce(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let (f, init, coll) = match args {
+        [f, coll] => (f, None, coll),
+        [f, init, coll] => (f, Some(init.clone()), coll),
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 2,
+                max: 3,
+                realized: args.len(),
+            })
+        }
+    };
+    let mut elems = SeqIter::new(coll)?;
+    let mut acc = match init.or_else(|| elems.next()) {
+        Some(acc) => acc,
+        None => return apply_callable(interpreter, f, &[]),
+    };
+    for elem in elems {
+        match apply_callable(interpreter, f, &[acc, elem])? {
+            Value::Reduced(result) => return Ok(*result),
+            result => acc = result,
+        }
+    }
+    Ok(acc)
+}
+
+// `(reduced x)` wraps `x` so that returning it from the fn given to `reduce` or `reduce-kv`
+// ends the fold with `x`
+fn to_reduced(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Reduced(Box::new(args[0].clone())))
+}
+
+// `(reduce-kv f init coll)` folds `(f acc k v)` over the entries of a map or the
+// indices and elements of a vector, starting from `init`
+fn reduce_kv(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    let entries: Vec<(Value, Value)> = match &args[2] {
+        Value::Nil => vec![],
+        Value::Map(map) => map.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        Value::Vector(elems) => elems
+            .iter()
+            .enumerate()
+            .map(|(index, elem)| (Value::Number(index as i64), elem.clone()))
+            .collect(),
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Map, Vector, Nil",
+                realized: other.clone(),
+            })
+        }
+    };
+    let mut acc = args[1].clone();
+    for (k, v) in entries {
+        match apply_callable(interpreter, &args[0], &[acc, k, v])? {
+            Value::Reduced(result) => return Ok(*result),
+            result => acc = result,
+        }
+    }
+    Ok(acc)
+}
+
+// `(filter pred coll)` lists the elements of `coll` satisfying `pred`;
+// `(filter pred)` returns a transducer, see `transduce`
+fn filter(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [_] => call_core_fn(interpreter, "transducer-filter", args),
+        [pred, coll] => filter_seq(interpreter, pred, coll, true),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+// `(remove pred coll)` lists the elements of `coll` not satisfying `pred`;
+// `(remove pred)` returns a transducer, see `transduce`
+fn remove(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [pred] => {
+            let pred = call_core_fn(interpreter, "complement", std::slice::from_ref(pred))?;
+            call_core_fn(interpreter, "transducer-filter", &[pred])
+        }
+        [pred, coll] => filter_seq(interpreter, pred, coll, false),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+// the elements of `coll` for which `pred` is `keep`
+fn filter_seq(
+    interpreter: &mut Interpreter,
+    pred: &Value,
+    coll: &Value,
+    keep: bool,
+) -> EvaluationResult<Value> {
+    let mut result = vec![];
+    for elem in SeqIter::new(coll)? {
+        if apply_callable(interpreter, pred, std::slice::from_ref(&elem))?.is_truthy() == keep {
+            result.push(elem);
+        }
+    }
+    Ok(list_with_values(result))
+}
+
+fn filterv(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let elems: Vec<_> = SeqIter::new(&args[1])?.collect();
+    let mut result = PersistentVector::new();
+    for elem in elems {
+        if apply_callable(interpreter, &args[0], std::slice::from_ref(&elem))?.is_truthy() {
+            result.push_back_mut(elem);
+        }
+    }
+    Ok(Value::Vector(result))
+}
+
+fn is_some(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Bool(!matches!(args[0], Value::Nil)))
+}
+
+fn to_boolean(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Bool(args[0].is_truthy()))
+}
+
+fn not(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Bool(!args[0].is_truthy()))
+}
+
+macro_rules! is_type {
+    ($name:ident, $($target_type:pat) ,*) => {
+         fn $name(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+            if args.len() != 1 {
+                return Err(EvaluationError::WrongArity {
+                    expected: 1,
+                    realized: args.len(),
+                });
+            }
+            match &args[0] {
+                $($target_type) |* => Ok(Value::Bool(true)),
+                _ => Ok(Value::Bool(false)),
+            }
+        }
+    };
+}
+
+is_type!(is_nil, Value::Nil);
+is_type!(is_true, Value::Bool(true));
+is_type!(is_false, Value::Bool(false));
+is_type!(is_symbol, Value::Symbol(..));
+is_type!(is_keyword, Value::Keyword(..));
+is_type!(is_vector, Value::Vector(..));
+is_type!(
+    is_sequential,
+    Value::List(..),
+    Value::Vector(..),
+    Value::Queue(..)
+);
+is_type!(
+    is_coll,
+    Value::List(..),
+    Value::Vector(..),
+    Value::Map(..),
+    Value::Set(..),
+    Value::Queue(..)
+);
+is_type!(
+    is_seqable,
+    Value::Nil,
+    Value::String(..),
+    Value::List(..),
+    Value::Vector(..),
+    Value::Map(..),
+    Value::Set(..),
+    Value::Queue(..)
+);
+is_type!(is_map, Value::Map(..));
+is_type!(is_set, Value::Set(..));
+is_type!(is_queue, Value::Queue(..));
+is_type!(is_weak_ref, Value::WeakRef(..));
+is_type!(is_reduced, Value::Reduced(..));
+is_type!(is_string, Value::String(..));
+is_type!(is_number, Value::Number(..));
+is_type!(
+    is_fn,
+    Value::Fn(..),
+    Value::FnWithCaptures(..),
+    Value::Primitive(..),
+    Value::Macro(..)
+);
+is_type!(is_macro, Value::Macro(..));
+
+is_type!(is_bytes, Value::Bytes(..));
+is_type!(is_uuid, Value::Uuid(..));
+is_type!(is_inst, Value::Instant(..));
+
+fn to_byte(value: &Value) -> EvaluationResult<u8> {
+    match value {
+        Value::Number(n) if (0..=255).contains(n) => Ok(*n as u8),
+        other => Err(EvaluationError::WrongType {
+            expected: "Number between 0 and 255",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn to_byte_index(bytes: &[u8], index: &Value) -> EvaluationResult<usize> {
+    match index {
+        Value::Number(index) if *index >= 0 => {
+            let index = *index as usize;
+            if index < bytes.len() {
+                Ok(index)
+            } else {
+                Err(EvaluationError::IndexOutOfBounds(index, bytes.len()))
+            }
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn byte_array(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Number(n) if *n >= 0 => Ok(bytes_with_values(vec![0; *n as usize])),
+        Value::Nil => Ok(bytes_with_values(vec![])),
+        Value::List(elems) => Ok(bytes_with_values(
+            elems.iter().map(to_byte).collect::<Result<Vec<_>, _>>()?,
+        )),
+        Value::Vector(elems) => Ok(bytes_with_values(
+            elems.iter().map(to_byte).collect::<Result<Vec<_>, _>>()?,
+        )),
+        Value::Queue(elems) => Ok(bytes_with_values(
+            elems.iter().map(to_byte).collect::<Result<Vec<_>, _>>()?,
+        )),
+        b @ Value::Bytes(..) => Ok(b.clone()),
+        other => Err(EvaluationError::WrongType {
+            expected: "Number, Nil, List, Vector, Queue, Bytes",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn aget(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Bytes(bytes) => {
+            let index = to_byte_index(bytes, &args[1])?;
+            Ok(Value::Number(bytes[index] as i64))
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "Bytes",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// bytes are immutable like every other value so `aset` yields an updated copy
+fn aset(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Bytes(bytes) => {
+            let index = to_byte_index(bytes, &args[1])?;
+            let byte = to_byte(&args[2])?;
+            let mut result = bytes.to_vec();
+            result[index] = byte;
+            Ok(Value::Bytes(result.into()))
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "Bytes",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn encoding_from_args(args: &[Value]) -> EvaluationResult<&'static str> {
+    match args.get(1) {
+        None => Ok("utf-8"),
+        Some(Value::Keyword(encoding, None)) if encoding == "utf-8" => Ok("utf-8"),
+        Some(Value::Keyword(encoding, None)) if encoding == "base64" => Ok("base64"),
+        Some(Value::Keyword(encoding, None)) if encoding == "hex" => Ok("hex"),
+        Some(other) => Err(EvaluationError::UnknownEncoding(other.clone())),
+    }
+}
+
+fn bytes_to_string(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 1 || args.len() == 2) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let encoding = encoding_from_args(args)?;
+    match &args[0] {
+        Value::Bytes(bytes) => {
+            let result = match encoding {
+                "utf-8" => std::str::from_utf8(bytes)
+                    .map_err(|_| EvaluationError::CannotDecode(args[0].clone(), "utf-8"))?
+                    .to_string(),
+                "base64" => BASE64.encode(bytes),
+                "hex" => encode_hex(bytes),
+                _ => unreachable!("validated encoding"),
+            };
+            Ok(Value::String(result.into()))
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "Bytes",
+            realized: other.clone(),
+        }),
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let digit = |b: &u8| (*b as char).to_digit(16).map(|d| d as u8);
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some(digit(high)? << 4 | digit(low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+fn string_to_bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 1 || args.len() == 2) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let encoding = encoding_from_args(args)?;
+    match &args[0] {
+        Value::String(s) => {
+            let result = match encoding {
+                "utf-8" => Some(s.as_bytes().to_vec()),
+                "base64" => BASE64.decode(s.as_bytes()).ok(),
+                "hex" => decode_hex(s),
+                _ => unreachable!("validated encoding"),
+            };
+            result
+                .map(bytes_with_values)
+                .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), encoding))
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// the optional namespace argument of the two-arity `symbol` and `keyword`
+fn namespace_from_arg(arg: &Value) -> EvaluationResult<Option<String>> {
+    match arg {
+        Value::Nil => Ok(None),
+        Value::String(ns) => Ok(Some(ns.to_string())),
+        other => Err(EvaluationError::WrongType {
+            expected: "Nil, String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn to_symbol(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [Value::String(name)] => Ok(Value::Symbol(name.to_string(), None)),
+        [s @ Value::Symbol(..)] => Ok(s.clone()),
+        [ns, Value::String(name)] => Ok(Value::Symbol(name.to_string(), namespace_from_arg(ns)?)),
+        [other] | [_, other] => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+fn to_keyword(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [Value::String(name)] => Ok(Value::Keyword(name.to_string(), None)),
+        [k @ Value::Keyword(..)] => Ok(k.clone()),
+        [Value::Symbol(name, ns)] => Ok(Value::Keyword(name.clone(), ns.clone())),
+        [other] => Err(EvaluationError::WrongType {
+            expected: "String, Keyword, Symbol",
+            realized: other.clone(),
+        }),
+        [ns, Value::String(name)] => Ok(Value::Keyword(name.to_string(), namespace_from_arg(ns)?)),
+        [_, other] => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+fn to_name(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        s @ Value::String(..) => Ok(s.clone()),
+        Value::Keyword(name, _) | Value::Symbol(name, _) => Ok(Value::String(name.as_str().into())),
+        other => Err(EvaluationError::WrongType {
+            expected: "String, Keyword, Symbol",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn to_namespace(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Keyword(_, ns) | Value::Symbol(_, ns) => Ok(ns
+            .as_deref()
+            .map(|ns| Value::String(ns.into()))
+            .unwrap_or(Value::Nil)),
+        other => Err(EvaluationError::WrongType {
+            expected: "Keyword, Symbol",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn to_vector(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    Ok(vector_with_values(args.iter().cloned()))
+}
+
+fn to_map(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() % 2 != 0 {
+        return Err(EvaluationError::MapRequiresPairs(
+            vector_with_values(args.iter().cloned()),
+            args.len(),
+        ));
+    }
+    Ok(map_with_values(args.iter().cloned().tuples()))
+}
+
+fn to_set(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Nil => Ok(Value::Set(PersistentSet::new())),
+        Value::String(s) => Ok(set_with_values(s.chars().map(char_string))),
+        Value::List(coll) => Ok(set_with_values(coll.iter().cloned())),
+        Value::Vector(coll) => Ok(set_with_values(coll.iter().cloned())),
+        Value::Queue(coll) => Ok(set_with_values(coll.iter().cloned())),
+        Value::Map(coll) => Ok(set_with_values(coll.iter().map(|(k, v)| {
+            let mut inner = PersistentVector::new();
+            inner.push_back_mut(k.clone());
+            inner.push_back_mut(v.clone());
+            Value::Vector(inner)
+        }))),
+        s @ Value::Set(..) => Ok(s.clone()),
+        other => Err(EvaluationError::WrongType {
+            expected: "Nil, String, List, Vector, Map, Set, Queue",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn assoc(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() < 3 {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    if (args.len() - 1) % 2 != 0 {
+        return Err(EvaluationError::MapRequiresPairs(
+            vector_with_values(args.iter().cloned()),
+            args.len(),
+        ));
+    }
+    args.iter()
+        .skip(1)
+        .tuples()
+        .try_fold(args[0].clone(), |coll, (key, val)| {
+            coll.assoc(key.clone(), val.clone())
+        })
+}
+
+fn dissoc(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.is_empty() {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    args.iter()
+        .skip(1)
+        .try_fold(args[0].clone(), |coll, key| coll.dissoc(key))
+}
+
+fn update(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() < 3 {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    let coll = &args[0];
+    let key = &args[1];
+    if !matches!(coll, Value::Nil | Value::Map(..) | Value::Vector(..)) {
+        return Err(EvaluationError::WrongType {
+            expected: "Nil, Map, Vector",
+            realized: coll.clone(),
+        });
+    }
+    let mut fn_args = vec![coll.lookup(key).unwrap_or(Value::Nil)];
+    fn_args.extend_from_slice(&args[3..]);
+    let updated = apply_callable(interpreter, &args[2], &fn_args)?;
+    coll.assoc(key.clone(), updated)
+}
+
+// a map argument where nil stands in for the empty map
+fn expect_map(value: &Value) -> EvaluationResult<PersistentMap<Value, Value>> {
+    match value {
+        Value::Nil => Ok(PersistentMap::new()),
+        Value::Map(map) => Ok(map.clone()),
+        other => Err(EvaluationError::WrongType {
+            expected: "Map, Nil",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// `(select-keys m ks)` returns the entries of `m` with a key in `ks`
+fn select_keys(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let map = expect_map(&args[0])?;
+    let mut result = PersistentMap::new();
+    for key in SeqIter::new(&args[1])? {
+        if let Some(value) = map.get(&key) {
+            result.insert_mut(key, value.clone());
+        }
+    }
+    Ok(Value::Map(result))
+}
+
+// `(rename-keys m kmap)` moves the value under each key of `kmap` in `m` to the corresponding value in `kmap`
+fn rename_keys(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let map = expect_map(&args[0])?;
+    let renames = expect_map(&args[1])?;
+    // remove every renamed key first so that keys can be swapped
+    let mut result = renames
+        .keys()
+        .fold(map.clone(), |result, old| result.remove(old));
+    for (old, new) in renames.iter() {
+        if let Some(value) = map.get(old) {
+            result.insert_mut(new.clone(), value.clone());
+        }
+    }
+    Ok(Value::Map(result))
+}
+
+// merges `from` into `into`, recursing where both hold a map under the same key and
+// otherwise resolving conflicts with `resolve` if given or else taking the value in `from`
+fn deep_merge_maps(
+    interpreter: &mut Interpreter,
+    into: PersistentMap<Value, Value>,
+    from: &PersistentMap<Value, Value>,
+    resolve: Option<&Value>,
+) -> EvaluationResult<PersistentMap<Value, Value>> {
+    let mut result = into;
+    for (key, value) in from.iter() {
+        let merged = match (result.get(key), value) {
+            (Some(Value::Map(existing)), Value::Map(value)) => Value::Map(deep_merge_maps(
+                interpreter,
+                existing.clone(),
+                value,
+                resolve,
+            )?),
+            (Some(existing), value) => match resolve {
+                Some(f) => apply_callable(interpreter, f, &[existing.clone(), value.clone()])?,
+                None => value.clone(),
+            },
+            (None, value) => value.clone(),
+        };
+        result.insert_mut(key.clone(), merged);
+    }
+    Ok(result)
+}
+
+fn deep_merge_all(
+    interpreter: &mut Interpreter,
+    maps: &[Value],
+    resolve: Option<&Value>,
+) -> EvaluationResult<Value> {
+    if maps.iter().all(|map| matches!(map, Value::Nil)) {
+        return Ok(Value::Nil);
+    }
+    let mut result = PersistentMap::new();
+    for map in maps {
+        result = deep_merge_maps(interpreter, result, &expect_map(map)?, resolve)?;
+    }
+    Ok(Value::Map(result))
+}
+
+// `(deep-merge & maps)` merges nested maps, with later values winning otherwise
+fn deep_merge(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    deep_merge_all(interpreter, args, None)
+}
 
-fn to_symbol(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+// `(deep-merge-with f & maps)` is `deep-merge` calling `(f existing value)` for conflicting
+// values that are not both maps
+fn deep_merge_with(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.is_empty() {
+        return Err(EvaluationError::WrongArityAtLeast {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    deep_merge_all(interpreter, &args[1..], Some(&args[0]))
+}
+
+// `(lru-cache capacity)` returns an empty cache holding at most `capacity` entries
+fn lru_cache(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
@@ -897,147 +3219,314 @@ fn to_symbol(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         });
     }
     match &args[0] {
-        Value::String(name) => Ok(Value::Symbol(name.clone(), None)),
+        Value::Number(capacity) if *capacity > 0 => {
+            Ok(Value::LruCache(LruCacheImpl::new(*capacity as usize)))
+        }
         other => Err(EvaluationError::WrongType {
-            expected: "String",
+            expected: "positive Number",
             realized: other.clone(),
         }),
     }
 }
 
-fn to_keyword(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 1 {
+// `(lru-lookup cache k)` returns `[v]` if `cache` holds `v` for `k`, otherwise `nil`,
+// without counting as a use of `k`
+fn lru_lookup(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
-            expected: 1,
+            expected: 2,
             realized: args.len(),
         });
     }
     match &args[0] {
-        Value::String(name) => Ok(Value::Keyword(name.clone(), None)),
-        k @ Value::Keyword(..) => Ok(k.clone()),
+        Value::LruCache(cache) => Ok(cache
+            .get(&args[1])
+            .map(|value| vector_with_values([value]))
+            .unwrap_or(Value::Nil)),
         other => Err(EvaluationError::WrongType {
-            expected: "String, Keyword",
+            expected: "LruCache",
             realized: other.clone(),
         }),
     }
 }
 
-fn to_vector(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    Ok(vector_with_values(args.iter().cloned()))
+// `(lru-put cache k v)` makes `cache` hold `v` for `k` as its most recently used entry,
+// evicting the least recently used entry if `cache` is full, and returns `cache`
+fn lru_put(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::LruCache(cache) => {
+            cache.put(args[1].clone(), args[2].clone());
+            Ok(args[0].clone())
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "LruCache",
+            realized: other.clone(),
+        }),
+    }
 }
 
-fn to_map(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() % 2 != 0 {
-        return Err(EvaluationError::MapRequiresPairs(
-            vector_with_values(args.iter().cloned()),
-            args.len(),
-        ));
+fn subvec(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 2 || args.len() == 3) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 2,
+            max: 3,
+            realized: args.len(),
+        });
     }
-    Ok(map_with_values(args.iter().cloned().tuples()))
+    let elems = match &args[0] {
+        Value::Vector(elems) => elems,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Vector",
+                realized: other.clone(),
+            })
+        }
+    };
+    let mut bounds = [0, elems.len()];
+    for (bound, arg) in bounds.iter_mut().zip(&args[1..]) {
+        match arg {
+            Value::Number(n) if *n >= 0 => *bound = *n as usize,
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "Number",
+                    realized: other.clone(),
+                })
+            }
+        }
+    }
+    let [start, end] = bounds;
+    if end > elems.len() {
+        return Err(EvaluationError::IndexOutOfBounds(end, elems.len()));
+    }
+    if start > end {
+        return Err(EvaluationError::IndexOutOfBounds(start, end));
+    }
+    Ok(vector_with_values(
+        elems.iter().skip(start).take(end - start).cloned(),
+    ))
 }
 
-fn to_set(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 1 {
+// a count of elements, where a negative `n` counts none
+fn count_arg(n: &Value) -> EvaluationResult<usize> {
+    match n {
+        Value::Number(n) => Ok(usize::try_from(*n).unwrap_or(0)),
+        other => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// the number of elements in `coll`, counted without walking lists, vectors or queues
+fn seq_len(coll: &Value) -> usize {
+    match coll {
+        Value::String(s) => s.chars().count(),
+        Value::List(elems) => elems.len(),
+        Value::Vector(elems) => elems.len(),
+        Value::Map(elems) => elems.size(),
+        Value::Set(elems) => elems.size(),
+        Value::Queue(elems) => elems.len(),
+        _ => 0,
+    }
+}
+
+fn take_last(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
-            expected: 1,
+            expected: 2,
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::Nil => Ok(Value::Set(PersistentSet::new())),
-        Value::String(s) => Ok(set_with_values(
-            s.chars().map(|c| Value::String(c.to_string())),
+    let n = count_arg(&args[0])?;
+    let coll = &args[1];
+    let elems = SeqIter::new(coll)?;
+    let len = seq_len(coll);
+    if n == 0 || len == 0 {
+        return Ok(Value::Nil);
+    }
+    match coll {
+        Value::Vector(elems) => Ok(list_with_values(
+            (len.saturating_sub(n)..len).map(|index| elems[index].clone()),
         )),
-        Value::List(coll) => Ok(set_with_values(coll.iter().cloned())),
-        Value::Vector(coll) => Ok(set_with_values(coll.iter().cloned())),
-        Value::Map(coll) => Ok(set_with_values(coll.iter().map(|(k, v)| {
-            let mut inner = PersistentVector::new();
-            inner.push_back_mut(k.clone());
-            inner.push_back_mut(v.clone());
-            Value::Vector(inner)
-        }))),
-        s @ Value::Set(..) => Ok(s.clone()),
-        other => Err(EvaluationError::WrongType {
-            expected: "Nil, String, List, Vector, Map, Set",
-            realized: other.clone(),
+        _ => Ok(list_with_values(elems.skip(len.saturating_sub(n)))),
+    }
+}
+
+// `(take n coll)` lists the first `n` elements of `coll`;
+// `(take n)` returns a transducer, see `transduce`
+fn take(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [_] => call_core_fn(interpreter, "transducer-take", args),
+        [n, coll] => Ok(list_with_values(SeqIter::new(coll)?.take(count_arg(n)?))),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
         }),
     }
 }
 
-fn assoc(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() < 3 {
+// `(drop n coll)` lists the elements of `coll` after the first `n`;
+// `(drop n)` returns a transducer, see `transduce`
+fn drop(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args {
+        [_] => call_core_fn(interpreter, "transducer-drop", args),
+        [n, coll] => Ok(list_with_values(SeqIter::new(coll)?.skip(count_arg(n)?))),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        }),
+    }
+}
+
+fn drop_last(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let (n, coll) = match args {
+        [coll] => (1, coll),
+        [n, coll] => (count_arg(n)?, coll),
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 1,
+                max: 2,
+                realized: args.len(),
+            })
+        }
+    };
+    let elems = SeqIter::new(coll)?;
+    Ok(list_with_values(
+        elems.take(seq_len(coll).saturating_sub(n)),
+    ))
+}
+
+fn nthrest(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
-            expected: 3,
+            expected: 2,
             realized: args.len(),
         });
     }
-    if (args.len() - 1) % 2 != 0 {
-        return Err(EvaluationError::MapRequiresPairs(
-            vector_with_values(args.iter().cloned()),
-            args.len(),
-        ));
+    let coll = &args[0];
+    let n = count_arg(&args[1])?;
+    let elems = SeqIter::new(coll)?;
+    if n == 0 {
+        return Ok(coll.clone());
     }
-    match &args[0] {
-        Value::Map(map) => {
-            let mut result = map.clone();
-            for (key, val) in args.iter().skip(1).tuples() {
-                result.insert_mut(key.clone(), val.clone());
+    match coll {
+        // shares the remaining elements with `coll`
+        Value::List(elems) => {
+            let mut rest = elems.clone();
+            for _ in 0..n {
+                if !rest.drop_first_mut() {
+                    break;
+                }
             }
-            Ok(Value::Map(result))
+            Ok(Value::List(rest))
         }
-        other => Err(EvaluationError::WrongType {
-            expected: "Map",
-            realized: other.clone(),
-        }),
+        Value::Vector(elems) => Ok(list_with_values(
+            (n.min(elems.len())..elems.len()).map(|index| elems[index].clone()),
+        )),
+        _ => Ok(list_with_values(elems.skip(n))),
     }
 }
 
-fn dissoc(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.is_empty() {
+// the elements of `coll` from `start` up to `end`, or its end, as a collection of the same
+// kind for lists and vectors; bounds outside `coll` are clamped so pages past the end are empty
+fn slice(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 2 || args.len() == 3) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 2,
+            max: 3,
+            realized: args.len(),
+        });
+    }
+    let coll = &args[0];
+    let elems = SeqIter::new(coll)?;
+    let len = seq_len(coll);
+    let start = count_arg(&args[1])?.min(len);
+    let end = match args.get(2) {
+        Some(end) => count_arg(end)?.clamp(start, len),
+        None => len,
+    };
+    match coll {
+        Value::Vector(elems) => Ok(vector_with_values(
+            (start..end).map(|index| elems[index].clone()),
+        )),
+        _ => Ok(list_with_values(elems.skip(start).take(end - start))),
+    }
+}
+
+fn peek(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
             realized: args.len(),
         });
     }
     match &args[0] {
-        Value::Map(map) => {
-            let mut result = map.clone();
-            for key in args.iter().skip(1) {
-                result.remove_mut(key);
-            }
-            Ok(Value::Map(result))
-        }
+        Value::Nil => Ok(Value::Nil),
+        Value::List(elems) => Ok(elems.first().cloned().unwrap_or(Value::Nil)),
+        Value::Vector(elems) => Ok(elems.last().cloned().unwrap_or(Value::Nil)),
+        Value::Queue(elems) => Ok(elems.peek().cloned().unwrap_or(Value::Nil)),
         other => Err(EvaluationError::WrongType {
-            expected: "Map",
+            expected: "Nil, List, Vector, Queue",
             realized: other.clone(),
         }),
     }
 }
 
-fn get(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() != 2 {
+fn pop(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
-            expected: 2,
+            expected: 1,
             realized: args.len(),
         });
     }
     match &args[0] {
         Value::Nil => Ok(Value::Nil),
-        Value::Map(map) => {
-            let result = if let Some(val) = map.get(&args[1]) {
-                val.clone()
-            } else {
-                Value::Nil
-            };
-            Ok(result)
-        }
+        Value::List(elems) => elems
+            .drop_first()
+            .map(Value::List)
+            .ok_or_else(|| EvaluationError::CannotPopEmptyCollection(args[0].clone())),
+        Value::Vector(elems) => elems
+            .drop_last()
+            .map(Value::Vector)
+            .ok_or_else(|| EvaluationError::CannotPopEmptyCollection(args[0].clone())),
+        Value::Queue(elems) => elems
+            .dequeue()
+            .map(Value::Queue)
+            .ok_or_else(|| EvaluationError::CannotPopEmptyCollection(args[0].clone())),
         other => Err(EvaluationError::WrongType {
-            expected: "Nil, Map",
+            expected: "Nil, List, Vector, Queue",
             realized: other.clone(),
         }),
     }
 }
 
+fn get(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(args.len() == 2 || args.len() == 3) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 2,
+            max: 3,
+            realized: args.len(),
+        });
+    }
+    let coll = &args[0];
+    if !coll.is_associative() {
+        return Err(EvaluationError::WrongType {
+            expected: "Nil, Map, Vector, Set, String",
+            realized: coll.clone(),
+        });
+    }
+    let not_found = args.get(2).cloned().unwrap_or(Value::Nil);
+    Ok(coll.lookup(&args[1]).unwrap_or(not_found))
+}
+
 fn does_contain(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 2 {
         return Err(EvaluationError::WrongArity {
@@ -1045,19 +3534,17 @@ fn does_contain(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value>
             realized: args.len(),
         });
     }
-    match &args[0] {
-        Value::Nil => Ok(Value::Bool(false)),
-        Value::Map(map) => {
-            let contains = map.contains_key(&args[1]);
-            Ok(Value::Bool(contains))
-        }
-        other => Err(EvaluationError::WrongType {
-            expected: "Nil, Map",
-            realized: other.clone(),
-        }),
+    let coll = &args[0];
+    if !coll.is_associative() {
+        return Err(EvaluationError::WrongType {
+            expected: "Nil, Map, Vector, Set, String",
+            realized: coll.clone(),
+        });
     }
+    Ok(Value::Bool(coll.contains_key(&args[1])))
 }
 
+// maps are ordered by key so `keys`, `vals` and `seq` of the same map always agree in order
 fn to_keys(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
@@ -1084,6 +3571,59 @@ fn to_keys(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     Ok(result)
 }
 
+// map entries, as yielded by `seq` on a map, are represented by `[k v]` vectors
+fn map_entry(value: &Value) -> Option<(&Value, &Value)> {
+    match value {
+        Value::Vector(elems) if elems.len() == 2 => Some((
+            elems.get(0).expect("has two elements"),
+            elems.get(1).expect("has two elements"),
+        )),
+        _ => None,
+    }
+}
+
+fn is_map_entry(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Bool(map_entry(&args[0]).is_some()))
+}
+
+fn to_key(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match map_entry(&args[0]) {
+        Some((k, _)) => Ok(k.clone()),
+        None => Err(EvaluationError::WrongType {
+            expected: "MapEntry",
+            realized: args[0].clone(),
+        }),
+    }
+}
+
+fn to_val(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match map_entry(&args[0]) {
+        Some((_, v)) => Ok(v.clone()),
+        None => Err(EvaluationError::WrongType {
+            expected: "MapEntry",
+            realized: args[0].clone(),
+        }),
+    }
+}
+
 fn to_vals(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
@@ -1133,21 +3673,23 @@ fn last(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
                 Ok(Value::Nil)
             }
         }
+        Value::Queue(elems) => Ok(elems.iter().last().cloned().unwrap_or(Value::Nil)),
         other => Err(EvaluationError::WrongType {
-            expected: "Nil, List, Vector",
+            expected: "Nil, List, Vector, Queue",
             realized: other.clone(),
         }),
     }
 }
 
+// `(conj)` is an empty vector and `(conj coll)` is `coll`, so `conj` can complete a `transduce`
 fn conj(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
-    if args.len() < 2 {
-        return Err(EvaluationError::WrongArity {
-            expected: 2,
-            realized: args.len(),
-        });
+    match args.len() {
+        0 => return Ok(Value::Vector(PersistentVector::new())),
+        1 => return Ok(args[0].clone()),
+        _ => {}
     }
-    match &args[0] {
+    // the collection keeps its metadata
+    let result = match args[0].without_meta() {
         Value::Nil => Ok(list_with_values(args[1..].iter().cloned())),
         Value::List(seq) => {
             let mut inner = seq.clone();
@@ -1166,24 +3708,7 @@ fn conj(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
         Value::Map(seq) => {
             let mut inner = seq.clone();
             for elem in &args[1..] {
-                match elem {
-                    Value::Vector(kv) if kv.len() == 2 => {
-                        let k = &kv[0];
-                        let v = &kv[1];
-                        inner.insert_mut(k.clone(), v.clone());
-                    }
-                    Value::Map(elems) => {
-                        for (k, v) in elems {
-                            inner.insert_mut(k.clone(), v.clone());
-                        }
-                    }
-                    other => {
-                        return Err(EvaluationError::WrongType {
-                            expected: "Vector, Map",
-                            realized: other.clone(),
-                        })
-                    }
-                }
+                conj_entry(&mut inner, elem)?;
             }
             Ok(Value::Map(inner))
         }
@@ -1194,11 +3719,40 @@ fn conj(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
             }
             Ok(Value::Set(inner))
         }
+        Value::Queue(seq) => {
+            let mut inner = seq.clone();
+            for elem in &args[1..] {
+                inner.enqueue_mut(elem.clone());
+            }
+            Ok(Value::Queue(inner))
+        }
         other => Err(EvaluationError::WrongType {
-            expected: "Nil, List, Vector, Map, Set",
+            expected: "Nil, List, Vector, Map, Set, Queue",
             realized: other.clone(),
         }),
+    }?;
+    result.with_meta(args[0].meta().cloned().unwrap_or(Value::Nil))
+}
+
+// adds `elem` to `map` as `conj` does, given as `[k v]` or as a map of entries
+fn conj_entry(map: &mut PersistentMap<Value, Value>, elem: &Value) -> EvaluationResult<()> {
+    match elem.without_meta() {
+        Value::Vector(kv) if kv.len() == 2 => {
+            map.insert_mut(kv[0].clone(), kv[1].clone());
+        }
+        Value::Map(elems) => {
+            for (k, v) in elems {
+                map.insert_mut(k.clone(), v.clone());
+            }
+        }
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Vector, Map",
+                realized: other.clone(),
+            })
+        }
     }
+    Ok(())
 }
 
 fn time_in_millis(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
@@ -1206,12 +3760,112 @@ fn time_in_millis(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value
         return Err(EvaluationError::WrongArity {
             expected: 0,
             realized: args.len(),
-        });
+        });
+    }
+    let duration = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| -> EvaluationError { InterpreterError::SystemTimeError(err).into() })?;
+    Ok(Value::Number(duration.as_millis() as i64))
+}
+
+// nanoseconds elapsed on a monotonic clock, unaffected by changes to the system time
+fn nano_time(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Number(
+        interpreter.started_at.elapsed().as_nanos() as i64
+    ))
+}
+
+// milliseconds elapsed on the same clock as `nano-time`
+fn monotonic_millis(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Number(
+        interpreter.started_at.elapsed().as_millis() as i64
+    ))
+}
+
+fn into(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    match args.len() {
+        0 => Ok(Value::Vector(PersistentVector::new())),
+        1 => Ok(args[0].clone()),
+        2 => {
+            let mut from = SeqIter::new(&args[1])?.peekable();
+            if from.peek().is_none() {
+                return Ok(args[0].clone());
+            }
+            // built in one pass over `from`, where a list takes each element in turn at
+            // its front as `conj` does
+            let result = match &args[0] {
+                Value::Nil => {
+                    let mut to = PersistentList::new();
+                    from.for_each(|elem| to.push_front_mut(elem));
+                    Value::List(to)
+                }
+                Value::List(to) => {
+                    let mut to = to.clone();
+                    from.for_each(|elem| to.push_front_mut(elem));
+                    Value::List(to)
+                }
+                Value::Vector(to) => {
+                    let mut to = to.clone();
+                    to.extend(from);
+                    Value::Vector(to)
+                }
+                Value::Map(to) => {
+                    let mut to = to.clone();
+                    for elem in from {
+                        conj_entry(&mut to, &elem)?;
+                    }
+                    Value::Map(to)
+                }
+                Value::Set(to) if to.is_empty() => Value::Set(from.collect()),
+                Value::Set(to) => {
+                    let mut to = to.clone();
+                    from.for_each(|elem| to.insert_mut(elem));
+                    Value::Set(to)
+                }
+                Value::Queue(to) if to.is_empty() => Value::Queue(from.collect()),
+                Value::Queue(to) => {
+                    let mut to = to.clone();
+                    from.for_each(|elem| to.enqueue_mut(elem));
+                    Value::Queue(to)
+                }
+                other => {
+                    return Err(EvaluationError::WrongType {
+                        expected: "Nil, List, Vector, Map, Set, Queue",
+                        realized: other.clone(),
+                    })
+                }
+            };
+            Ok(result)
+        }
+        // `(into to xform from)` conjoins each element of `from` transformed by `xform`
+        3 => call_core_fn(
+            interpreter,
+            "transduce",
+            &[
+                args[1].clone(),
+                Value::Primitive(conj),
+                args[0].clone(),
+                args[2].clone(),
+            ],
+        ),
+        _ => Err(EvaluationError::WrongArityRange {
+            min: 0,
+            max: 3,
+            realized: args.len(),
+        }),
     }
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|err| -> EvaluationError { InterpreterError::SystemTimeError(err).into() })?;
-    Ok(Value::Number(duration.as_millis() as i64))
 }
 
 fn to_seq(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
@@ -1221,33 +3875,18 @@ fn to_seq(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
             realized: args.len(),
         });
     }
+    let mut elems = SeqIter::new(&args[0])?.peekable();
+    if elems.peek().is_none() {
+        return Ok(Value::Nil);
+    }
     match &args[0] {
-        Value::Nil => Ok(Value::Nil),
-        Value::String(s) if s.is_empty() => Ok(Value::Nil),
-        Value::String(s) => Ok(list_with_values(
-            s.chars().map(|c| Value::String(c.to_string())),
-        )),
-        Value::List(coll) if coll.is_empty() => Ok(Value::Nil),
         l @ Value::List(..) => Ok(l.clone()),
-        Value::Vector(coll) if coll.is_empty() => Ok(Value::Nil),
-        Value::Vector(coll) => Ok(list_with_values(coll.iter().cloned())),
-        Value::Map(coll) if coll.is_empty() => Ok(Value::Nil),
-        Value::Map(coll) => Ok(list_with_values(coll.iter().map(|(k, v)| {
-            let mut inner = PersistentVector::new();
-            inner.push_back_mut(k.clone());
-            inner.push_back_mut(v.clone());
-            Value::Vector(inner)
-        }))),
-        Value::Set(coll) if coll.is_empty() => Ok(Value::Nil),
-        Value::Set(coll) => Ok(list_with_values(coll.iter().cloned())),
-        other => Err(EvaluationError::WrongType {
-            expected: "Nil, String, List, Vector, Map, Set",
-            realized: other.clone(),
-        }),
+        _ => Ok(list_with_values(elems)),
     }
 }
 
-fn readline(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+fn readline(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    audit_io(interpreter, "readline", args);
     if args.len() != 1 {
         return Err(EvaluationError::WrongArity {
             expected: 1,
@@ -1290,7 +3929,7 @@ fn readline(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
                 if input.ends_with('\n') {
                     input.pop();
                 }
-                Ok(Value::String(input))
+                Ok(Value::String(input.into()))
             }
         }
         other => Err(EvaluationError::WrongType {
@@ -1300,12 +3939,45 @@ fn readline(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
 }
 
-fn to_meta(_: &mut Interpreter, _args: &[Value]) -> EvaluationResult<Value> {
-    Ok(Value::Nil)
+// only vars carry metadata, as attached when they are defined
+fn to_meta(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Var(var) => Ok(var.meta().unwrap_or(Value::Nil)),
+        other => Ok(other.meta().cloned().unwrap_or(Value::Nil)),
+    }
 }
 
-fn with_meta(_: &mut Interpreter, _args: &[Value]) -> EvaluationResult<Value> {
-    Ok(Value::Nil)
+fn with_meta(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    args[0].clone().with_meta(args[1].clone())
+}
+
+// the primitives given their operands with any metadata: those reading or attaching it
+// and those building collections, so that e.g. a macro can pass `^:private name` along
+pub(crate) fn keeps_metadata(native_fn: NativeFn) -> bool {
+    [
+        to_meta as NativeFn,
+        with_meta,
+        list,
+        to_vector,
+        to_map,
+        queue,
+        cons,
+        conj,
+    ]
+    .iter()
+    .any(|f| *f as usize == native_fn as usize)
 }
 
 fn is_zero(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
@@ -1324,14 +3996,348 @@ fn is_zero(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
     }
 }
 
+// applies `inner` to each element of `form`, rebuilding a collection of the same type;
+// the elements of a map are given to `inner` as `[k v]` vectors
+fn walk_elems(
+    interpreter: &mut Interpreter,
+    form: &Value,
+    inner: &mut dyn FnMut(&mut Interpreter, &Value) -> EvaluationResult<Value>,
+) -> EvaluationResult<Value> {
+    match form {
+        Value::List(elems) => Ok(list_with_values(
+            elems
+                .iter()
+                .map(|elem| inner(interpreter, elem))
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        Value::Vector(elems) => Ok(vector_with_values(
+            elems
+                .iter()
+                .map(|elem| inner(interpreter, elem))
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        Value::Set(elems) => Ok(set_with_values(
+            elems
+                .iter()
+                .map(|elem| inner(interpreter, elem))
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        Value::Queue(elems) => Ok(Value::Queue(
+            elems
+                .iter()
+                .map(|elem| inner(interpreter, elem))
+                .collect::<Result<_, _>>()?,
+        )),
+        Value::Map(elems) => {
+            let mut entries = Vec::with_capacity(elems.size());
+            for (k, v) in elems.iter() {
+                let entry = vector_with_values([k.clone(), v.clone()]);
+                match inner(interpreter, &entry)? {
+                    Value::Vector(pair) if pair.len() == 2 => {
+                        entries.push((pair[0].clone(), pair[1].clone()));
+                    }
+                    Value::List(pair) if pair.len() == 2 => {
+                        let mut pair = pair.iter().cloned();
+                        let k = pair.next().expect("has two elements");
+                        let v = pair.next().expect("has two elements");
+                        entries.push((k, v));
+                    }
+                    other => {
+                        return Err(EvaluationError::WrongType {
+                            expected: "Vector or List of length 2",
+                            realized: other,
+                        })
+                    }
+                }
+            }
+            Ok(map_with_values(entries))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+// `(walk inner outer form)`
+fn walk(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 3 {
+        return Err(EvaluationError::WrongArity {
+            expected: 3,
+            realized: args.len(),
+        });
+    }
+    let (inner, outer) = (&args[0], &args[1]);
+    let walked = walk_elems(interpreter, &args[2], &mut |interpreter, elem| {
+        apply_callable(interpreter, inner, std::slice::from_ref(elem))
+    })?;
+    apply_callable(interpreter, outer, &[walked])
+}
+
+fn postwalk_value(
+    interpreter: &mut Interpreter,
+    f: &Value,
+    form: &Value,
+) -> EvaluationResult<Value> {
+    let walked = walk_elems(interpreter, form, &mut |interpreter, elem| {
+        postwalk_value(interpreter, f, elem)
+    })?;
+    apply_callable(interpreter, f, &[walked])
+}
+
+// `(postwalk f form)` applies `f` to each subform of `form` after its elements
+fn postwalk(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    postwalk_value(interpreter, &args[0], &args[1])
+}
+
+fn prewalk_value(
+    interpreter: &mut Interpreter,
+    f: &Value,
+    form: &Value,
+) -> EvaluationResult<Value> {
+    let form = apply_callable(interpreter, f, std::slice::from_ref(form))?;
+    walk_elems(interpreter, &form, &mut |interpreter, elem| {
+        prewalk_value(interpreter, f, elem)
+    })
+}
+
+// `(prewalk f form)` applies `f` to each subform of `form` before its elements
+fn prewalk(interpreter: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    prewalk_value(interpreter, &args[0], &args[1])
+}
+
+fn hash(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Number(args[0].stable_hash() as i64))
+}
+
+#[derive(Default)]
+struct Measurement {
+    nodes: usize,
+    leaves: usize,
+    // levels of nesting, so a value that is not a collection has depth 0
+    depth: usize,
+    // the largest count of any collection in the value
+    max_count: usize,
+}
+
+// walks `value` with an explicit stack so deeply nested values cannot overflow the native stack
+fn measure_value(value: &Value) -> Measurement {
+    let mut measurement = Measurement::default();
+    let mut pending = vec![(value, 0)];
+    while let Some((value, depth)) = pending.pop() {
+        measurement.nodes += 1;
+        measurement.depth = measurement.depth.max(depth);
+        let count = match value {
+            Value::List(elems) => {
+                pending.extend(elems.iter().map(|elem| (elem, depth + 1)));
+                elems.len()
+            }
+            Value::Vector(elems) => {
+                pending.extend(elems.iter().map(|elem| (elem, depth + 1)));
+                elems.len()
+            }
+            Value::Set(elems) => {
+                pending.extend(elems.iter().map(|elem| (elem, depth + 1)));
+                elems.size()
+            }
+            Value::Queue(elems) => {
+                pending.extend(elems.iter().map(|elem| (elem, depth + 1)));
+                elems.len()
+            }
+            Value::Map(elems) => {
+                for (k, v) in elems.iter() {
+                    pending.push((k, depth + 1));
+                    pending.push((v, depth + 1));
+                }
+                elems.size()
+            }
+            _ => {
+                measurement.leaves += 1;
+                continue;
+            }
+        };
+        measurement.max_count = measurement.max_count.max(count);
+    }
+    measurement
+}
+
+// `(count-nodes x)` counts `x` and every value nested in it, counting map keys and values separately
+fn count_nodes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Number(measure_value(&args[0]).nodes as i64))
+}
+
+// `(measure x)` returns `{:nodes n :leaves n :depth n :max-count n}` describing the shape of `x`
+fn measure(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let measurement = measure_value(&args[0]);
+    let entry = |name: &str, n: usize| {
+        (
+            Value::Keyword(name.to_string(), None),
+            Value::Number(n as i64),
+        )
+    };
+    Ok(map_with_values([
+        entry("nodes", measurement.nodes),
+        entry("leaves", measurement.leaves),
+        entry("depth", measurement.depth),
+        entry("max-count", measurement.max_count),
+    ]))
+}
+
+// `(diff a b)` returns `[things-only-in-a things-only-in-b things-in-both]`,
+// recursing into maps, sets and sequential collections
+fn diff(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    Ok(vector_with_values(diff_values(&args[0], &args[1])))
+}
+
+fn diff_values(a: &Value, b: &Value) -> [Value; 3] {
+    if a == b {
+        return [Value::Nil, Value::Nil, a.clone()];
+    }
+    match (a, b) {
+        (Value::Map(a), Value::Map(b)) => {
+            let keys = a
+                .keys()
+                .chain(b.keys().filter(|k| !a.contains_key(*k)))
+                .cloned();
+            diff_associative(keys, |k| a.get(k), |k| b.get(k)).map(|entries| {
+                if entries.is_empty() {
+                    Value::Nil
+                } else {
+                    map_with_values(entries)
+                }
+            })
+        }
+        (Value::Set(a), Value::Set(b)) => {
+            let only_in = |a: &PersistentSet<Value>, b: &PersistentSet<Value>| {
+                let elems = a.iter().filter(|elem| !b.contains(*elem)).cloned();
+                let set = PersistentSet::from_iter(elems);
+                if set.is_empty() {
+                    Value::Nil
+                } else {
+                    Value::Set(set)
+                }
+            };
+            let both = PersistentSet::from_iter(a.iter().filter(|elem| b.contains(*elem)).cloned());
+            [
+                only_in(a, b),
+                only_in(b, a),
+                if both.is_empty() {
+                    Value::Nil
+                } else {
+                    Value::Set(both)
+                },
+            ]
+        }
+        (
+            Value::List(_) | Value::Vector(_) | Value::Queue(_),
+            Value::List(_) | Value::Vector(_) | Value::Queue(_),
+        ) => {
+            let a = sequential_elems(a);
+            let b = sequential_elems(b);
+            let keys = 0..a.len().max(b.len());
+            diff_associative(keys, |i| a.get(*i).copied(), |i| b.get(*i).copied()).map(|entries| {
+                match entries.last() {
+                    None => Value::Nil,
+                    Some((last, _)) => {
+                        let mut elems = vec![Value::Nil; last + 1];
+                        for (i, elem) in entries {
+                            elems[i] = elem;
+                        }
+                        vector_with_values(elems)
+                    }
+                }
+            })
+        }
+        _ => [a.clone(), b.clone(), Value::Nil],
+    }
+}
+
+fn sequential_elems(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::List(elems) => elems.iter().collect(),
+        Value::Vector(elems) => elems.iter().collect(),
+        Value::Queue(elems) => elems.iter().collect(),
+        _ => unreachable!("only called with sequential values"),
+    }
+}
+
+// diffs the values under each of `keys` returning the entries that are
+// only in `a`, only in `b` and in both, in the same order as `keys`
+fn diff_associative<'a, K: Clone>(
+    keys: impl Iterator<Item = K>,
+    get_a: impl Fn(&K) -> Option<&'a Value>,
+    get_b: impl Fn(&K) -> Option<&'a Value>,
+) -> [Vec<(K, Value)>; 3] {
+    let mut only_a = vec![];
+    let mut only_b = vec![];
+    let mut both = vec![];
+    for k in keys {
+        let a = get_a(&k);
+        let b = get_b(&k);
+        let [diff_a, diff_b, diff_both] =
+            diff_values(a.unwrap_or(&Value::Nil), b.unwrap_or(&Value::Nil));
+        let same = match (a, b) {
+            (Some(Value::Nil), Some(Value::Nil)) => true,
+            (Some(_), Some(_)) => !matches!(diff_both, Value::Nil),
+            _ => false,
+        };
+        if a.is_some() && (!matches!(diff_a, Value::Nil) || !same) {
+            only_a.push((k.clone(), diff_a));
+        }
+        if b.is_some() && (!matches!(diff_b, Value::Nil) || !same) {
+            only_b.push((k.clone(), diff_b));
+        }
+        if same {
+            both.push((k, diff_both));
+        }
+    }
+    [only_a, only_b, both]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testing::run_eval_test;
     use crate::value::{
-        list_with_values, map_with_values, set_with_values, vector_with_values, Value::*,
+        bytes_with_values, list_with_values, map_with_values, set_with_values, vector_with_values,
+        Value::*,
     };
     use crate::value::{PersistentList, PersistentMap, PersistentSet, PersistentVector};
+    use crate::Interpreter;
     use std::iter::FromIterator;
+    use std::rc::Rc;
 
     #[test]
     fn test_basic_prelude() {
@@ -1348,6 +4354,23 @@ mod tests {
             ("(empty? (list 1))", Bool(false)),
             ("(empty? [1 2 3])", Bool(false)),
             ("(empty? [])", Bool(true)),
+            ("(min 3)", Number(3)),
+            ("(min 3 -1 2)", Number(-1)),
+            ("(max 3 -1 2)", Number(3)),
+            ("(apply max [1 5 2])", Number(5)),
+            ("(try* (min) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (max 1 :a) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(sum [])", Number(0)),
+            ("(sum nil)", Number(0)),
+            ("(sum [1 2 3])", Number(6)),
+            ("(sum '(1 -2))", Number(-1)),
+            ("(sum #{1 2})", Number(3)),
+            ("(try* (sum [9223372036854775807 1]) (catch* e :overflow))", Keyword("overflow".to_string(), None)),
+            ("(clamp 5 0 10)", Number(5)),
+            ("(clamp -5 0 10)", Number(0)),
+            ("(clamp 15 0 10)", Number(10)),
+            ("(clamp 15 3 3)", Number(3)),
+            ("(try* (clamp 1 10 0) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
             ("(count nil)", Number(0)),
             ("(count \"hi\")", Number(2)),
             ("(count \"\")", Number(0)),
@@ -1474,6 +4497,34 @@ mod tests {
             ("(read-string \"7;;`\")", Number(7)),
             ("(read-string \"7;; &()*+,-./:;<=>?@[]^_{|}~\")", Number(7)),
             ("(read-string \";; comment\")", Nil),
+            (
+                "(read-string \"1 (2) 3\" {:all true})",
+                list_with_values(vec![
+                    Number(1),
+                    list_with_values(vec![Number(2)]),
+                    Number(3),
+                ]),
+            ),
+            ("(read-string \"\" {:all true})", List(PersistentList::new())),
+            ("(read-string \"1 2\" {:all false})", Number(2)),
+            ("(read-string \";; comment\" {:eof :done})", Keyword("done".to_string(), None)),
+            ("(read-string \"1\" {:eof :done})", Number(1)),
+            (
+                "(read-string \"#point [1 2]\" {:readers {'point (fn* [v] (* (first v) (nth v 1)))}})",
+                Number(2),
+            ),
+            (
+                "(read-string \"[#point [1 2] #inst \\\"1970-01-01T00:00:00.042Z\\\"]\" {:readers {'point (fn* [v] (apply + v))}})",
+                vector_with_values(vec![Number(3), Instant(42)]),
+            ),
+            (
+                "(try* (read-string \"#point 1\" {:readers {'point (fn* [v] (throw (ex-info \"bad point\" {})))}}) (catch* e (ex-message e)))",
+                String("bad point".into()),
+            ),
+            (
+                "(try* (read-string \"#point 1\" {:readers {}}) (catch* e :unknown-tag))",
+                Keyword("unknown-tag".to_string(), None),
+            ),
             ("(eval (list + 1 2 3))", Number(6)),
             ("(eval (read-string \"(+ 2 3)\"))", Number(5)),
             (
@@ -1484,32 +4535,87 @@ mod tests {
                 "(let* [b 12] (do (eval (read-string \"(def! aa 7)\")) aa))",
                 Number(7),
             ),
-            ("(str)", String("".to_string())),
-            ("(str \"\")", String("".to_string())),
-            ("(str \"hi\" 3 :foo)", String("hi3:foo".to_string())),
-            ("(str \"hi   \" 3 :foo)", String("hi   3:foo".to_string())),
-            ("(str [])", String("[]".to_string())),
-            ("(str [\"hi\"])", String("[\"hi\"]".to_string())),
+            ("(parse-long \"42\")", Number(42)),
+            ("(parse-long \"-42\")", Number(-42)),
+            ("(parse-long \"+7\")", Number(7)),
+            ("(parse-long \"ff\" 16)", Number(255)),
+            ("(parse-long \"4.2\")", Nil),
+            ("(parse-long \"\")", Nil),
+            ("(parse-long \"99999999999999999999\")", Nil),
+            ("(number->string 42)", String("42".into())),
+            ("(number->string -255 16)", String("-ff".into())),
+            ("(number->string 5 2)", String("101".into())),
+            ("(number->string 0 36)", String("0".into())),
+            (
+                "(number->string (- -9223372036854775807 1) 2)",
+                String(format!("-1{}", "0".repeat(63)).into()),
+            ),
+            (
+                "(try* (number->string 1 37) (catch* e :invalid))",
+                Keyword("invalid".to_string(), None),
+            ),
+            ("(str)", String("".into())),
+            ("(str \"\")", String("".into())),
+            ("(str \"hi\" 3 :foo)", String("hi3:foo".into())),
+            ("(str \"hi   \" 3 :foo)", String("hi   3:foo".into())),
+            ("(str [])", String("[]".into())),
+            ("(str [\"hi\"])", String("[\"hi\"]".into())),
             (
                 "(str \"A\" {:abc \"val\"} \"Z\")",
-                String("A{:abc \"val\"}Z".to_string()),
+                String("A{:abc \"val\"}Z".into()),
             ),
             (
                 "(str true \".\" false \".\" nil \".\" :keyw \".\" 'symb)",
-                String("true.false.nil.:keyw.symb".to_string()),
+                String("true.false.nil.:keyw.symb".into()),
             ),
             (
                 "(str true \".\" false \".\" nil \".\" :keyw \".\" 'symb)",
-                String("true.false.nil.:keyw.symb".to_string()),
+                String("true.false.nil.:keyw.symb".into()),
             ),
             (
                 "(pr-str \"A\" {:abc \"val\"} \"Z\")",
-                String("\"A\" {:abc \"val\"} \"Z\"".to_string()),
+                String("\"A\" {:abc \"val\"} \"Z\"".into()),
             ),
             (
                 "(pr-str true \".\" false \".\" nil \".\" :keyw \".\" 'symb)",
-                String("true \".\" false \".\" nil \".\" :keyw \".\" symb".to_string()),
+                String("true \".\" false \".\" nil \".\" :keyw \".\" symb".into()),
+            ),
+            (
+                "(print-str \"A\" [\"b\" :c] \"Z\")",
+                String("A [b :c] Z".into()),
             ),
+            (
+                "(add-print-method! (fn* [x] (= x :secret)) (fn* [x] \"<redacted>\")) (pr-str [1 :secret {:a :secret}])",
+                String("[1 <redacted> {:a <redacted>}]".into()),
+            ),
+            (
+                "(add-print-method! (fn* [x] (= x :secret)) (fn* [x] \"<redacted>\")) (str \"x\" #{:secret} (atom :secret))",
+                String("x#{<redacted>}(atom <redacted>)".into()),
+            ),
+            (
+                "(add-print-method! map? (fn* [x] \"<map>\")) (print-str \"a\" '({:a 1}))",
+                String("a (<map>)".into()),
+            ),
+            (
+                "(add-print-method! map? (fn* [x] \"<map>\")) (add-print-method! map? (fn* [x] \"<m>\")) (pr-str {:a 1})",
+                String("<m>".into()),
+            ),
+            ("[(= map? map?) (= map? list?) (contains? #{map?} map?)]", vector_with_values(vec![Bool(true), Bool(false), Bool(true)])),
+            ("(alias 'h 'hash) (pr-str :hash/x 'hash/y)", String(":hash/x hash/y".into())),
+            ("(alias 'h 'hash) (def! *print-aliases* true) (pr-str :hash/x 'hash/y :other/z [:hash/w])", String("::h/x hash/y :other/z [::h/w]".into())),
+            ("(alias 'hs 'hash) (alias 'h 'hash) (alias 'g 'hash) (def! *print-aliases* true) (pr-str :hash/x)", String("::g/x".into())),
+            ("(alias 'h:a 'hash) (def! *print-aliases* true) (pr-str :hash/x)", String(":hash/x".into())),
+            ("(alias 'h 'hash) (def! *print-aliases* true) (def! *print-namespace-maps* true) (let* [v [:hash/x 'hash/y {:hash/a 1 :hash/b :hash/c} {:_/d 2}]] (= v (read-string (pr-str v))))", Bool(true)),
+            ("(alias 'h 'hash) (= [(read-string \"::h/x\") (read-string \"#::h{:a 1 :_/b 2 :c/d 3}\") (read-string \"#:n{:a 1}\")] [:hash/x {:hash/a 1 :b 2 :c/d 3} {:n/a 1}])", Bool(true)),
+            ("(try* (read-string \"::h/x\") (catch* e :unknown-alias))", Keyword("unknown-alias".to_string(), None)),
+            ("(alias 'h 'hash) (def! *print-aliases* true) (str :hash/x)", String("::h/x".into())),
+            ("(alias 'h 'hash) (= (ns-aliases) {'h 'hash})", Bool(true)),
+            ("(alias 'h 'hash) (h/hex-encode \"a\")", String("61".into())),
+            ("(try* (alias 'h 'does-not-exist) (catch* e :missing))", Keyword("missing".to_string(), None)),
+            ("(try* (alias \"h\" 'hash) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(pr-str {:a/x 1 :a/y 2})", String("{:a/x 1, :a/y 2}".into())),
+            ("(def! *print-namespace-maps* true) (pr-str {:a/x 1 :a/y 2} {:a/x 1 :b/y 2} {:a/x 1 :y 2} {})", String("#:a{:x 1, :y 2} {:a/x 1, :b/y 2} {:a/x 1, :y 2} {}".into())),
+            ("(alias 'h 'hash) (def! *print-namespace-maps* true) (def! *print-aliases* true) (pr-str {:hash/x {:hash/y 1}})", String("#::h{:x #::h{:y 1}}".into())),
             (
                 "(cons 1 (list))",
                 list_with_values([Number(1)].iter().cloned()),
@@ -1687,9 +4793,9 @@ mod tests {
                 "(rest (cons 10 [11 12]))",
                 list_with_values(vec![Number(11), Number(12)]),
             ),
-            ("(apply str [1 2 3])", String("123".to_string())),
-            ("(apply str '(1 2 3))", String("123".to_string())),
-            ("(apply str 0 1 2 '(1 2 3))", String("012123".to_string())),
+            ("(apply str [1 2 3])", String("123".into())),
+            ("(apply str '(1 2 3))", String("123".into())),
+            ("(apply str 0 1 2 '(1 2 3))", String("012123".into())),
             ("(apply + '(2 3))", Number(5)),
             ("(apply + 4 '(5))", Number(9)),
             ("(apply + 4 [5])", Number(9)),
@@ -1732,6 +4838,14 @@ mod tests {
                 list_with_values(vec![Number(1), Number(1)]),
             ),
             ("(= () (map str ()))", Bool(true)),
+            ("(= (map str \"abc\") '(\"a\" \"b\" \"c\"))", Bool(true)),
+            ("(= (map first {:a 1 :b 2}) '(:a :b))", Bool(true)),
+            ("(= (map inc #{1}) '(2))", Bool(true)),
+            ("(map inc nil)", list_with_values(vec![])),
+            ("(apply str \"ab\" \"cd\")", String("abcd".into())),
+            ("(apply + #{1 2})", Number(3)),
+            ("(apply (fn* [entry] (last entry)) {:a 1})", Number(1)),
+            ("(try* (map inc 1) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
             ("(nil? nil)", Bool(true)),
             ("(nil? true)", Bool(false)),
             ("(nil? false)", Bool(false)),
@@ -1757,6 +4871,28 @@ mod tests {
             ("(symbol \"hi\")", Symbol("hi".to_string(), None)),
             ("(keyword \"hi\")", Keyword("hi".to_string(), None)),
             ("(keyword :hi)", Keyword("hi".to_string(), None)),
+            ("(keyword 'hi)", Keyword("hi".to_string(), None)),
+            ("(keyword 'a/hi)", Keyword("hi".to_string(), Some("a".to_string()))),
+            (
+                "(keyword \"a\" \"hi\")",
+                Keyword("hi".to_string(), Some("a".to_string())),
+            ),
+            ("(keyword nil \"hi\")", Keyword("hi".to_string(), None)),
+            ("(= :a/hi (keyword \"a\" \"hi\"))", Bool(true)),
+            ("(symbol 'hi)", Symbol("hi".to_string(), None)),
+            (
+                "(symbol \"a\" \"hi\")",
+                Symbol("hi".to_string(), Some("a".to_string())),
+            ),
+            ("(symbol nil \"hi\")", Symbol("hi".to_string(), None)),
+            ("(name :hi)", String("hi".into())),
+            ("(name :a/hi)", String("hi".into())),
+            ("(name 'a/hi)", String("hi".into())),
+            ("(name \"hi\")", String("hi".into())),
+            ("(namespace :a/hi)", String("a".into())),
+            ("(namespace 'a/hi)", String("a".into())),
+            ("(namespace :hi)", Nil),
+            ("(namespace (symbol \"b\" \"c\"))", String("b".into())),
             ("(keyword? :a)", Bool(true)),
             ("(keyword? false)", Bool(false)),
             ("(keyword? 'abc)", Bool(false)),
@@ -1779,6 +4915,21 @@ mod tests {
             ("(vector? [1 2])", Bool(true)),
             ("(vector? '(1 2))", Bool(false)),
             ("(vector? :hi)", Bool(false)),
+            ("(bytes? (byte-array 2))", Bool(true)),
+            ("(bytes? [])", Bool(false)),
+            ("(count (byte-array 3))", Number(3)),
+            ("(byte-array [104 105])", bytes_with_values(vec![104, 105])),
+            ("(= (byte-array [104 105]) #bytes \"aGk=\")", Bool(true)),
+            ("(aget #bytes \"aGk=\" 1)", Number(105)),
+            ("(aset (byte-array 2) 1 255)", bytes_with_values(vec![0, 255])),
+            ("(let* [b (byte-array 1)] (aset b 0 1) b)", bytes_with_values(vec![0])),
+            ("(bytes->string #bytes \"aGk=\")", String("hi".into())),
+            ("(bytes->string (byte-array [104 105]) :base64)", String("aGk=".into())),
+            ("(bytes->string (byte-array [0 171 255]) :hex)", String("00abff".into())),
+            ("(string->bytes \"hi\")", bytes_with_values(vec![104, 105])),
+            ("(string->bytes \"aGk=\" :base64)", bytes_with_values(vec![104, 105])),
+            ("(string->bytes \"00abff\" :hex)", bytes_with_values(vec![0, 171, 255])),
+            ("(pr-str (string->bytes \"hi\"))", String("#bytes \"aGk=\"".into())),
             ("(= [] (vector))", Bool(true)),
             ("(sequential? '(1 2))", Bool(true)),
             ("(sequential? [1 2])", Bool(true)),
@@ -1786,6 +4937,43 @@ mod tests {
             ("(sequential? nil)", Bool(false)),
             ("(sequential? \"abc\")", Bool(false)),
             ("(sequential? sequential?)", Bool(false)),
+            ("(coll? [])", Bool(true)),
+            ("(coll? '(1))", Bool(true)),
+            ("(coll? {})", Bool(true)),
+            ("(coll? #{})", Bool(true)),
+            ("(coll? nil)", Bool(false)),
+            ("(coll? \"abc\")", Bool(false)),
+            ("(seqable? nil)", Bool(true)),
+            ("(seqable? \"abc\")", Bool(true)),
+            ("(seqable? #{1})", Bool(true)),
+            ("(seqable? 1)", Bool(false)),
+            ("(seqable? :a)", Bool(false)),
+            ("(empty [1 2])", Vector(PersistentVector::new())),
+            ("(empty '(1 2))", List(PersistentList::new())),
+            ("(empty {:a 1})", Map(PersistentMap::new())),
+            ("(empty #{1})", Set(PersistentSet::new())),
+            ("(empty nil)", Nil),
+            ("(empty 1)", Nil),
+            (
+                "(mapv inc '(1 2))",
+                vector_with_values(vec![Number(2), Number(3)]),
+            ),
+            ("(mapv inc nil)", Vector(PersistentVector::new())),
+            (
+                "(def! x 10) (mapv (fn* [a] (+ a x)) [1 2])",
+                vector_with_values(vec![Number(11), Number(12)]),
+            ),
+            (
+                "(filterv (fn* [a] (< a 3)) '(1 2 3 4))",
+                vector_with_values(vec![Number(1), Number(2)]),
+            ),
+            (
+                "(filterv identity [1 nil false 2])",
+                vector_with_values(vec![Number(1), Number(2)]),
+            ),
+            ("(filterv nil? [])", Vector(PersistentVector::new())),
+            ("(= (filterv (fn* [c] (= c \"a\")) \"banana\") [\"a\" \"a\" \"a\"])", Bool(true)),
+            ("(= (mapv last {:a 1 :b 2}) [1 2])", Bool(true)),
             ("(hash-map)", Map(PersistentMap::new())),
             (
                 "(hash-map :a 2)",
@@ -1843,6 +5031,11 @@ mod tests {
                 "(assoc {} :a nil)",
                 map_with_values(vec![(Keyword("a".to_string(), None), Nil)]),
             ),
+            (
+                "(assoc nil :a 1)",
+                map_with_values(vec![(Keyword("a".to_string(), None), Number(1))]),
+            ),
+            ("(dissoc nil :a)", Nil),
             ("(dissoc {})", map_with_values([].iter().cloned())),
             ("(dissoc {} :a)", map_with_values([].iter().cloned())),
             (
@@ -1858,15 +5051,75 @@ mod tests {
                 map_with_values([].iter().cloned()),
             ),
             ("(count (keys (assoc {} :b 2 :c 3)))", Number(2)),
+            (
+                "(assoc [1 2 3] 0 10)",
+                vector_with_values(vec![Number(10), Number(2), Number(3)]),
+            ),
+            (
+                "(assoc [1 2] 2 3 0 0)",
+                vector_with_values(vec![Number(0), Number(2), Number(3)]),
+            ),
+            ("(update {:a 1} :a inc)", map_with_values(vec![(Keyword("a".to_string(), None), Number(2))])),
+            ("(update {} :a (fn* [a] (nil? a)))", map_with_values(vec![(Keyword("a".to_string(), None), Bool(true))])),
+            ("(update nil :a (fn* [a b] b) 3)", map_with_values(vec![(Keyword("a".to_string(), None), Number(3))])),
+            (
+                "(update [1 2 3] 1 + 10 100)",
+                vector_with_values(vec![Number(1), Number(112), Number(3)]),
+            ),
+            (
+                "(subvec [1 2 3 4] 1)",
+                vector_with_values(vec![Number(2), Number(3), Number(4)]),
+            ),
+            (
+                "(subvec [1 2 3 4] 1 3)",
+                vector_with_values(vec![Number(2), Number(3)]),
+            ),
+            ("(subvec [1 2 3 4] 4)", Vector(PersistentVector::new())),
+            ("(peek [1 2 3])", Number(3)),
+            ("(peek '(1 2 3))", Number(1)),
+            ("(peek [])", Nil),
+            ("(peek nil)", Nil),
+            (
+                "(pop [1 2 3])",
+                vector_with_values(vec![Number(1), Number(2)]),
+            ),
+            ("(pop '(1 2 3))", list_with_values(vec![Number(2), Number(3)])),
+            ("(pop nil)", Nil),
             ("(get {:a 1} :a)", Number(1)),
             ("(get {:a 1} :b)", Nil),
             ("(get nil :b)", Nil),
+            ("(:a {:a 1})", Number(1)),
+            ("(:b {:a 1})", Nil),
+            ("(:b {:a 1} 2)", Number(2)),
+            ("(:a nil)", Nil),
+            ("(:a #{:a})", Keyword("a".to_string(), None)),
+            ("(let* [k :a m {:a 3}] (k m))", Number(3)),
+            ("(mapv :a [{:a 1} {:a 2} {}])", vector_with_values(vec![Number(1), Number(2), Nil])),
+            ("(update {:a {:b 1}} :a :b)", map_with_values(vec![(Keyword("a".to_string(), None), Number(1))])),
+            ("(get {:a 1} :b 2)", Number(2)),
+            ("(get {:a nil} :a 2)", Nil),
+            ("(get nil :b 2)", Number(2)),
+            ("(get [1 2 3] 1)", Number(2)),
+            ("(get [1 2 3] 3)", Nil),
+            ("(get [1 2 3] -1 :none)", Keyword("none".to_string(), None)),
+            ("(get [1 2 3] :a)", Nil),
+            ("(get #{1 2} 2)", Number(2)),
+            ("(get #{1 2} 3 4)", Number(4)),
+            ("(get \"abc\" 1)", String("b".into())),
+            ("(get \"abc\" 3)", Nil),
             ("(contains? {:a 1} :b)", Bool(false)),
             ("(contains? {:a 1} :a)", Bool(true)),
             ("(contains? {:abc nil} :abc)", Bool(true)),
             ("(contains? nil :abc)", Bool(false)),
             ("(contains? nil 'abc)", Bool(false)),
             ("(contains? nil [1 2 3])", Bool(false)),
+            ("(contains? [1 2 3] 0)", Bool(true)),
+            ("(contains? [1 2 3] 3)", Bool(false)),
+            ("(contains? [1 2 3] :a)", Bool(false)),
+            ("(contains? #{1 2} 1)", Bool(true)),
+            ("(contains? #{1 2} 3)", Bool(false)),
+            ("(contains? \"abc\" 2)", Bool(true)),
+            ("(contains? \"abc\" 3)", Bool(false)),
             ("(keyword? (nth (keys {:abc 123 :def 456}) 0))", Bool(true)),
             ("(keyword? (nth (vals {123 :abc 456 :def}) 0))", Bool(true)),
             ("(keys {})", Nil),
@@ -1879,8 +5132,30 @@ mod tests {
                 "(= (set '(:a :c)) (set (keys {:a 1 :b 2 :c 3})))",
                 Bool(false),
             ),
+            (
+                "(keys {:c 1 :a 2 :b 3})",
+                list_with_values(vec![
+                    Keyword("a".to_string(), None),
+                    Keyword("b".to_string(), None),
+                    Keyword("c".to_string(), None),
+                ]),
+            ),
+            (
+                "(seq #{3 1 2})",
+                list_with_values(vec![Number(1), Number(2), Number(3)]),
+            ),
+            ("(pr-str {:b 2 :a 1 :c 3})", String("{:a 1, :b 2, :c 3}".into())),
             ("(vals {})", Nil),
             ("(vals nil)", Nil),
+            ("(vals {:c 1 :a 2 :b 3})", list_with_values(vec![Number(2), Number(3), Number(1)])),
+            ("(def! m {:c 1 :a 2 :b 3 \"d\" 4 5 6}) (= (keys m) (map key m))", Bool(true)),
+            ("(def! m {:c 1 :a 2 :b 3 \"d\" 4 5 6}) (= (vals m) (map val m))", Bool(true)),
+            ("(key (first (seq {:a 1})))", Keyword("a".to_string(), None)),
+            ("(val (first (seq {:a 1})))", Number(1)),
+            ("(map-entry? (first (seq {:a 1})))", Bool(true)),
+            ("(map-entry? [1 2 3])", Bool(false)),
+            ("(map-entry? {:a 1})", Bool(false)),
+            ("(try* (key [1]) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
             (
                 "(= (set '(1 2 3)) (set (vals {:a 1 :b 2 :c 3})))",
                 Bool(true),
@@ -1896,6 +5171,18 @@ mod tests {
             ("(not [])", Bool(false)),
             ("(not '(1 2 3))", Bool(false)),
             ("(not nil)", Bool(true)),
+            ("(not 0)", Bool(false)),
+            ("(try* (not) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(boolean nil)", Bool(false)),
+            ("(boolean false)", Bool(false)),
+            ("(boolean 0)", Bool(true)),
+            ("(boolean \"\")", Bool(true)),
+            ("(boolean [])", Bool(true)),
+            ("(some? nil)", Bool(false)),
+            ("(some? false)", Bool(true)),
+            ("(some? 0)", Bool(true)),
+            ("(if 0 :yes :no)", Keyword("yes".to_string(), None)),
+            ("(cond false :no 0 :yes)", Keyword("yes".to_string(), None)),
             ("(not true)", Bool(false)),
             ("(not false)", Bool(true)),
             ("(not 1)", Bool(false)),
@@ -1909,7 +5196,7 @@ mod tests {
             // NOTE: these all rely on an _unguaranteed_ insertion order...
             (
                 "(set \"hi\")",
-                set_with_values(vec![String("h".to_string()), String("i".to_string())]),
+                set_with_values(vec![String("h".into()), String("i".into())]),
             ),
             ("(set '(1 2))", set_with_values(vec![Number(1), Number(2)])),
             (
@@ -2031,6 +5318,44 @@ mod tests {
                 "(conj #{1 2} 1 3 2 2 2 2 1)",
                 set_with_values(vec![Number(1), Number(2), Number(3)]),
             ),
+            ("(into)", Vector(PersistentVector::new())),
+            ("(into [1])", vector_with_values(vec![Number(1)])),
+            ("(into [] nil)", Vector(PersistentVector::new())),
+            (
+                "(into [1] '(2 3))",
+                vector_with_values(vec![Number(1), Number(2), Number(3)]),
+            ),
+            (
+                "(into '() [1 2])",
+                list_with_values(vec![Number(2), Number(1)]),
+            ),
+            (
+                "(into #{1} [1 2 2])",
+                set_with_values(vec![Number(1), Number(2)]),
+            ),
+            (
+                "(into {} [[:a 1] [:b 2]])",
+                map_with_values(vec![
+                    (Keyword("a".to_string(), None), Number(1)),
+                    (Keyword("b".to_string(), None), Number(2)),
+                ]),
+            ),
+            (
+                "(into {:a 1} {:b 2})",
+                map_with_values(vec![
+                    (Keyword("a".to_string(), None), Number(1)),
+                    (Keyword("b".to_string(), None), Number(2)),
+                ]),
+            ),
+            (
+                "(into [] \"ab\")",
+                vector_with_values(vec![String("a".into()), String("b".into())]),
+            ),
+            ("(into nil [1 2])", list_with_values([Number(2), Number(1)])),
+            ("(into {:a 1} [[:b 2] {:c 3 :a 4}])", map_with_values([(Keyword("a".to_string(), None), Number(4)), (Keyword("b".to_string(), None), Number(2)), (Keyword("c".to_string(), None), Number(3))])),
+            ("(into #{1} #{2 3})", set_with_values([Number(1), Number(2), Number(3)])),
+            ("(str (into (queue 1) [2 3]))", String("#queue [1 2 3]".into())),
+            ("(try* (into {} [1]) (catch* e (:type (ex-data e))))", Keyword("wrong-type".to_string(), None)),
             ("(macro? nil)", Bool(false)),
             ("(macro? true)", Bool(false)),
             ("(macro? false)", Bool(false)),
@@ -2045,13 +5370,17 @@ mod tests {
             ("(def! foo (fn* [a] a)) (macro? foo)", Bool(false)),
             ("(defmacro! foo (fn* [a] a)) (macro? foo)", Bool(true)),
             ("(number? (time-ms))", Bool(true)),
+            ("(let* [a (nano-time) b (nano-time)] (<= a b))", Bool(true)),
+            ("(let* [a (monotonic-ms) b (monotonic-ms)] (<= a b))", Bool(true)),
+            ("(time (+ 1 2))", Number(3)),
+            ("(let* [a 2] (time (* a 3)))", Number(6)),
             ("(seq nil)", Nil),
             ("(seq \"\")", Nil),
             (
                 "(seq \"ab\")",
-                list_with_values(vec![String("a".to_string()), String("b".to_string())]),
+                list_with_values(vec![String("a".into()), String("b".into())]),
             ),
-            ("(apply str (seq \"ab\"))", String("ab".to_string())),
+            ("(apply str (seq \"ab\"))", String("ab".into())),
             ("(seq '())", Nil),
             ("(seq '(1 2))", list_with_values(vec![Number(1), Number(2)])),
             ("(seq [])", Nil),
@@ -2063,16 +5392,310 @@ mod tests {
             ),
             ("(seq #{})", Nil),
             ("(= (set '(1 2)) (set (seq #{1 2})))", Bool(true)),
+            ("(== 1 1)", Bool(true)),
+            ("(== 1 2)", Bool(false)),
+            ("(try* (== 1 :a) (catch* e :error))", Keyword("error".to_string(), None)),
             ("(zero? 0)", Bool(true)),
             ("(zero? 10)", Bool(false)),
             ("(zero? -10)", Bool(false)),
+            ("(ex-message (ex-info \"boom\" {}))", String("boom".into())),
+            ("(ex-data (ex-info \"boom\" {:a 1}))", map_with_values(vec![(Keyword("a".to_string(), None), Number(1))])),
+            ("(ex-cause (ex-info \"boom\" {}))", Nil),
+            ("(ex-message (ex-cause (ex-info \"outer\" {} (ex-info \"inner\" {}))))", String("inner".into())),
+            ("(ex-data (ex-cause (ex-info \"outer\" {} (ex-info \"inner\" [1]))))", vector_with_values(vec![Number(1)])),
+            ("(ex-cause (ex-info \"outer\" {} nil))", Nil),
+            ("(ex-message 1)", Nil),
+            ("(ex-data nil)", Nil),
+            ("(ex-data (try* (throw :oops) (catch* e e)))", Keyword("oops".to_string(), None)),
+            ("(ex-message (try* (try* (throw (ex-info \"boom\" {})) (catch* e (throw (ex-info \"wrapped\" {} e)))) (catch* e (ex-cause e))))", String("boom".into())),
+            ("(string? (ex-message (try* (+ 1 :a) (catch* e e))))", Bool(true)),
+            ("(ex-data (try* (+ 1 :a) (catch* e e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("wrong-type".to_string(), None)), (Keyword("expected".to_string(), None), String("Number".into())), (Keyword("realized".to_string(), None), Keyword("a".to_string(), None))])),
+            ("(def! f (fn* [a] a)) (string? (ex-message (ex-cause (try* (f) (catch* e e)))))", Bool(true)),
+            ("(completions \"parse-\")", list_with_values(vec![Symbol("parse-long".to_string(), None)])),
+            ("(completions \"with-\")", list_with_values(vec![Symbol("with-meta".to_string(), None), Symbol("with-open".to_string(), None)])),
+            ("(def! with-foo 1) (completions \"with-f\")", list_with_values(vec![Symbol("with-foo".to_string(), None)])),
+            ("(completions \"does-not-exist\")", List(PersistentList::new())),
+            ("(resolve-symbol 'if)", map_with_values(vec![(Keyword("name".to_string(), None), String("if".into())), (Keyword("kind".to_string(), None), Keyword("special-form".to_string(), None))])),
+            ("(resolve-symbol 'does-not-exist)", Nil),
+            ("(:kind (resolve-symbol 'first))", Keyword("primitive".to_string(), None)),
+            ("(:namespace (resolve-symbol 'first))", String("core".into())),
+            ("(:namespace (resolve-symbol 'time/now))", String("time".into())),
+            ("(:kind (resolve-symbol 'defn))", Keyword("macro".to_string(), None)),
+            ("(def! f (fn* [a & more] a)) [(:kind (resolve-symbol 'f)) (:arity (resolve-symbol 'f)) (:variadic (resolve-symbol 'f))]", vector_with_values(vec![Keyword("fn".to_string(), None), Number(1), Bool(true)])),
+            ("(def! x 1) (:kind (resolve-symbol 'x))", Keyword("value".to_string(), None)),
+            ("(def! x) (:kind (resolve-symbol 'x))", Keyword("unbound".to_string(), None)),
+            ("(walk inc (fn* [xs] (apply + xs)) [1 2 3])", Number(9)),
+            ("(walk identity identity '(1 2))", list_with_values(vec![Number(1), Number(2)])),
+            ("(walk (fn* [e] [(nth e 1) (first e)]) identity {:a 1})", map_with_values(vec![(Number(1), Keyword("a".to_string(), None))])),
+            ("(= (postwalk (fn* [x] (if (number? x) (inc x) x)) [1 {:a 2} #{3} '(4 [5])]) [2 {:a 3} #{4} '(5 [6])])", Bool(true)),
+            ("(postwalk (fn* [x] (if (vector? x) (count x) x)) [[1 2] [3]])", Number(2)),
+            ("(= (prewalk (fn* [x] (if (vector? x) (vec (rest x)) x)) [1 [2 3 4] [5 6]]) [[3 4] [6]])", Bool(true)),
+            ("(= (prewalk (fn* [x] (if (map? x) (dissoc x :secret) x)) {:a {:secret 1 :b 2} :secret 3}) {:a {:b 2}})", Bool(true)),
+            ("(postwalk identity 7)", Number(7)),
+            ("(try* (walk (fn* [e] 1) identity {:a 1}) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(= (hash {:a [1 \"b\"]}) (hash {:a [1 \"b\"]}))", Bool(true)),
+            ("(= (hash #{1 2 3}) (hash (conj #{3 2} 1)))", Bool(true)),
+            ("(= (hash [1 2]) (hash [2 1]))", Bool(false)),
+            ("(hash nil)", Number(Nil.stable_hash() as i64)),
+            ("(diff 1 1)", vector_with_values(vec![Nil, Nil, Number(1)])),
+            ("(diff 1 2)", vector_with_values(vec![Number(1), Number(2), Nil])),
+            ("(diff [1 2] {:a 1})", vector_with_values(vec![vector_with_values(vec![Number(1), Number(2)]), map_with_values(vec![(Keyword("a".to_string(), None), Number(1))]), Nil])),
+            ("(= (diff [1 2 3] [1 5 3]) [[nil 2] [nil 5] [1 nil 3]])", Bool(true)),
+            ("(= (diff '(1 2) [1 2 3]) [nil [nil nil 3] [1 2]])", Bool(true)),
+            ("(= (diff #{1 2} #{2 3}) [#{1} #{3} #{2}])", Bool(true)),
+            ("(= (diff {:a 1 :b 2} {:a 1 :c 3}) [{:b 2} {:c 3} {:a 1}])", Bool(true)),
+            ("(= (diff {:a {:x 1 :y 2}} {:a {:x 1 :y 3}}) [{:a {:y 2}} {:a {:y 3}} {:a {:x 1}}])", Bool(true)),
+            ("(= (diff {:a nil} {:a nil :b nil}) [nil {:b nil} {:a nil}])", Bool(true)),
+            ("(= (diff {:a 1} {:a nil}) [{:a 1} {:a nil} nil])", Bool(true)),
+            ("(count \"héllo\")", Number(5)),
+            ("(count \"日本\")", Number(2)),
+            ("(nth \"héllo\" 1)", String("é".into())),
+            ("(try* (nth \"hé\" 2) (catch* e :out-of-bounds))", Keyword("out-of-bounds".to_string(), None)),
+            ("(seq \"hé\")", list_with_values([String("h".into()), String("é".into())])),
+            ("(subs \"héllo\" 1)", String("éllo".into())),
+            ("(subs \"héllo\" 1 3)", String("él".into())),
+            ("(subs \"héllo\" 5)", String("".into())),
+            ("(try* (subs \"héllo\" 6) (catch* e :out-of-bounds))", Keyword("out-of-bounds".to_string(), None)),
+            ("(try* (subs \"héllo\" 3 1) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(/ -7 2)", Number(-4)),
+            ("(/ 7 -2)", Number(-3)),
+            ("(try* (/ 1 0) (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("arithmetic-error".to_string(), None)), (Keyword("operands".to_string(), None), vector_with_values([Number(1)]))])),
+            ("(try* (/ 0) (catch* e (ex-message e)))", String("cannot divide 1 by zero".into())),
+            ("(try* (/ (- -9223372036854775807 1) -1) (catch* e (:type (ex-data e))))", Keyword("arithmetic-error".to_string(), None)),
+            ("(try* (+ 9223372036854775807 1) (catch* e (:type (ex-data e))))", Keyword("arithmetic-error".to_string(), None)),
+            ("(defn f [x] x) (try* (f 1 2) (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("arity-error".to_string(), None)), (Keyword("name".to_string(), None), String("f".into())), (Keyword("expected".to_string(), None), String("[x]".into())), (Keyword("realized".to_string(), None), Number(2))])),
+            ("(try* (subs \"abc\") (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("arity-error".to_string(), None)), (Keyword("name".to_string(), None), String("subs".into())), (Keyword("expected".to_string(), None), vector_with_values([Number(2), Number(3)])), (Keyword("realized".to_string(), None), Number(1))])),
+            ("(defn f [x] x) (def! calls (atom 0)) (try* (f (swap! calls inc) (swap! calls inc)) (catch* e nil)) @calls", Number(2)),
+            ("(try* (first 1) (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("wrong-type".to_string(), None)), (Keyword("expected".to_string(), None), String("List, Vector, Queue, Nil".into())), (Keyword("realized".to_string(), None), Number(1))])),
+            ("(try* (nth [1] 3) (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("index-out-of-bounds".to_string(), None)), (Keyword("index".to_string(), None), Number(3)), (Keyword("length".to_string(), None), Number(1))])),
+            ("(try* undefined-var (catch* e (ex-data e)))", map_with_values([(Keyword("type".to_string(), None), Keyword("missing-var".to_string(), None)), (Keyword("name".to_string(), None), String("undefined-var".into())), (Keyword("namespace".to_string(), None), String("core".into()))])),
+            ("(try* (let* [a] a) (catch* e (:type (ex-data e))))", Keyword("syntax-error".to_string(), None)),
+            ("(= (intern-data [{:name \"a\" :tags #{:x}} {:name \"a\" :tags #{:x}}]) [{:name \"a\" :tags #{:x}} {:name \"a\" :tags #{:x}}])", Bool(true)),
+            ("(intern-data [[1 2] '(1 2)])", vector_with_values([vector_with_values([Number(1), Number(2)]), list_with_values([Number(1), Number(2)])])),
+            ("(intern-data 1)", Number(1)),
+            ("(read-string \"[\\\"a\\\" \\\"a\\\"]\" {:intern true})", vector_with_values([String("a".into()), String("a".into())])),
+            ("(filter keyword? [1 :a 2 :b])", list_with_values([Keyword("a".to_string(), None), Keyword("b".to_string(), None)])),
+            ("(remove keyword? [1 :a 2 :b])", list_with_values([Number(1), Number(2)])),
+            ("(take 2 [1 2 3])", list_with_values([Number(1), Number(2)])),
+            ("(take 5 '(1 2))", list_with_values([Number(1), Number(2)])),
+            ("(drop 2 [1 2 3])", list_with_values([Number(3)])),
+            ("(drop -1 [1 2])", list_with_values([Number(1), Number(2)])),
+            ("((comp inc inc) 1)", Number(3)),
+            ("((comp) 1)", Number(1)),
+            ("((complement zero?) 0)", Bool(false)),
+            ("(conj)", vector_with_values([])),
+            ("(conj [1])", vector_with_values([Number(1)])),
+            ("(transduce (map inc) + [1 2 3])", Number(9)),
+            ("(transduce (filter zero?) + 10 [1 0 3])", Number(10)),
+            ("(transduce (comp (map inc) (filter (fn* [x] (> x 2))) (take 2)) conj [1 2 3 4 5 6])", vector_with_values([Number(3), Number(4)])),
+            ("(into [] (map inc) [1 2 3])", vector_with_values([Number(2), Number(3), Number(4)])),
+            ("(into [] (comp (drop 1) (remove zero?)) '(0 1 0 2))", vector_with_values([Number(1), Number(2)])),
+            ("(into '() (take 0) [1 2])", list_with_values([])),
+            ("(into {} (map (fn* [e] [(nth e 1) (first e)])) {:a 1})", map_with_values([(Number(1), Keyword("a".to_string(), None))])),
+            ("(let* [seen (atom 0)] (into [] (comp (map (fn* [x] (swap! seen inc) x)) (take 2)) [1 2 3 4 5]) @seen)", Number(2)),
+            ("(reduce + [1 2 3 4])", Number(10)),
+            ("(reduce + 10 [1 2 3 4])", Number(20)),
+            ("(reduce + [])", Number(0)),
+            ("(reduce + [5])", Number(5)),
+            ("(reduce + 5 nil)", Number(5)),
+            ("(reduce conj [] '(1 2 3))", vector_with_values([Number(1), Number(2), Number(3)])),
+            ("(reduce (fn* [acc x] (if (> x 2) (reduced acc) (+ acc x))) [1 2 3 4])", Number(3)),
+            ("(let* [calls (atom 0)] (reduce (fn* [_ x] (swap! calls inc) (if (= x 2) (reduced x) x)) 0 [1 2 3 4]) @calls)", Number(2)),
+            ("(reduce (fn* [acc x] (reduced [acc x])) :init [1 2])", vector_with_values([Keyword("init".to_string(), None), Number(1)])),
+            ("(reduce-kv (fn* [acc k v] (if (= v :b) (reduced k) acc)) nil [:a :b :c])", Number(1)),
+            ("(reduced? (reduced 1))", Bool(true)),
+            ("(reduced? 1)", Bool(false)),
+            ("@(reduced 1)", Number(1)),
+            ("(= (reduced 1) (reduced 1))", Bool(true)),
+            ("(let* [a (atom 1) w (weak-ref a)] @@w)", Number(1)),
+            ("(let* [a (atom 1) w (weak-ref a)] (reset! a 2) @@w)", Number(2)),
+            ("@(weak-ref (atom 1))", Nil),
+            ("(weak-ref? (weak-ref (atom 1)))", Bool(true)),
+            ("(weak-ref? (atom 1))", Bool(false)),
+            ("(let* [a (atom 1)] (= (weak-ref a) (weak-ref a)))", Bool(true)),
+            ("(let* [a (atom 1) b (atom 1)] (= (weak-ref a) (weak-ref b)))", Bool(false)),
+            ("(let* [a (atom 1) w (weak-ref a)] (str w))", String("<weak-ref (atom 1)>".into())),
+            ("(str (weak-ref (atom 1)))", String("<weak-ref collected>".into())),
+            ("(do (def! weak-target 3) @@(weak-ref #'weak-target))", Number(3)),
+            ("(take-last 2 [1 2 3])", list_with_values(vec![Number(2), Number(3)])),
+            ("(take-last 5 '(1 2))", list_with_values(vec![Number(1), Number(2)])),
+            ("[(take-last 0 [1]) (take-last 2 nil) (take-last 2 [])]", vector_with_values(vec![Nil, Nil, Nil])),
+            ("(take-last 2 \"abc\")", list_with_values(vec![String("b".into()), String("c".into())])),
+            ("(drop-last [1 2 3])", list_with_values(vec![Number(1), Number(2)])),
+            ("(drop-last 2 '(1 2 3))", list_with_values(vec![Number(1)])),
+            ("[(drop-last 5 [1 2]) (drop-last -1 [1])]", vector_with_values(vec![list_with_values(vec![]), list_with_values(vec![Number(1)])])),
+            ("(nthrest '(1 2 3) 1)", list_with_values(vec![Number(2), Number(3)])),
+            ("(nthrest [1 2 3] 2)", list_with_values(vec![Number(3)])),
+            ("[(nthrest [1 2] 0) (nthrest [1 2] 5) (nthrest (queue 1 2) 1)]", vector_with_values(vec![vector_with_values(vec![Number(1), Number(2)]), list_with_values(vec![]), list_with_values(vec![Number(2)])])),
+            ("(slice [1 2 3 4 5] 1 3)", vector_with_values(vec![Number(2), Number(3)])),
+            ("(slice '(1 2 3 4 5) 3)", list_with_values(vec![Number(4), Number(5)])),
+            ("[(slice [1 2 3] 2 10) (slice [1 2 3] 5 10) (slice [1 2 3] 2 1)]", vector_with_values(vec![vector_with_values(vec![Number(3)]), vector_with_values(vec![]), vector_with_values(vec![])])),
+            ("(try* (slice 1 0) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (take-last :a [1]) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("[(queue? (queue 1)) (queue? [1]) (coll? (queue)) (sequential? (queue))]", vector_with_values(vec![Bool(true), Bool(false), Bool(true), Bool(true)])),
+            ("(let* [q (conj (queue 1) 2 3)] [(peek q) (peek (pop q)) (count q) (first q)])", vector_with_values(vec![Number(1), Number(2), Number(3), Number(1)])),
+            ("(pr-str (pop (conj (queue) 1 2 3)))", String("#queue [2 3]".into())),
+            ("(pr-str #queue [1 (+ 1 1) \"a\"])", String("#queue [1 2 \"a\"]".into())),
+            ("(str (into (queue) [1 2]))", String("#queue [1 2]".into())),
+            ("(seq (queue 1 2))", list_with_values(vec![Number(1), Number(2)])),
+            ("[(seq (queue)) (peek (queue)) (empty? (queue)) (rest (queue 1 2))]", vector_with_values(vec![Nil, Nil, Bool(true), list_with_values(vec![Number(2)])])),
+            ("(map inc (queue 1 2))", list_with_values(vec![Number(2), Number(3)])),
+            ("[(= (queue 1 2) (queue 1 2)) (= (queue 1 2) (queue 2 1)) (queue? (empty (queue 1)))]", vector_with_values(vec![Bool(true), Bool(false), Bool(true)])),
+            ("(try* (pop (queue)) (catch* e :empty))", Keyword("empty".to_string(), None)),
+            ("(let* [q (queue 1 2)] [(vec q) (nth q 1) (last q) (cons 0 q) (concat q [3])])", vector_with_values(vec![vector_with_values(vec![Number(1), Number(2)]), Number(2), Number(2), list_with_values(vec![Number(0), Number(1), Number(2)]), list_with_values(vec![Number(1), Number(2), Number(3)])])),
+            ("[(= #queue [1 2] [1 2]) (= '(1 2) (queue 1 2)) (= (queue 1 2) [2 1]) (= (queue) [])]", vector_with_values(vec![Bool(true), Bool(true), Bool(false), Bool(true)])),
+            ("[(set (queue 1 1)) (pr-str (postwalk identity (queue 1 [2])))]", vector_with_values(vec![set_with_values(vec![Number(1)]), String("#queue [1 [2]]".into())])),
+            ("(defn bfs [graph start] (let* [step (fn* [q seen order] (if (empty? q) order (let* [node (peek q) next (filterv (fn* [n] (not (contains? seen n))) (get graph node))] (step (into (pop q) next) (into seen next) (conj order node)))))] (step (queue start) #{start} []))) (bfs {:a [:b :c] :b [:d] :c [:d] :d []} :a)", vector_with_values(vec![Keyword("a".to_string(), None), Keyword("b".to_string(), None), Keyword("c".to_string(), None), Keyword("d".to_string(), None)])),
+            ("(map + [1 2 3] '(10 20))", list_with_values(vec![Number(11), Number(22)])),
+            ("(map vector [:a :b] [1 2] \"xy\")", list_with_values(vec![vector_with_values(vec![Keyword("a".to_string(), None), Number(1), String("x".into())]), vector_with_values(vec![Keyword("b".to_string(), None), Number(2), String("y".into())])])),
+            ("(mapv + [1 2] [3 4] [5 6])", vector_with_values(vec![Number(9), Number(12)])),
+            ("(map + [1 2] nil)", list_with_values(vec![])),
+            ("(try* (map) (catch* e :arity))", Keyword("arity".to_string(), None)),
+            ("(reduce-kv (fn* [acc k v] (+ acc (* k v))) 0 [5 6 7])", Number(20)),
+            ("(reduce-kv (fn* [acc k v] (assoc acc v k)) {} {:a 1 :b 2})", map_with_values(vec![(Number(1), Keyword("a".to_string(), None)), (Number(2), Keyword("b".to_string(), None))])),
+            ("(reduce-kv (fn* [acc k v] (conj acc k)) [] nil)", vector_with_values(vec![])),
+            ("(try* (reduce-kv + 0 '(1 2)) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(def calls (atom 0)) (def f (memoize (fn* [x y] (swap! calls inc) (+ x y)))) [(f 1 2) (f 1 2) (f 2 1) @calls]", vector_with_values(vec![Number(3), Number(3), Number(3), Number(2)])),
+            ("(def f (memoize (fn* [] nil))) [(f) (f)]", vector_with_values(vec![Nil, Nil])),
+            ("(defn-memo fib [n] (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2))))) (fib 80)", Number(23416728348467685)),
+            ("(defn-memo f \"doubles\" [x] (* 2 x)) [(f 2) (:doc (meta #'f))]", vector_with_values(vec![Number(4), String("doubles".into())])),
+            ("(lru-lookup (lru-cache 2) :a)", Nil),
+            ("(lru-lookup (lru-put (lru-cache 2) :a 1) :a)", vector_with_values(vec![Number(1)])),
+            ("(let* [c (lru-cache 1)] (lru-put c :a 1) (lru-put c :b 2) [(lru-lookup c :a) (lru-lookup c :b)])", vector_with_values(vec![Nil, vector_with_values(vec![Number(2)])])),
+            ("(str (lru-put (lru-cache 2) :a 1))", String("<lru-cache 1/2>".into())),
+            ("(try* (lru-lookup {} :a) (catch* e (:type (ex-data e))))", Keyword("wrong-type".to_string(), None)),
+            ("(let* [c (lru-put (lru-put (lru-put (lru-put (lru-cache 2) :a 1) :b 2) :a 3) :c 4)] [(lru-lookup c :a) (lru-lookup c :b) (lru-lookup c :c)])", vector_with_values(vec![vector_with_values(vec![Number(3)]), Nil, vector_with_values(vec![Number(4)])])),
+            ("(def calls (atom [])) (def f (memoize-lru (fn* [x] (swap! calls conj x) x) 2)) (f 1) (f 2) (f 1) (f 3) (f 2) (f 1) @calls", vector_with_values(vec![Number(1), Number(2), Number(3), Number(2), Number(1)])),
+            ("(= (select-keys {:a 1 :b 2 :c 3} [:a :c :d]) {:a 1 :c 3})", Bool(true)),
+            ("(= (select-keys nil [:a]) {})", Bool(true)),
+            ("(= (select-keys {:a 1} #{}) {})", Bool(true)),
+            ("(try* (select-keys [1 2] [0]) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(= (rename-keys {:a 1 :b 2 :c 3} {:a :x :b :y}) {:x 1 :y 2 :c 3})", Bool(true)),
+            ("(= (rename-keys {:a 1 :b 2} {:a :b :b :a}) {:a 2 :b 1})", Bool(true)),
+            ("(= (rename-keys {:a 1} {:z :y}) {:a 1})", Bool(true)),
+            ("(= (deep-merge {:a {:b 1 :c 2} :d 1} {:a {:c 3 :e 4} :d 2}) {:a {:b 1 :c 3 :e 4} :d 2})", Bool(true)),
+            ("(= (deep-merge {:a {:b 1}} {:a 2}) {:a 2})", Bool(true)),
+            ("(= (deep-merge {:a 1} nil {:b 2}) {:a 1 :b 2})", Bool(true)),
+            ("(deep-merge)", Nil),
+            ("(deep-merge nil nil)", Nil),
+            ("(= (deep-merge-with + {:a {:b 1} :c 1} {:a {:b 2} :c 10 :d 5}) {:a {:b 3} :c 11 :d 5})", Bool(true)),
+            ("(= (deep-merge-with (fn* [a b] a) {:a {:b [1]}} {:a {:b [2]}}) {:a {:b [1]}})", Bool(true)),
+            ("(try* (deep-merge {:a 1} [1]) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(count-nodes 1)", Number(1)),
+            ("(count-nodes [])", Number(1)),
+            ("(count-nodes [1 [2 3] {:a \"b\"} #{nil}])", Number(10)),
+            ("(count-nodes '(1 (2)))", Number(4)),
+            ("(= (measure 1) {:nodes 1 :leaves 1 :depth 0 :max-count 0})", Bool(true)),
+            ("(= (measure [1 [2 3 4] {:a [5]}]) {:nodes 10 :leaves 6 :depth 3 :max-count 3})", Bool(true)),
+            ("(:depth (loop* [i 0 x 1] (if (< i 1000) (recur (inc i) [x]) (measure x))))", Number(1000)),
+            ("(count-nodes (loop* [i 0 x nil] (if (< i 1000) (recur (inc i) (list x)) x)))", Number(1001)),
+            ("(derive! :square :rect) (derive! :rect :shape) [(isa? :square :shape) (isa? :shape :square) (isa? :rect :rect) (isa? 1 1)]", vector_with_values(vec![Bool(true), Bool(false), Bool(true), Bool(true)])),
+            ("(derive! :square :rect) (derive! :red :color) [(isa? [:square :red] [:rect :color]) (isa? [:square] [:rect :color])]", vector_with_values(vec![Bool(true), Bool(false)])),
+            ("(derive! :square :rect) (derive! :square :polygon) (= (parents :square) #{:rect :polygon})", Bool(true)),
+            ("(derive! :square :rect) (derive! :rect :shape) (= (ancestors :square) #{:rect :shape})", Bool(true)),
+            ("(derive! :square :rect) (derive! :circle :shape) (derive! :rect :shape) (= (descendants :shape) #{:square :rect :circle})", Bool(true)),
+            ("[(parents :square) (ancestors :square) (descendants :square)]", vector_with_values(vec![Nil, Nil, Nil])),
+            ("(derive! 'square 'rect) (isa? 'square 'rect)", Bool(true)),
+            ("(derive! :square :rect) (derive! :rect :shape) (underive! :rect :shape) [(isa? :square :shape) (parents :rect)]", vector_with_values(vec![Bool(false), Nil])),
+            ("(derive! :square :rect) (try* (derive! :rect :square) (catch* e :cyclic))", Keyword("cyclic".to_string(), None)),
+            ("(try* (derive! :a :a) (catch* e :cyclic))", Keyword("cyclic".to_string(), None)),
+            ("(try* (derive! \"a\" :b) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
         ];
         run_eval_test(&test_cases);
     }
 
+    #[test]
+    fn test_intern_data() {
+        let mut interpreter = Interpreter::default();
+        let result = interpreter
+            .evaluate_from_source(
+                "(intern-data [{:id 1 :kind \"point\" :at [0 0]} {:id 2 :kind \"point\" :at [0 0]}])",
+            )
+            .unwrap();
+        let elems = match &result[0] {
+            Vector(elems) => elems.iter().collect::<Vec<_>>(),
+            other => panic!("expected a vector, found {}", other),
+        };
+        let field = |index: usize, name: &str| {
+            elems[index]
+                .get_path(&[name])
+                .cloned()
+                .expect("field is present")
+        };
+        match (field(0, "kind"), field(1, "kind")) {
+            (String(a), String(b)) => assert!(Rc::ptr_eq(&a, &b)),
+            other => panic!("expected strings, found {:?}", other),
+        }
+        match (field(0, "at"), field(1, "at")) {
+            // shared vectors hold their elements in the same nodes
+            (Vector(a), Vector(b)) => assert!(std::ptr::eq(&a[0], &b[0])),
+            other => panic!("expected vectors, found {:?}", other),
+        }
+    }
+
     #[test]
     fn test_core_macros() {
-        let test_cases = &[("(defn f [x] (let [y 29] (+ x y))) (f 1)", Number(30))];
+        let test_cases = &[
+            ("(defn f [x] (let [y 29] (+ x y))) (f 1)", Number(30)),
+            ("(defn f \"adds 29\" [x] (+ x 29)) (f 1)", Number(30)),
+            ("(defn f \"adds 29\" [x] (+ x 29)) (:doc (meta (var f)))", String("adds 29".into())),
+            ("(defn f [x] x) (meta #'f)", Nil),
+            ("(defn ^:private f \"doc\" [] 1) [(:private (meta #'f)) (:doc (meta #'f))]", vector_with_values(vec![Bool(true), String("doc".into())])),
+            ("(defn-memo ^:private f \"doc\" [] 1) [(f) (:private (meta #'f)) (:doc (meta #'f))]", vector_with_values(vec![Number(1), Bool(true), String("doc".into())])),
+            ("(defn f [^long x ^{:doc \"the y\"} y & ^:rest more] (+ x y (count more))) (f 1 2 3)", Number(4)),
+            ("((fn* [^String s] s) \"hi\")", String("hi".into())),
+            ("(str (fn* [x & more] x))", String("<fn* [x & more]>".into())),
+            ("(str ((fn* [x] (fn* [y] (+ x y))) 1))", String("<fn* [y] +captures>".into())),
+            ("(defmacro! m (fn* [a b] a)) (str @(var m))", String("<macro [a b]>".into())),
+            ("(defnk f [x & {:keys [a b] :or {b 2}}] [x a b]) (f 1 :a 3)", vector_with_values(vec![Number(1), Number(3), Number(2)])),
+            ("(defnk f [x & {:keys [a b] :or {b 2}}] [x a b]) (f 1 {:a 3 :b 4})", vector_with_values(vec![Number(1), Number(3), Number(4)])),
+            ("(defnk f [& {:keys [a] :as opts}] [a opts]) (f)", vector_with_values(vec![Nil, map_with_values(vec![])])),
+            ("(defnk f [& {:keys [a] :or {a (throw \"unused\")}}] a) (f :a nil)", Nil),
+            ("(defnk f \"named\" [& {:keys [a]}] a) [(f :a 1) (:doc (meta #'f))]", vector_with_values(vec![Number(1), String("named".into())])),
+            ("(defnk f [x] (+ x 1)) (f 1)", Number(2)),
+            ("(defnk f [& {:keys [a]}] a) (try* (f :a) (catch* e :odd))", Keyword("odd".to_string(), None)),
+            ("(and)", Bool(true)),
+            ("(and 1)", Number(1)),
+            ("(and 1 2 3)", Number(3)),
+            ("(and 1 nil 3)", Nil),
+            ("(and 1 false 3)", Bool(false)),
+            ("(def! a (atom 0)) (and false (reset! a 1)) @a", Number(0)),
+            ("(def! a (atom 0)) (and (swap! a inc) true) @a", Number(1)),
+            ("(or)", Nil),
+            ("(or false)", Bool(false)),
+            ("(or nil false 3 4)", Number(3)),
+            ("(or nil false)", Bool(false)),
+            ("(def! a (atom 0)) (or 1 (reset! a 1)) @a", Number(0)),
+            ("(def! a (atom 0)) (or (swap! a inc) 2)", Number(1)),
+            ("(let* [x 5] (and (> x 1) (or (< x 3) x)))", Number(5)),
+            ("(let* [name \"sigil\"] (<< \"Hello ~{name}!\"))", String("Hello sigil!".into())),
+            ("(<< \"~{(+ 1 2)} and ~{(get {:a \\\"}\\\"} :a)}\")", String("3 and }".into())),
+            ("(<< \"no forms, ~~{x} and ~ alone\")", String("no forms, ~{x} and ~ alone".into())),
+            ("(<< \"\")", String("".into())),
+            ("(let* [xs [1 2]] (<< \"~{xs}:~{:k}\"))", String("[1 2]::k".into())),
+            ("(template-parts \"a ~{b} c\")", list_with_values(vec![String("a ".into()), Symbol("b".to_string(), None), String(" c".into())])),
+            ("(try* (template-parts \"~{x\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (template-parts \"~{x y}\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (template-parts \"~{}\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(with-open [] 1)", Number(1)),
+            ("(with-open [r nil] 1 2)", Number(2)),
+            (
+                "(def closed (atom [])) (defn resource [n] {:n n :close (fn* [r] (swap! closed conj (:n r)))}) (with-open [a (resource 1) b (resource 2)] (+ (:n a) (:n b)))",
+                Number(3),
+            ),
+            (
+                "(def closed (atom [])) (defn resource [n] {:n n :close (fn* [r] (swap! closed conj (:n r)))}) (with-open [a (resource 1) b (resource 2)] nil) (deref closed)",
+                vector_with_values(vec![Number(2), Number(1)]),
+            ),
+            (
+                "(def closed (atom [])) (defn resource [n] {:n n :close (fn* [r] (swap! closed conj (:n r)))}) (try* (with-open [a (resource 1)] (throw :oops)) (catch* e [(ex-data e) (deref closed)]))",
+                vector_with_values(vec![
+                    Keyword("oops".to_string(), None),
+                    vector_with_values(vec![Number(1)]),
+                ]),
+            ),
+            ("(try* (close! 1) (catch* e :error))", Keyword("error".to_string(), None)),
+        ];
         run_eval_test(test_cases);
     }
 }
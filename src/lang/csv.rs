@@ -0,0 +1,260 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::namespace::Namespace;
+use crate::value::{map_with_values, vector_with_values, NativeFn, PrintMode, Value};
+use std::borrow::Cow;
+
+const NAME: &str = "csv";
+const BINDINGS: &[(&str, NativeFn)] = &[("parse", parse), ("write-str", write_str)];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+struct Options {
+    separator: char,
+    quote: char,
+    headers: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            separator: ',',
+            quote: '"',
+            headers: false,
+        }
+    }
+}
+
+// reads `{:separator "," :quote "\"" :headers false}` with any of the keys omitted
+fn options_from(value: Option<&Value>) -> EvaluationResult<Options> {
+    let mut options = Options::default();
+    let opts = match value {
+        None | Some(Value::Nil) => return Ok(options),
+        Some(Value::Map(opts)) => opts,
+        Some(other) => {
+            return Err(EvaluationError::WrongType {
+                expected: "Map, Nil",
+                realized: other.clone(),
+            })
+        }
+    };
+    let char_option = |name: &str, default: char| -> EvaluationResult<char> {
+        match opts.get(&Value::Keyword(name.to_string(), None)) {
+            None => Ok(default),
+            Some(Value::String(s)) if s.chars().count() == 1 => {
+                Ok(s.chars().next().expect("has one char"))
+            }
+            Some(other) => Err(EvaluationError::WrongType {
+                expected: "String of length 1",
+                realized: other.clone(),
+            }),
+        }
+    };
+    options.separator = char_option("separator", options.separator)?;
+    options.quote = char_option("quote", options.quote)?;
+    options.headers = opts
+        .get(&Value::Keyword("headers".to_string(), None))
+        .is_some_and(Value::is_truthy);
+    Ok(options)
+}
+
+// splits `input` into records of fields, or `None` if a quoted field is not terminated
+fn parse_records(input: &str, options: &Options) -> Option<Vec<Vec<String>>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut chars = input.chars().peekable();
+    // whether any of the current record has been read, so a trailing newline does not add a record
+    let mut in_record = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch == options.quote && field.is_empty() => {
+                in_record = true;
+                loop {
+                    match chars.next()? {
+                        ch if ch == options.quote => {
+                            if chars.peek() == Some(&options.quote) {
+                                chars.next();
+                                field.push(ch);
+                            } else {
+                                break;
+                            }
+                        }
+                        ch => field.push(ch),
+                    }
+                }
+            }
+            ch if ch == options.separator => {
+                in_record = true;
+                record.push(std::mem::take(&mut field));
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                in_record = false;
+            }
+            ch => {
+                in_record = true;
+                field.push(ch);
+            }
+        }
+    }
+    if in_record {
+        record.push(field);
+        records.push(record);
+    }
+    Some(records)
+}
+
+// `(csv/parse s)` or `(csv/parse s opts)`, returning a vector of records as vectors of strings
+// or, with `:headers true`, as maps from the keywords of the first record to each field
+fn parse(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(1..=2).contains(&args.len()) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let input = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "String",
+                realized: other.clone(),
+            })
+        }
+    };
+    let options = options_from(args.get(1))?;
+    let records = parse_records(input, &options)
+        .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), "CSV"))?;
+    let mut records = records.into_iter();
+    if !options.headers {
+        return Ok(vector_with_values(records.map(|record| {
+            vector_with_values(record.into_iter().map(|field| Value::String(field.into())))
+        })));
+    }
+    let headers = match records.next() {
+        Some(headers) => headers,
+        None => return Ok(vector_with_values([])),
+    };
+    Ok(vector_with_values(records.map(|record| {
+        map_with_values(headers.iter().zip(record).map(|(header, field)| {
+            (
+                Value::Keyword(header.clone(), None),
+                Value::String(field.into()),
+            )
+        }))
+    })))
+}
+
+fn write_field(buffer: &mut String, value: &Value, options: &Options) {
+    let field = match value {
+        Value::String(s) => Cow::Borrowed(&**s),
+        Value::Nil => Cow::Borrowed(""),
+        other => Cow::Owned(other.to_string_in_mode(PrintMode::Display)),
+    };
+    let needs_quotes = field
+        .chars()
+        .any(|ch| ch == options.separator || ch == options.quote || ch == '\n' || ch == '\r');
+    if needs_quotes {
+        buffer.push(options.quote);
+        for ch in field.chars() {
+            if ch == options.quote {
+                buffer.push(ch);
+            }
+            buffer.push(ch);
+        }
+        buffer.push(options.quote);
+    } else {
+        buffer.push_str(&field);
+    }
+}
+
+// `(csv/write-str records)` or `(csv/write-str records opts)` where `records` is a sequence
+// of sequences of fields, quoting fields as needed and ending each record with a newline
+fn write_str(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !(1..=2).contains(&args.len()) {
+        return Err(EvaluationError::WrongArityRange {
+            min: 1,
+            max: 2,
+            realized: args.len(),
+        });
+    }
+    let options = options_from(args.get(1))?;
+    let records: Vec<_> = match &args[0] {
+        Value::Nil => vec![],
+        Value::List(records) => records.iter().collect(),
+        Value::Vector(records) => records.iter().collect(),
+        Value::Queue(records) => records.iter().collect(),
+        other => {
+            return Err(EvaluationError::WrongType {
+                expected: "Nil, List, Vector, Queue",
+                realized: other.clone(),
+            })
+        }
+    };
+    let mut buffer = String::new();
+    for record in records {
+        let fields: Vec<_> = match record {
+            Value::List(fields) => fields.iter().collect(),
+            Value::Vector(fields) => fields.iter().collect(),
+            Value::Queue(fields) => fields.iter().collect(),
+            other => {
+                return Err(EvaluationError::WrongType {
+                    expected: "List, Vector, Queue",
+                    realized: other.clone(),
+                })
+            }
+        };
+        for (index, field) in fields.into_iter().enumerate() {
+            if index > 0 {
+                buffer.push(options.separator);
+            }
+            write_field(&mut buffer, field, &options);
+        }
+        buffer.push('\n');
+    }
+    Ok(Value::String(buffer.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_csv() {
+        let test_cases = vec![
+            ("(= (csv/parse \"\") [])", Bool(true)),
+            ("(= (csv/parse \"a,b\\n1,2\\n\") [[\"a\" \"b\"] [\"1\" \"2\"]])", Bool(true)),
+            ("(= (csv/parse \"a,b\r\\n1,2\") [[\"a\" \"b\"] [\"1\" \"2\"]])", Bool(true)),
+            ("(= (csv/parse \"a,,\\n\\n\") [[\"a\" \"\" \"\"] [\"\"]])", Bool(true)),
+            (
+                "(= (csv/parse \"\\\"a,b\\\",\\\"say \\\"\\\"hi\\\"\\\"\\\",\\\"x\\ny\\\"\") [[\"a,b\" \"say \\\"hi\\\"\" \"x\\ny\"]])",
+                Bool(true),
+            ),
+            ("(= (csv/parse \"a;'b;c'\" {:separator \";\" :quote \"'\"}) [[\"a\" \"b;c\"]])", Bool(true)),
+            (
+                "(= (csv/parse \"name,age\\nada,36\\nbob,7\" {:headers true}) [{:name \"ada\" :age \"36\"} {:name \"bob\" :age \"7\"}])",
+                Bool(true),
+            ),
+            ("(try* (csv/parse \"\\\"open\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (csv/parse \"a\" {:separator \"ab\"}) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(csv/write-str [])", String("".into())),
+            ("(csv/write-str [[\"a\" 1 :b nil] '(\"x,y\")])", String("a,1,:b,\n\"x,y\"\n".into())),
+            ("(csv/write-str [[\"say \\\"hi\\\"\" \"a\\nb\"]])", String("\"say \"\"hi\"\"\",\"a\nb\"\n".into())),
+            ("(csv/write-str [[1 2]] {:separator \"\t\"})", String("1\t2\n".into())),
+            ("(= (csv/parse (csv/write-str [[\"a,b\" \"c\\\"d\"]])) [[\"a,b\" \"c\\\"d\"]])", Bool(true)),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::lang::core::{decode_hex, encode_hex};
+use crate::namespace::Namespace;
+use crate::value::{NativeFn, Value};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+const NAME: &str = "hash";
+const BINDINGS: &[(&str, NativeFn)] = &[
+    ("sha256", sha256),
+    ("sha256-bytes", sha256_bytes),
+    ("sha1", sha1),
+    ("sha1-bytes", sha1_bytes),
+    ("md5", md5),
+    ("md5-bytes", md5_bytes),
+    ("hmac-sha256", hmac_sha256),
+    ("hmac-sha256-bytes", hmac_sha256_bytes),
+    ("base64-encode", base64_encode),
+    ("base64-decode", base64_decode),
+    ("hex-encode", hex_encode),
+    ("hex-decode", hex_decode),
+];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+// strings are taken as their UTF-8 encoding
+fn bytes_of(value: &Value) -> EvaluationResult<&[u8]> {
+    match value {
+        Value::String(s) => Ok(s.as_bytes()),
+        Value::Bytes(bytes) => Ok(bytes),
+        other => Err(EvaluationError::WrongType {
+            expected: "String, Bytes",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn string_of(value: &Value) -> EvaluationResult<&str> {
+    match value {
+        Value::String(s) => Ok(s),
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// the digests are given as lowercase hex strings, or by the `-bytes` fns as bytes
+fn hex_of(bytes: Vec<u8>) -> Value {
+    Value::String(encode_hex(&bytes).into())
+}
+
+fn bytes_value(bytes: Vec<u8>) -> Value {
+    Value::Bytes(bytes.into())
+}
+
+fn digest<D: Digest>(args: &[Value]) -> EvaluationResult<Vec<u8>> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    let input = bytes_of(&args[0])?;
+    Ok(D::digest(input).to_vec())
+}
+
+fn sha256(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Sha256>(args).map(hex_of)
+}
+
+fn sha256_bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Sha256>(args).map(bytes_value)
+}
+
+fn sha1(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Sha1>(args).map(hex_of)
+}
+
+fn sha1_bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Sha1>(args).map(bytes_value)
+}
+
+fn md5(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Md5>(args).map(hex_of)
+}
+
+fn md5_bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    digest::<Md5>(args).map(bytes_value)
+}
+
+// `(hash/hmac-sha256 key message)`
+fn hmac(args: &[Value]) -> EvaluationResult<Vec<u8>> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    let key = bytes_of(&args[0])?;
+    let message = bytes_of(&args[1])?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(message);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn hmac_sha256(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    hmac(args).map(hex_of)
+}
+
+fn hmac_sha256_bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    hmac(args).map(bytes_value)
+}
+
+fn base64_encode(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::String(BASE64.encode(bytes_of(&args[0])?).into()))
+}
+
+fn base64_decode(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    BASE64
+        .decode(string_of(&args[0])?)
+        .map(|bytes| Value::Bytes(bytes.into()))
+        .map_err(|_| EvaluationError::CannotDecode(args[0].clone(), "base64"))
+}
+
+fn hex_encode(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::String(encode_hex(bytes_of(&args[0])?).into()))
+}
+
+fn hex_decode(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    decode_hex(string_of(&args[0])?)
+        .map(|bytes| Value::Bytes(bytes.into()))
+        .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), "hex"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::{bytes_with_values, Value::*};
+
+    #[test]
+    fn test_hash() {
+        let test_cases = vec![
+            (
+                "(hash/sha256 \"abc\")",
+                String("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into()),
+            ),
+            (
+                "(hash/sha256 (string->bytes \"abc\"))",
+                String("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into()),
+            ),
+            (
+                "(hash/hex-encode (hash/sha256-bytes \"abc\"))",
+                String("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into()),
+            ),
+            (
+                "(hash/sha1 \"abc\")",
+                String("a9993e364706816aba3e25717850c26c9cd0d89d".into()),
+            ),
+            ("(count (hash/sha1-bytes \"abc\"))", Number(20)),
+            (
+                "(hash/md5 \"\")",
+                String("d41d8cd98f00b204e9800998ecf8427e".into()),
+            ),
+            (
+                "(hash/md5-bytes \"\")",
+                bytes_with_values(vec![
+                    0xd4, 0x1d, 0x8c, 0xd9, 0x8f, 0x00, 0xb2, 0x04, 0xe9, 0x80, 0x09, 0x98, 0xec,
+                    0xf8, 0x42, 0x7e,
+                ]),
+            ),
+            (
+                "(hash/hmac-sha256 \"key\" \"The quick brown fox jumps over the lazy dog\")",
+                String("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8".into()),
+            ),
+            (
+                "(hash/hex-encode (hash/hmac-sha256-bytes \"key\" \"The quick brown fox jumps over the lazy dog\"))",
+                String("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8".into()),
+            ),
+            ("(hash/base64-encode \"hello\")", String("aGVsbG8=".into())),
+            ("(hash/base64-decode \"aGVsbG8=\")", bytes_with_values(b"hello".to_vec())),
+            ("(hash/hex-encode (byte-array [0 171 255]))", String("00abff".into())),
+            ("(hash/hex-decode \"00abff\")", bytes_with_values(vec![0, 171, 255])),
+            ("(try* (hash/hex-decode \"0\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (hash/base64-decode \"!\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (hash/sha256 1) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (hash/md5-bytes) (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
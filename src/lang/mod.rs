@@ -2,3 +2,21 @@
 
 // Contains the `core` namespace
 pub mod core;
+// Contains the `csv` namespace
+pub mod csv;
+// Contains the `hash` namespace
+pub mod hash;
+// Contains the `str` namespace
+pub mod string;
+// Contains the `sys` namespace
+pub mod sys;
+// Contains the `time` namespace
+pub mod time;
+// Contains the `toml` namespace
+#[cfg(feature = "toml")]
+pub mod toml;
+// Contains the `uuid` namespace
+pub mod uuid;
+// Contains the `yaml` namespace
+#[cfg(feature = "yaml")]
+pub mod yaml;
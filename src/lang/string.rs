@@ -0,0 +1,109 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::namespace::Namespace;
+use crate::value::{bytes_with_values, char_string, list_with_values, NativeFn, Value};
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+const NAME: &str = "str";
+const BINDINGS: &[(&str, NativeFn)] = &[
+    ("chars", chars),
+    ("bytes", bytes),
+    ("code-points", code_points),
+    #[cfg(feature = "graphemes")]
+    ("graphemes", graphemes),
+];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+fn string_arg(args: &[Value]) -> EvaluationResult<&str> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// the chars of the string, each as a string of one char
+fn chars(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let s = string_arg(args)?;
+    Ok(list_with_values(s.chars().map(char_string)))
+}
+
+// the UTF-8 encoding of the string
+fn bytes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let s = string_arg(args)?;
+    Ok(bytes_with_values(s.bytes()))
+}
+
+// the Unicode scalar value of each char of the string
+fn code_points(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let s = string_arg(args)?;
+    Ok(list_with_values(
+        s.chars().map(|c| Value::Number(u32::from(c) as i64)),
+    ))
+}
+
+// the extended grapheme clusters of the string, e.g. a letter with its combining accents
+#[cfg(feature = "graphemes")]
+fn graphemes(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let s = string_arg(args)?;
+    Ok(list_with_values(
+        s.graphemes(true).map(|g| Value::String(g.into())),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::{bytes_with_values, list_with_values, Value::*};
+
+    #[test]
+    fn test_str() {
+        let mut test_cases = vec![
+            (
+                "(str/chars \"añb\")",
+                list_with_values(vec![
+                    String("a".into()),
+                    String("ñ".into()),
+                    String("b".into()),
+                ]),
+            ),
+            ("(str/chars \"\")", list_with_values(vec![])),
+            (
+                "(str/bytes \"añ\")",
+                bytes_with_values(vec![0x61, 0xc3, 0xb1]),
+            ),
+            (
+                "(str/code-points \"a€\")",
+                list_with_values(vec![Number(97), Number(8364)]),
+            ),
+            (
+                "(try* (str/chars 1) (catch* e :invalid))",
+                Keyword("invalid".to_string(), None),
+            ),
+        ];
+        if cfg!(feature = "graphemes") {
+            test_cases.push((
+                "(str/graphemes \"e\u{301}a\")",
+                list_with_values(vec![String("e\u{301}".into()), String("a".into())]),
+            ));
+        }
+        run_eval_test(&test_cases);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter, InterpreterError};
+use crate::namespace::Namespace;
+use crate::value::{NativeFn, Value};
+
+const NAME: &str = "sys";
+const BINDINGS: &[(&str, NativeFn)] = &[("exit", exit)];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+// `(sys/exit)` or `(sys/exit status)` stops evaluation, leaving the host to exit with `status`
+fn exit(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    let status = match args {
+        [] => 0,
+        [Value::Number(n)] => i32::try_from(*n).map_err(|_| EvaluationError::WrongType {
+            expected: "Number in the range of an exit status",
+            realized: args[0].clone(),
+        })?,
+        [other] => {
+            return Err(EvaluationError::WrongType {
+                expected: "Number",
+                realized: other.clone(),
+            })
+        }
+        _ => {
+            return Err(EvaluationError::WrongArityRange {
+                min: 0,
+                max: 1,
+                realized: args.len(),
+            })
+        }
+    };
+    Err(InterpreterError::Exit(status).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{EvaluationError, InterpreterError};
+    use crate::Interpreter;
+
+    #[test]
+    fn test_exit() {
+        let cases = [
+            ("(sys/exit)", 0),
+            ("(sys/exit 3)", 3),
+            ("(do (sys/exit 2) (sys/exit 4))", 2),
+            ("(try* (sys/exit 5) (catch* e 1))", 5),
+        ];
+        for (source, expected) in cases {
+            let mut interpreter = Interpreter::default();
+            match interpreter.evaluate_from_source(source) {
+                Err(EvaluationError::Interpreter(InterpreterError::Exit(status))) => {
+                    assert_eq!(status, expected)
+                }
+                other => panic!("`{}` should exit but gave {:?}", source, other),
+            }
+        }
+        let mut interpreter = Interpreter::default();
+        assert!(interpreter
+            .evaluate_from_source("(sys/exit 4294967296)")
+            .unwrap_err()
+            .exit_status()
+            .is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter, InterpreterError};
+use crate::namespace::Namespace;
+use crate::value::{format_instant, parse_instant, NativeFn, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const NAME: &str = "time";
+const BINDINGS: &[(&str, NativeFn)] = &[
+    ("now", now),
+    ("plus-ms", plus_ms),
+    ("diff-ms", diff_ms),
+    ("epoch-ms", epoch_ms),
+    ("from-epoch-ms", from_epoch_ms),
+    ("format", format),
+    ("parse", parse),
+];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+fn now(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    let duration = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| -> EvaluationError { InterpreterError::SystemTimeError(err).into() })?;
+    Ok(Value::Instant(duration.as_millis() as i64))
+}
+
+fn plus_ms(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match (&args[0], &args[1]) {
+        (Value::Instant(t), Value::Number(ms)) => t
+            .checked_add(*ms)
+            .map(Value::Instant)
+            .ok_or(EvaluationError::Overflow(*t, *ms)),
+        (Value::Instant(_), other) => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+        (other, _) => Err(EvaluationError::WrongType {
+            expected: "Instant",
+            realized: other.clone(),
+        }),
+    }
+}
+
+// milliseconds elapsed from the second instant to the first
+fn diff_ms(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 2 {
+        return Err(EvaluationError::WrongArity {
+            expected: 2,
+            realized: args.len(),
+        });
+    }
+    match (&args[0], &args[1]) {
+        (Value::Instant(a), Value::Instant(b)) => a
+            .checked_sub(*b)
+            .map(Value::Number)
+            .ok_or(EvaluationError::Underflow(*a, *b)),
+        (Value::Instant(_), other) | (other, _) => Err(EvaluationError::WrongType {
+            expected: "Instant",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn epoch_ms(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Instant(t) => Ok(Value::Number(*t)),
+        other => Err(EvaluationError::WrongType {
+            expected: "Instant",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn from_epoch_ms(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Number(ms) => Ok(Value::Instant(*ms)),
+        other => Err(EvaluationError::WrongType {
+            expected: "Number",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn format(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::Instant(t) => format_instant(*t)
+            .map(|s| Value::String(s.into()))
+            .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), "RFC3339")),
+        other => Err(EvaluationError::WrongType {
+            expected: "Instant",
+            realized: other.clone(),
+        }),
+    }
+}
+
+fn parse(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => parse_instant(s)
+            .map(Value::Instant)
+            .ok_or_else(|| EvaluationError::CannotDecode(args[0].clone(), "RFC3339")),
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_time() {
+        let test_cases = vec![
+            ("(inst? (time/now))", Bool(true)),
+            ("(inst? 1)", Bool(false)),
+            (
+                "(time/plus-ms #inst \"1970-01-01T00:00:00.000Z\" 1500)",
+                Instant(1500),
+            ),
+            (
+                "(time/diff-ms #inst \"1970-01-01T00:00:01.500Z\" #inst \"1970-01-01T00:00:00.000Z\")",
+                Number(1500),
+            ),
+            ("(time/epoch-ms (time/from-epoch-ms 42))", Number(42)),
+            (
+                "(time/format (time/from-epoch-ms 1500))",
+                String("1970-01-01T00:00:01.500Z".into()),
+            ),
+            (
+                "(time/parse \"1970-01-01T00:00:01.500+00:00\")",
+                Instant(1500),
+            ),
+            (
+                "(= (time/parse \"2021-06-01T12:00:00Z\") #inst \"2021-06-01T12:00:00.000Z\")",
+                Bool(true),
+            ),
+            (
+                "(pr-str (time/from-epoch-ms 0))",
+                String("#inst \"1970-01-01T00:00:00.000Z\"".into()),
+            ),
+            (
+                "(str (time/from-epoch-ms 0))",
+                String("1970-01-01T00:00:00.000Z".into()),
+            ),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
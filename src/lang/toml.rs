@@ -0,0 +1,94 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::namespace::Namespace;
+use crate::value::{map_with_values, parse_instant, vector_with_values, NativeFn, Value};
+
+const NAME: &str = "toml";
+const BINDINGS: &[(&str, NativeFn)] = &[("parse", parse)];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+// tables become maps with keyword keys and datetimes with an offset become instants
+fn to_value(value: ::toml::Value) -> EvaluationResult<Value> {
+    match value {
+        ::toml::Value::String(s) => Ok(Value::String(s.into())),
+        ::toml::Value::Integer(n) => Ok(Value::Number(n)),
+        ::toml::Value::Float(f) => Err(EvaluationError::CannotDecode(
+            Value::String(f.to_string().into()),
+            "an integer",
+        )),
+        ::toml::Value::Boolean(b) => Ok(Value::Bool(b)),
+        ::toml::Value::Datetime(t) => {
+            let t = t.to_string();
+            Ok(parse_instant(&t)
+                .map(Value::Instant)
+                .unwrap_or(Value::String(t.into())))
+        }
+        ::toml::Value::Array(elems) => Ok(vector_with_values(
+            elems
+                .into_iter()
+                .map(to_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        ::toml::Value::Table(table) => Ok(map_with_values(
+            table
+                .into_iter()
+                .map(|(k, v)| Ok((Value::Keyword(k, None), to_value(v)?)))
+                .collect::<Result<Vec<_>, EvaluationError>>()?,
+        )),
+    }
+}
+
+fn parse(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => {
+            let table = s
+                .parse::<::toml::Table>()
+                .map_err(|_| EvaluationError::CannotDecode(args[0].clone(), "TOML"))?;
+            to_value(::toml::Value::Table(table))
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_toml() {
+        let test_cases = vec![
+            ("(= (toml/parse \"\") {})", Bool(true)),
+            (
+                "(= (toml/parse \"name = \\\"sigil\\\"\\nport = 8080\\ndebug = true\\ntags = [\\\"a\\\", \\\"b\\\"]\") {:name \"sigil\" :port 8080 :debug true :tags [\"a\" \"b\"]})",
+                Bool(true),
+            ),
+            (
+                "(= (toml/parse \"[server]\\nhost = \\\"localhost\\\"\\n[[users]]\\nid = 1\\n[[users]]\\nid = 2\") {:server {:host \"localhost\"} :users [{:id 1} {:id 2}]})",
+                Bool(true),
+            ),
+            ("(:at (toml/parse \"at = 1970-01-01T00:00:01.5Z\"))", Instant(1500)),
+            ("(:on (toml/parse \"on = 1979-05-27\"))", String("1979-05-27".into())),
+            ("(try* (toml/parse \"a = \") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (toml/parse \"a = 1.5\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
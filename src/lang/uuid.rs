@@ -0,0 +1,77 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::namespace::Namespace;
+use crate::value::{NativeFn, Value};
+use ::uuid::Uuid;
+
+const NAME: &str = "uuid";
+const BINDINGS: &[(&str, NativeFn)] = &[("random", random), ("parse", parse)];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+fn random(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if !args.is_empty() {
+        return Err(EvaluationError::WrongArity {
+            expected: 0,
+            realized: args.len(),
+        });
+    }
+    Ok(Value::Uuid(Uuid::new_v4()))
+}
+
+fn parse(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => Uuid::parse_str(s)
+            .map(Value::Uuid)
+            .map_err(|_| EvaluationError::CannotDecode(args[0].clone(), "UUID")),
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_uuid() {
+        let test_cases = vec![
+            ("(uuid? (uuid/random))", Bool(true)),
+            ("(uuid? \"67e55044-10b1-426f-9247-bb680e5fe0c8\")", Bool(false)),
+            ("(= (uuid/random) (uuid/random))", Bool(false)),
+            (
+                "(= (uuid/parse \"67e55044-10b1-426f-9247-bb680e5fe0c8\") #uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\")",
+                Bool(true),
+            ),
+            (
+                "(str (uuid/parse \"67E55044-10B1-426F-9247-BB680E5FE0C8\"))",
+                String("67e55044-10b1-426f-9247-bb680e5fe0c8".into()),
+            ),
+            (
+                "(pr-str (uuid/parse \"67e55044-10b1-426f-9247-bb680e5fe0c8\"))",
+                String("#uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\"".into()),
+            ),
+            (
+                "(str [#uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\"])",
+                String("[#uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\"]".into()),
+            ),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
+use crate::namespace::Namespace;
+use crate::value::{map_with_values, vector_with_values, NativeFn, Value};
+
+const NAME: &str = "yaml";
+const BINDINGS: &[(&str, NativeFn)] = &[("parse", parse)];
+
+// loads the namespace represented by this Rust module into `interpreter`
+pub fn loader(interpreter: &mut Interpreter) -> EvaluationResult<()> {
+    let mut namespace = Namespace::new(NAME);
+    for (k, f) in BINDINGS.iter() {
+        let value = Value::Primitive(*f);
+        namespace.intern(k, &value).expect("can intern");
+    }
+    interpreter.load_namespace(namespace)
+}
+
+// mappings become maps where string keys are made keywords; tags are dropped
+fn to_value(value: serde_yaml::Value) -> EvaluationResult<Value> {
+    match value {
+        serde_yaml::Value::Null => Ok(Value::Nil),
+        serde_yaml::Value::Bool(b) => Ok(Value::Bool(b)),
+        serde_yaml::Value::Number(n) => n.as_i64().map(Value::Number).ok_or_else(|| {
+            EvaluationError::CannotDecode(Value::String(n.to_string().into()), "an integer")
+        }),
+        serde_yaml::Value::String(s) => Ok(Value::String(s.into())),
+        serde_yaml::Value::Sequence(elems) => Ok(vector_with_values(
+            elems
+                .into_iter()
+                .map(to_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        serde_yaml::Value::Mapping(mapping) => Ok(map_with_values(
+            mapping
+                .into_iter()
+                .map(|(k, v)| {
+                    let k = match k {
+                        serde_yaml::Value::String(k) => Value::Keyword(k, None),
+                        other => to_value(other)?,
+                    };
+                    Ok((k, to_value(v)?))
+                })
+                .collect::<Result<Vec<_>, EvaluationError>>()?,
+        )),
+        serde_yaml::Value::Tagged(tagged) => to_value(tagged.value),
+    }
+}
+
+fn parse(_: &mut Interpreter, args: &[Value]) -> EvaluationResult<Value> {
+    if args.len() != 1 {
+        return Err(EvaluationError::WrongArity {
+            expected: 1,
+            realized: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => {
+            let value = serde_yaml::from_str(s)
+                .map_err(|_| EvaluationError::CannotDecode(args[0].clone(), "YAML"))?;
+            to_value(value)
+        }
+        other => Err(EvaluationError::WrongType {
+            expected: "String",
+            realized: other.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::run_eval_test;
+    use crate::value::Value::*;
+
+    #[test]
+    fn test_yaml() {
+        let test_cases = vec![
+            ("(yaml/parse \"\")", Nil),
+            ("(yaml/parse \"42\")", Number(42)),
+            (
+                "(= (yaml/parse \"name: sigil\\nport: 8080\\ndebug: true\\nnothing: ~\\ntags:\\n  - a\\n  - b\") {:name \"sigil\" :port 8080 :debug true :nothing nil :tags [\"a\" \"b\"]})",
+                Bool(true),
+            ),
+            (
+                "(= (yaml/parse \"server:\\n  host: localhost\\n1: one\") {:server {:host \"localhost\"} 1 \"one\"})",
+                Bool(true),
+            ),
+            ("(yaml/parse \"!custom 7\")", Number(7)),
+            ("(try* (yaml/parse \"a: [\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+            ("(try* (yaml/parse \"a: 1.5\") (catch* e :invalid))", Keyword("invalid".to_string(), None)),
+        ];
+        run_eval_test(&test_cases);
+    }
+}
@@ -1,8 +1,20 @@
+pub mod analysis;
 mod analyzer;
+mod audit;
+mod cache;
+mod dependencies;
+mod diagnostics;
+pub mod docs;
+mod handle;
+mod hierarchy;
+mod hints;
 mod interpreter;
 mod lang;
+mod module;
 mod namespace;
 mod reader;
+mod recovery;
+mod streams;
 mod value;
 
 #[cfg(test)]
@@ -13,5 +25,16 @@ mod repl;
 #[cfg(feature = "repl")]
 pub use repl::{repl_with_interpreter, StdRepl};
 
-pub use interpreter::Interpreter;
-pub use reader::read;
+pub use audit::AuditEvent;
+pub use dependencies::VarChange;
+pub use diagnostics::{Diagnostic, DiagnosticKind};
+pub use handle::FnHandle;
+pub use interpreter::{
+    CallContext, DeferredSource, ErrorPolicy, EvaluationError, Interpreter, InterpreterBuilder,
+};
+pub use module::{expand_path, FilesystemResolver, InMemoryResolver, ModuleResolver};
+pub use reader::{
+    read, read_partial, read_with_comments, read_with_options, Comment, PartialForm, ReadOptions,
+};
+pub use recovery::Recovery;
+pub use value::Value;
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+const SOURCE_EXTENSION: &str = "sigil";
+
+/// Finds the source for a namespace loaded via `require`.
+pub trait ModuleResolver: fmt::Debug {
+    /// Returns the source of `namespace` or `None` if this resolver does not know of it.
+    fn resolve(&self, namespace: &str) -> io::Result<Option<String>>;
+
+    /// Describes where the source of `namespace` is found, e.g. to bind `*file*` while loading it.
+    fn describe(&self, namespace: &str) -> String {
+        namespace.to_string()
+    }
+
+    /// The locations searched for `namespace`, in order, to report when it is not found.
+    fn searched(&self, _namespace: &str) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Expands a leading `~` to the home directory and each `$VAR` or `${VAR}` to the
+/// value of the environment variable `VAR`, or to nothing if it is unset, as a shell would.
+pub fn expand_path(path: &str) -> PathBuf {
+    let mut expanded = String::new();
+    let mut rest = path;
+    if let Some(after_tilde) = path.strip_prefix('~') {
+        if after_tilde.is_empty() || after_tilde.starts_with('/') {
+            expanded.push_str(&env::var("HOME").unwrap_or_default());
+            rest = after_tilde;
+        }
+    }
+    while let Some(index) = rest.find('$') {
+        expanded.push_str(&rest[..index]);
+        let after_dollar = &rest[index + 1..];
+        let (name, after_name) = match after_dollar.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after_dollar),
+            },
+            None => {
+                let end = after_dollar
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                    .unwrap_or(after_dollar.len());
+                after_dollar.split_at(end)
+            }
+        };
+        if name.is_empty() {
+            expanded.push('$');
+        } else {
+            expanded.push_str(&env::var(name).unwrap_or_default());
+        }
+        rest = after_name;
+    }
+    expanded.push_str(rest);
+    PathBuf::from(expanded)
+}
+
+/// Resolves namespaces to files under a root directory following the usual convention:
+/// `foo.bar-baz` is found at `foo/bar_baz.sigil`. Further roots added with `with_root`
+/// are searched in the order they were added, so the first root holding a file wins.
+/// Roots are expanded with `expand_path`.
+#[derive(Debug, Clone)]
+pub struct FilesystemResolver {
+    roots: Vec<PathBuf>,
+}
+
+impl FilesystemResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { roots: vec![] }.with_root(root)
+    }
+
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let root = match root.to_str() {
+            Some(root) => expand_path(root),
+            None => root,
+        };
+        self.roots.push(root);
+        self
+    }
+
+    fn paths_for<'a>(&'a self, namespace: &'a str) -> impl Iterator<Item = PathBuf> + 'a {
+        self.roots.iter().map(move |root| {
+            let mut path = root.clone();
+            for segment in namespace.split('.') {
+                path.push(segment.replace('-', "_"));
+            }
+            path.set_extension(SOURCE_EXTENSION);
+            path
+        })
+    }
+}
+
+impl Default for FilesystemResolver {
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+impl ModuleResolver for FilesystemResolver {
+    fn resolve(&self, namespace: &str) -> io::Result<Option<String>> {
+        for path in self.paths_for(namespace) {
+            match std::fs::read_to_string(path) {
+                Ok(source) => return Ok(Some(source)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(None)
+    }
+
+    fn describe(&self, namespace: &str) -> String {
+        let mut paths = self.paths_for(namespace).peekable();
+        let first = paths.peek().cloned().unwrap_or_default();
+        paths
+            .find(|path| path.is_file())
+            .unwrap_or(first)
+            .display()
+            .to_string()
+    }
+
+    fn searched(&self, namespace: &str) -> Vec<String> {
+        self.paths_for(namespace)
+            .map(|path| path.display().to_string())
+            .collect()
+    }
+}
+
+/// Resolves namespaces from sources held in memory, e.g. embedded in the host program.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryResolver {
+    modules: HashMap<String, String>,
+}
+
+impl InMemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, namespace: impl Into<String>, source: impl Into<String>) {
+        self.modules.insert(namespace.into(), source.into());
+    }
+}
+
+impl<N: Into<String>, S: Into<String>> FromIterator<(N, S)> for InMemoryResolver {
+    fn from_iter<I: IntoIterator<Item = (N, S)>>(iter: I) -> Self {
+        Self {
+            modules: iter
+                .into_iter()
+                .map(|(namespace, source)| (namespace.into(), source.into()))
+                .collect(),
+        }
+    }
+}
+
+impl ModuleResolver for InMemoryResolver {
+    fn resolve(&self, namespace: &str) -> io::Result<Option<String>> {
+        Ok(self.modules.get(namespace).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_filesystem_resolver_paths() {
+        let resolver = FilesystemResolver::new("lib");
+        assert_eq!(
+            resolver.paths_for("foo").collect::<Vec<_>>(),
+            vec![Path::new("lib/foo.sigil")]
+        );
+        assert_eq!(
+            resolver.paths_for("foo.bar-baz").collect::<Vec<_>>(),
+            vec![Path::new("lib/foo/bar_baz.sigil")]
+        );
+        assert!(resolver.resolve("does.not-exist").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_filesystem_resolver_roots() {
+        let dir = env::temp_dir().join(format!("sigil-resolver-roots-{}", std::process::id()));
+        let (first, second) = (dir.join("first"), dir.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(second.join("a.sigil"), "(def! x 2)").unwrap();
+        std::fs::write(second.join("b.sigil"), "(def! y 2)").unwrap();
+        std::fs::write(first.join("b.sigil"), "(def! y 1)").unwrap();
+
+        let resolver = FilesystemResolver::new(&first).with_root(&second);
+        assert_eq!(resolver.resolve("a").unwrap().unwrap(), "(def! x 2)");
+        assert_eq!(resolver.resolve("b").unwrap().unwrap(), "(def! y 1)");
+        assert_eq!(
+            resolver.describe("a"),
+            second.join("a.sigil").display().to_string()
+        );
+        assert_eq!(
+            resolver.searched("c"),
+            vec![
+                first.join("c.sigil").display().to_string(),
+                second.join("c.sigil").display().to_string()
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_path() {
+        env::set_var("SIGIL_TEST_EXPAND", "lib");
+        env::remove_var("SIGIL_TEST_UNSET");
+        let home = env::var("HOME").unwrap_or_default();
+        assert_eq!(expand_path("~/x"), PathBuf::from(format!("{home}/x")));
+        assert_eq!(expand_path("a~/x"), PathBuf::from("a~/x"));
+        assert_eq!(
+            expand_path("$SIGIL_TEST_EXPAND/${SIGIL_TEST_EXPAND}_2/$SIGIL_TEST_UNSET/x"),
+            PathBuf::from("lib/lib_2//x")
+        );
+        assert_eq!(expand_path("cost$/${"), PathBuf::from("cost$/${"));
+    }
+
+    #[test]
+    fn test_in_memory_resolver() {
+        let resolver: InMemoryResolver = [("foo", "(def! a 1)")].into_iter().collect();
+        assert_eq!(resolver.resolve("foo").unwrap().unwrap(), "(def! a 1)");
+        assert!(resolver.resolve("bar").unwrap().is_none());
+    }
+}
@@ -1,5 +1,6 @@
-use crate::value::{unbound_var, var_with_value, Value};
+use crate::value::{unbound_var, var_with_value, Value, VarImpl};
 use std::collections::HashMap;
+use std::rc::Rc;
 use thiserror::Error;
 
 pub(crate) const DEFAULT_NAME: &str = "core";
@@ -10,11 +11,14 @@ pub enum NamespaceError {
     ValueInNamespaceWasNotVar(Value),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 // map from identifier to Value::Var
+// the tables are shared by clones, e.g. those made by `Interpreter::fork`, until either changes
 pub struct Namespace {
     pub name: String,
-    bindings: HashMap<String, Value>,
+    bindings: Rc<HashMap<String, Value>>,
+    // alias to the name of the namespace it stands for, see `alias`
+    aliases: Rc<HashMap<String, String>>,
 }
 
 impl Default for Namespace {
@@ -27,7 +31,8 @@ impl Namespace {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
-            bindings: HashMap::new(),
+            bindings: Rc::default(),
+            aliases: Rc::default(),
         }
     }
 
@@ -37,7 +42,7 @@ impl Namespace {
 
     // NOTE: `value` must be a `Value::Var`
     fn insert(&mut self, identifier: &str, value: &Value) {
-        self.bindings.insert(identifier.to_string(), value.clone());
+        Rc::make_mut(&mut self.bindings).insert(identifier.to_string(), value.clone());
     }
 
     // NOTE: `value` will be wrapped in a `Value::Var` which is stored in this namespace
@@ -56,6 +61,11 @@ impl Namespace {
         }
     }
 
+    // binds `identifier` to `var`, replacing any var bound to it
+    pub(crate) fn rebind(&mut self, identifier: &str, var: &VarImpl) {
+        self.insert(identifier, &Value::Var(var.clone()));
+    }
+
     pub fn intern_unbound(&mut self, identifier: &str) -> Value {
         let var = unbound_var(&self.name, identifier);
         self.insert(identifier, &var);
@@ -63,11 +73,11 @@ impl Namespace {
     }
 
     pub fn remove(&mut self, identifier: &str) {
-        self.bindings.remove(identifier);
+        Rc::make_mut(&mut self.bindings).remove(identifier);
     }
 
     pub fn merge(&mut self, other: &Namespace) -> Result<(), NamespaceError> {
-        for (identifier, value) in &other.bindings {
+        for (identifier, value) in other.bindings.iter() {
             self.intern(identifier, value)?;
         }
         Ok(())
@@ -76,4 +86,21 @@ impl Namespace {
     pub fn symbols(&self) -> impl Iterator<Item = &String> {
         self.bindings.keys()
     }
+
+    pub fn add_alias(&mut self, alias: &str, namespace: &str) {
+        Rc::make_mut(&mut self.aliases).insert(alias.to_string(), namespace.to_string());
+    }
+
+    pub fn resolve_alias(&self, alias: &str) -> Option<&String> {
+        self.aliases.get(alias)
+    }
+
+    pub fn aliases(&self) -> &Rc<HashMap<String, String>> {
+        &self.aliases
+    }
+
+    // drop every alias standing for `namespace`
+    pub fn remove_aliases_to(&mut self, namespace: &str) {
+        Rc::make_mut(&mut self.aliases).retain(|_, target| target != namespace);
+    }
 }
@@ -1,8 +1,16 @@
-use crate::value::{list_with_values, map_with_values, set_with_values, vector_with_values, Value};
+use crate::value::{
+    list_with_values, map_with_values, parse_instant, set_with_values, vector_with_values, Value,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::{self, Peekable};
 use std::num::ParseIntError;
-use std::{iter::Peekable, str::CharIndices};
+use std::rc::Rc;
+use std::str::CharIndices;
 use thiserror::Error;
+use uuid::Uuid;
 
 fn is_newline(input: char) -> bool {
     input == '\n'
@@ -35,7 +43,7 @@ pub fn is_symbolic(input: char) -> bool {
 pub fn is_structural(input: char) -> bool {
     matches!(
         input,
-        '(' | ')' | '[' | ']' | '{' | '}' | '#' | '@' | '\'' | '`' | '~' | '"'
+        '(' | ')' | '[' | ']' | '{' | '}' | '#' | '@' | '^' | '\'' | '`' | '~' | '"'
     )
 }
 
@@ -157,6 +165,21 @@ enum Range {
     Slice(usize, usize),
 }
 
+impl Range {
+    fn slice<'a>(&self, input: &'a str) -> &'a str {
+        match self {
+            Range::ToEnd(start) => &input[*start..],
+            Range::Slice(start, end) => &input[*start..*end],
+        }
+    }
+
+    fn start(&self) -> usize {
+        match self {
+            Range::ToEnd(start) | Range::Slice(start, _) => *start,
+        }
+    }
+}
+
 #[derive(Debug, Error, Clone)]
 pub enum ReaderError {
     #[error("error parsing number: {0}")]
@@ -185,6 +208,18 @@ pub enum ReaderError {
     CouldNotParseDispatch(char),
     #[error("reader macro `#'` requires a symbol suffix but found {0} instead")]
     VarDispatchRequiresSymbol(Value),
+    #[error("tagged literal requires a symbol tag but found {0} instead")]
+    TaggedLiteralRequiresSymbol(Value),
+    #[error("no reader is known for the tagged literal `#{0}`")]
+    UnknownTag(String),
+    #[error("tagged literal `#{0}` could not read the form {1}")]
+    InvalidTaggedLiteral(String, Value),
+    #[error("reader extension `#{0}` could not read the form {1}: {2}")]
+    ReaderExtensionFailed(String, Box<Value>, String),
+    #[error("namespaced map `#:` requires a map suffix but found {0} instead")]
+    NamespaceMapRequiresMap(Value),
+    #[error("metadata must be a map, keyword or symbol but found {0} instead")]
+    MetadataMustBeMapOrKeyword(Value),
     #[error("internal error: {0}")]
     Internal(&'static str),
 }
@@ -198,6 +233,11 @@ impl ReadError {
     pub fn context<'a>(&self, input: &'a str) -> &'a str {
         &input[self.1..]
     }
+
+    /// The line of `input`, counting from 1, where the error occurred.
+    pub fn line(&self, input: &str) -> usize {
+        input[..self.1].matches('\n').count() + 1
+    }
 }
 
 impl std::fmt::Display for ReadError {
@@ -237,7 +277,78 @@ enum Span {
     Comment(Range),
 }
 
-#[derive(Default, Debug)]
+// resolves a tagged literal the reader does not know of, returning `None` if the tag
+// is unknown and `Some(Err(form))` if `form` is not valid for the tag
+pub(crate) type TagReader<'a> = dyn FnMut(&str, Value) -> Option<Result<Value, Value>> + 'a;
+
+type ReaderExtension = dyn Fn(&Value) -> Result<Value, String>;
+
+/// Configures how `read_with_options` reads its input.
+#[derive(Clone, Default)]
+pub struct ReadOptions {
+    // dispatch name, e.g. `js` for `#js`, to the reader of the form following it
+    extensions: HashMap<String, Rc<ReaderExtension>>,
+    // alias to the namespace it stands for in `::alias/name` and `#::alias{...}`
+    aliases: Rc<HashMap<String, String>>,
+}
+
+impl ReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `#name form` as the value `extension` returns given `form`, or fail with the
+    /// message it returns, e.g. to embed a DSL with `#sql "select ..."`. `name` is either a
+    /// tag, like a tagged literal, or a single dispatch character such as `$`.
+    /// The built-in dispatches (`#{`, `#'`, `#_`) and tags (`bytes`, `uuid`, `inst`)
+    /// take precedence over any extension.
+    pub fn with_extension(
+        mut self,
+        name: impl Into<String>,
+        extension: impl Fn(&Value) -> Result<Value, String> + 'static,
+    ) -> Self {
+        self.add_extension(name, extension);
+        self
+    }
+
+    pub(crate) fn add_extension(
+        &mut self,
+        name: impl Into<String>,
+        extension: impl Fn(&Value) -> Result<Value, String> + 'static,
+    ) {
+        self.extensions.insert(name.into(), Rc::new(extension));
+    }
+
+    /// Read `::alias/name` as the keyword `:namespace/name` and `#::alias{...}` as a map
+    /// keyed in `namespace`.
+    pub fn with_alias(mut self, alias: impl Into<String>, namespace: impl Into<String>) -> Self {
+        Rc::make_mut(&mut self.aliases).insert(alias.into(), namespace.into());
+        self
+    }
+
+    pub(crate) fn set_aliases(&mut self, aliases: Rc<HashMap<String, String>>) {
+        self.aliases = aliases;
+    }
+
+    pub(crate) fn has_extensions(&self) -> bool {
+        !self.extensions.is_empty()
+    }
+
+    fn extension(&self, name: &str) -> Option<&ReaderExtension> {
+        self.extensions.get(name).map(Rc::as_ref)
+    }
+}
+
+impl fmt::Debug for ReadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOptions")
+            .field("extensions", &self.extensions.keys().sorted().collect_vec())
+            .field("aliases", &self.aliases.iter().sorted().collect_vec())
+            .finish()
+    }
+}
+
+#[derive(Default)]
 struct Reader<'a> {
     input: &'a str,
     spans: Vec<Span>,
@@ -246,6 +357,23 @@ struct Reader<'a> {
     // beginning of the current focus in `input`
     cursor: usize,
     parse_state: ParseState,
+    tag_reader: Option<&'a mut TagReader<'a>>,
+    options: Option<&'a ReadOptions>,
+    // the comments read before forms, if retained, see `read_with_comments`
+    comments: Option<Vec<Comment>>,
+}
+
+impl std::fmt::Debug for Reader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reader")
+            .field("input", &self.input)
+            .field("spans", &self.spans)
+            .field("values", &self.values)
+            .field("line_count", &self.line_count)
+            .field("cursor", &self.cursor)
+            .field("parse_state", &self.parse_state)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<'a> Reader<'a> {
@@ -354,7 +482,14 @@ impl<'a> Reader<'a> {
         }
         if let Some(end) = end {
             let source = &self.input[start..end];
-            let value = parse_symbolic(source)?;
+            let aliased = source
+                .strip_prefix("::")
+                .and_then(|s| s.split_once('/'))
+                .filter(|(alias, _)| !alias.is_empty());
+            let value = match aliased {
+                Some((alias, identifier)) => self.read_aliased_keyword(alias, identifier)?,
+                None => parse_symbolic(source)?,
+            };
             self.values.push(value);
             let span = Range::Slice(start, end);
             self.spans.push(Span::Simple(span));
@@ -364,6 +499,21 @@ impl<'a> Reader<'a> {
         }
     }
 
+    // `::alias/identifier`, resolving `alias` through the aliases of the `options`
+    fn read_aliased_keyword(&self, alias: &str, identifier: &str) -> Result<Value, ReaderError> {
+        let namespace = self
+            .options
+            .and_then(|options| options.aliases.get(alias))
+            .ok_or(ReaderError::InvalidNamespace)?;
+        if identifier.is_empty() || identifier.contains([':', '/']) {
+            return Err(ReaderError::InvalidIdentifier);
+        }
+        Ok(Value::Keyword(
+            identifier.to_string(),
+            Some(namespace.clone()),
+        ))
+    }
+
     fn read_string(&mut self, stream: &mut Stream) -> Result<(), ReaderError> {
         let (start, _) = stream.next().expect("from peek");
         self.cursor = start;
@@ -374,7 +524,7 @@ impl<'a> Reader<'a> {
         let escaped_string = apply_string_escapes(source);
         let span = Range::Slice(start, end);
         self.spans.push(Span::Simple(span));
-        let value = Value::String(escaped_string);
+        let value = Value::String(escaped_string.into());
         self.values.push(value);
         Ok(())
     }
@@ -601,8 +751,180 @@ impl<'a> Reader<'a> {
                 self.spans.pop().expect("just ranged one form");
                 Ok(())
             }
-            ch => Err(ReaderError::CouldNotParseDispatch(ch)),
+            ':' => self.read_namespaced_map(start, stream),
+            ch if ch.is_alphabetic() => self.read_tagged_literal(start, stream),
+            ch => {
+                let name = ch.to_string();
+                if self
+                    .options
+                    .and_then(|options| options.extension(&name))
+                    .is_none()
+                {
+                    return Err(ReaderError::CouldNotParseDispatch(ch));
+                }
+                stream.next().expect("from peek");
+                self.read_exactly_one_form(start, stream)
+                    .inspect_err(|_| self.cursor = start)?;
+                let form = self.values.pop().expect("just read form");
+                let span = self.spans.pop().expect("just ranged form");
+                let value = self.read_extension(start, name, form)?;
+                self.push_dispatched(start, value, span);
+                Ok(())
+            }
+        }
+    }
+
+    // reads `#:ns{:a 1 :_/b 2}` as `{:ns/a 1 :b 2}`, or with `#::alias{...}` the namespace
+    // `alias` stands for
+    fn read_namespaced_map(
+        &mut self,
+        start: usize,
+        stream: &mut Stream,
+    ) -> Result<(), ReaderError> {
+        stream.next().expect("from peek");
+        let mut prefix = String::new();
+        while let Some((_, ch)) = stream.next_if(|(_, ch)| is_symbolic(*ch)) {
+            prefix.push(ch);
+        }
+        let namespace = match prefix.strip_prefix(':') {
+            Some(alias) => self
+                .options
+                .and_then(|options| options.aliases.get(alias))
+                .cloned(),
+            None => Some(prefix),
         }
+        .filter(|namespace| !namespace.is_empty() && !namespace.contains([':', '/']))
+        .ok_or(ReaderError::InvalidNamespace)?;
+
+        self.read_exactly_one_form(start, stream)?;
+        let map = self.values.pop().expect("just read form");
+        let span = self.spans.pop().expect("just ranged form");
+        let Value::Map(map) = map else {
+            self.cursor = start;
+            return Err(ReaderError::NamespaceMapRequiresMap(map));
+        };
+        let entries = map.iter().map(|(key, value)| {
+            let key = match key {
+                Value::Keyword(identifier, None) => {
+                    Value::Keyword(identifier.clone(), Some(namespace.clone()))
+                }
+                Value::Keyword(identifier, Some(ns)) if ns == "_" => {
+                    Value::Keyword(identifier.clone(), None)
+                }
+                key => key.clone(),
+            };
+            (key, value.clone())
+        });
+        self.push_dispatched(start, map_with_values(entries), span);
+        Ok(())
+    }
+
+    // reads `#tag form`, e.g. `#bytes "aGVsbG8="`
+    fn read_tagged_literal(
+        &mut self,
+        start: usize,
+        stream: &mut Stream,
+    ) -> Result<(), ReaderError> {
+        self.read_exactly_one_form(start, stream)
+            .inspect_err(|_| self.cursor = start)?;
+        let tag = self.values.pop().expect("just read tag");
+        self.spans.pop().expect("just ranged tag");
+        let tag = match tag {
+            Value::Symbol(identifier, None) => identifier,
+            other => {
+                self.cursor = start;
+                return Err(ReaderError::TaggedLiteralRequiresSymbol(other));
+            }
+        };
+        self.read_exactly_one_form(start, stream)
+            .inspect_err(|_| self.cursor = start)?;
+        let form = self.values.pop().expect("just read form");
+        let span = self.spans.pop().expect("just ranged form");
+
+        let value = match (tag.as_str(), form) {
+            ("bytes", Value::String(encoded)) => match BASE64.decode(encoded.as_bytes()) {
+                Ok(bytes) => Value::Bytes(bytes.into()),
+                Err(_) => {
+                    self.cursor = start;
+                    return Err(ReaderError::InvalidTaggedLiteral(
+                        tag,
+                        Value::String(encoded),
+                    ));
+                }
+            },
+            ("uuid", Value::String(encoded)) => match Uuid::parse_str(&encoded) {
+                Ok(uuid) => Value::Uuid(uuid),
+                Err(_) => {
+                    self.cursor = start;
+                    return Err(ReaderError::InvalidTaggedLiteral(
+                        tag,
+                        Value::String(encoded),
+                    ));
+                }
+            },
+            ("inst", Value::String(encoded)) => match parse_instant(&encoded) {
+                Some(millis) => Value::Instant(millis),
+                None => {
+                    self.cursor = start;
+                    return Err(ReaderError::InvalidTaggedLiteral(
+                        tag,
+                        Value::String(encoded),
+                    ));
+                }
+            },
+            ("queue", Value::Vector(elems)) => Value::Queue(elems.iter().cloned().collect()),
+            ("bytes" | "uuid" | "inst" | "queue", other) => {
+                self.cursor = start;
+                return Err(ReaderError::InvalidTaggedLiteral(tag, other));
+            }
+            // readers given for this read take precedence over the extensions of `options`
+            (_, form) => match self.tag_reader.as_mut() {
+                Some(read) => match read(&tag, form.clone()) {
+                    Some(Ok(value)) => value,
+                    Some(Err(form)) => {
+                        self.cursor = start;
+                        return Err(ReaderError::InvalidTaggedLiteral(tag, form));
+                    }
+                    None => self.read_extension(start, tag, form)?,
+                },
+                None => self.read_extension(start, tag, form)?,
+            },
+        };
+        self.push_dispatched(start, value, span);
+        Ok(())
+    }
+
+    // reads `form` following `#name` with the extension registered for `name`
+    fn read_extension(
+        &mut self,
+        start: usize,
+        name: String,
+        form: Value,
+    ) -> Result<Value, ReaderError> {
+        let result = match self.options.and_then(|options| options.extension(&name)) {
+            Some(extension) => extension(&form).map_err(|message| {
+                ReaderError::ReaderExtensionFailed(name, Box::new(form), message)
+            }),
+            None => Err(ReaderError::UnknownTag(name)),
+        };
+        if result.is_err() {
+            self.cursor = start;
+        }
+        result
+    }
+
+    // pushes `value` read from the dispatch at `start` and the form spanning `span`
+    fn push_dispatched(&mut self, start: usize, value: Value, span: Span) {
+        self.values.push(value);
+
+        let range = match span {
+            Span::Simple(range) | Span::Compound(range, _) => match range {
+                Range::Slice(_, end) => Range::Slice(start, end),
+                Range::ToEnd(_) => Range::ToEnd(start),
+            },
+            _ => unreachable!("reading a form yields a simple or compound span"),
+        };
+        self.spans.push(Span::Simple(range));
     }
 
     fn read_exactly_one_form(
@@ -672,6 +994,40 @@ impl<'a> Reader<'a> {
         Ok(())
     }
 
+    // `^meta form` reads as `form` with `meta` attached, where a keyword `^:k` is short for
+    // `^{:k true}` and a type hint `^T` for `^{:tag "T"}`
+    fn read_metadata(&mut self, start: usize, stream: &mut Stream) -> Result<(), ReaderError> {
+        self.read_exactly_one_form(start, stream)?;
+        let meta = match self.values.pop().expect("just read form") {
+            meta @ Value::Map(..) => meta,
+            keyword @ Value::Keyword(..) => map_with_values([(keyword, Value::Bool(true))]),
+            Value::Symbol(tag, None) => map_with_values([(
+                Value::Keyword("tag".to_string(), None),
+                Value::String(tag.into()),
+            )]),
+            other => {
+                self.cursor = start;
+                return Err(ReaderError::MetadataMustBeMapOrKeyword(other));
+            }
+        };
+        let meta_span = self.spans.pop().expect("just ranged form");
+        self.read_exactly_one_form(start, stream)?;
+        let form = self.values.pop().expect("just read form");
+        let form_span = self.spans.pop().expect("just ranged form");
+        self.values.push(attach_metadata(form, meta));
+
+        let range = match &form_span {
+            Span::Simple(range) | Span::Compound(range, _) => match range {
+                Range::Slice(_, end) => Range::Slice(start, *end),
+                Range::ToEnd(_) => Range::ToEnd(start),
+            },
+            _ => unreachable!("read some form"),
+        };
+        self.spans
+            .push(Span::Compound(range, vec![meta_span, form_span]));
+        Ok(())
+    }
+
     fn read_form(
         &mut self,
         next_char: char,
@@ -707,6 +1063,10 @@ impl<'a> Reader<'a> {
                 stream.next().expect("from peek");
                 self.read_dispatch(next_index, stream)?;
             }
+            '^' => {
+                stream.next().expect("from peek");
+                self.read_metadata(next_index, stream)?;
+            }
             '@' => {
                 stream.next().expect("from peek");
                 self.read_macro("deref", next_index, stream)?;
@@ -737,19 +1097,61 @@ impl<'a> Reader<'a> {
         Ok(())
     }
 
+    // the text of the whitespace or comment just read
+    fn last_trivia(&self) -> &'a str {
+        match self.spans.last() {
+            Some(Span::Whitespace(range) | Span::Comment(range)) => range.slice(self.input),
+            _ => "",
+        }
+    }
+
     fn read_from_stream(&mut self, stream: &mut Stream) -> Result<(), ReaderError> {
+        let retain_comments = self.comments.is_some();
+        let mut comments = vec![];
+        // whether a comment read now starts its own line rather than trailing a form
+        let mut line_start = true;
         while let Some((index, ch)) = stream.peek() {
             let ch = *ch;
             if is_whitespace(ch) {
                 self.read_whitespace(stream)?;
+                if retain_comments {
+                    let newlines = self.last_trivia().matches('\n').count();
+                    line_start |= newlines > 0;
+                    // a comment consumes its own newline so any further one is a blank line
+                    if newlines > 1 || (newlines > 0 && !comments.is_empty()) {
+                        comments.clear();
+                    }
+                }
                 continue;
             }
             if is_comment(ch) {
                 self.read_comment(stream)?;
+                if retain_comments {
+                    if line_start {
+                        comments.push(self.last_trivia().trim_end().to_string());
+                    }
+                    line_start = true;
+                }
                 continue;
             }
-            self.read_form(ch, *index, stream)?;
-            if matches!(self.parse_state, ParseState::Exiting) {
+            let values_count = self.values.len();
+            let start = *index;
+            self.read_form(ch, start, stream)?;
+            if retain_comments && self.values.len() > values_count {
+                line_start = false;
+                if !comments.is_empty() {
+                    let end = stream.peek().map_or(self.input.len(), |(index, _)| *index);
+                    let form = start..end;
+                    let retained = self.comments.as_mut().expect("retains comments");
+                    retained.extend(comments.drain(..).map(|text| Comment {
+                        text,
+                        form: form.clone(),
+                    }));
+                }
+            }
+            // a discarded form `#_ form` yields no value so keep reading for one
+            let discarded = self.values.len() == values_count && !matches!(ch, ')' | ']' | '}');
+            if matches!(self.parse_state, ParseState::Exiting) && !discarded {
                 break;
             }
         }
@@ -759,6 +1161,10 @@ impl<'a> Reader<'a> {
     fn read(&mut self, input: &'a str) -> Result<(), ReaderError> {
         self.input = input;
         let mut stream = input.char_indices().peekable();
+        // skip a leading `#!` line so that scripts can be executed directly
+        if input.starts_with("#!") {
+            stream.by_ref().find(|(_, ch)| *ch == '\n');
+        }
         self.read_from_stream(&mut stream)?;
         if let Some((_, ch)) = stream.next() {
             return Err(ReaderError::UnexpectedInput(ch));
@@ -767,6 +1173,23 @@ impl<'a> Reader<'a> {
     }
 }
 
+// attach `meta` to `form`, merged over any metadata read for it already, e.g. `^:a ^:b form`
+fn attach_metadata(form: Value, meta: Value) -> Value {
+    let (form, meta) = match (form, meta) {
+        (Value::WithMeta(form, existing), Value::Map(meta)) => match *existing {
+            Value::Map(existing) => {
+                let merged = meta.iter().fold(existing, |merged, (k, v)| {
+                    merged.insert(k.clone(), v.clone())
+                });
+                (*form, Value::Map(merged))
+            }
+            _ => (*form, Value::Map(meta)),
+        },
+        (form, meta) => (form, meta),
+    };
+    Value::WithMeta(Box::new(form), Box::new(meta))
+}
+
 pub fn read(input: &str) -> Result<Vec<Value>, ReadError> {
     let mut reader = Reader::new();
     match reader.read(input) {
@@ -775,12 +1198,163 @@ pub fn read(input: &str) -> Result<Vec<Value>, ReadError> {
     }
 }
 
+/// Like `read` but reading any dispatch registered with `ReadOptions::with_extension`.
+pub fn read_with_options(input: &str, options: &ReadOptions) -> Result<Vec<Value>, ReadError> {
+    let mut reader = Reader::new();
+    reader.options = Some(options);
+    match reader.read(input) {
+        Ok(_) => Ok(reader.values),
+        Err(err) => Err(ReadError(err, reader.cursor)),
+    }
+}
+
+/// A comment on the lines before a form, see `read_with_comments`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// The comment as written, e.g. `;; adds one`.
+    pub text: String,
+    /// The span in bytes of the form following the comment.
+    pub form: std::ops::Range<usize>,
+}
+
+/// Like `read_with_options` but also yielding alongside each top-level form the comments
+/// on the lines before it or before any form nested in it, e.g. for tools extracting
+/// documentation. A blank line or a form separates comments from those after it.
+pub fn read_with_comments(
+    input: &str,
+    options: &ReadOptions,
+) -> Result<Vec<(Value, Vec<Comment>)>, ReadError> {
+    let mut reader = Reader::new();
+    reader.options = Some(options);
+    reader.comments = Some(vec![]);
+    if let Err(err) = reader.read(input) {
+        return Err(ReadError(err, reader.cursor));
+    }
+    // comments before nested forms are retained before those of the enclosing form
+    let mut comments = reader.comments.unwrap_or_default();
+    comments.sort_by_key(|comment| comment.form.start);
+    let mut comments = comments.into_iter().peekable();
+    let starts = reader
+        .spans
+        .iter()
+        .filter_map(|span| match span {
+            Span::Simple(range) | Span::Compound(range, _) => Some(range.start()),
+            _ => None,
+        })
+        .collect_vec();
+    let forms = reader
+        .values
+        .into_iter()
+        .enumerate()
+        .map(|(i, form)| {
+            let next_start = starts.get(i + 1).copied().unwrap_or(usize::MAX);
+            let form_comments =
+                iter::from_fn(|| comments.next_if(|comment| comment.form.start < next_start))
+                    .collect();
+            (form, form_comments)
+        })
+        .collect();
+    Ok(forms)
+}
+
+/// A top-level region of the input given to `read_partial`, with its span in bytes.
+#[derive(Debug, Clone)]
+pub enum PartialForm {
+    Form(Value, std::ops::Range<usize>),
+    Error(ReadError, std::ops::Range<usize>),
+}
+
+// the start of the first line after `index` that begins a form in its first column,
+// where reading resumes after an error
+fn next_top_level_start(input: &str, index: usize) -> usize {
+    input[index..]
+        .match_indices('\n')
+        .map(|(offset, _)| index + offset + 1)
+        .find(|&start| {
+            input[start..]
+                .chars()
+                .next()
+                .is_some_and(|ch| !is_whitespace(ch) && !matches!(ch, ')' | ']' | '}'))
+        })
+        .unwrap_or(input.len())
+}
+
+/// Like `read` but never failing outright, for tooling working on input being edited:
+/// each top-level form that cannot be read is reported as a `PartialForm::Error`
+/// spanning up to the next line beginning with a form, where reading resumes.
+pub fn read_partial(input: &str) -> Vec<PartialForm> {
+    let mut result = vec![];
+    let mut stream = input.char_indices().peekable();
+    if input.starts_with("#!") {
+        stream.by_ref().find(|(_, ch)| *ch == '\n');
+    }
+    loop {
+        while let Some((_, ch)) = stream.peek() {
+            if is_whitespace(*ch) {
+                stream.next();
+            } else if is_comment(*ch) {
+                stream.by_ref().find(|(_, ch)| is_newline(*ch));
+            } else {
+                break;
+            }
+        }
+        let Some(&(start, ch)) = stream.peek() else {
+            break;
+        };
+        if matches!(ch, ')' | ']' | '}') {
+            stream.next();
+            let err = ReadError(ReaderError::UnexpectedInput(ch), start);
+            result.push(PartialForm::Error(err, start..start + 1));
+            continue;
+        }
+        let mut reader = Reader::new();
+        reader.input = input;
+        match reader.read_form(ch, start, &mut stream) {
+            Ok(()) => {
+                let end = stream.peek().map_or(input.len(), |(index, _)| *index);
+                // a discarded form `#_ form` yields no value
+                for value in reader.values {
+                    result.push(PartialForm::Form(value, start..end));
+                }
+            }
+            Err(err) => {
+                let next_start = next_top_level_start(input, reader.cursor.max(start));
+                let end = start + input[start..next_start].trim_end().len();
+                result.push(PartialForm::Error(
+                    ReadError(err, reader.cursor),
+                    start..end,
+                ));
+                stream = input.char_indices().peekable();
+                while stream.next_if(|(index, _)| *index < next_start).is_some() {}
+            }
+        }
+    }
+    result
+}
+
+// like `read_with_options` but first consults `tag_reader` for tagged literals with unknown tags
+pub(crate) fn read_with_tag_reader<'a>(
+    input: &'a str,
+    options: &'a ReadOptions,
+    tag_reader: &'a mut TagReader<'a>,
+) -> Result<Vec<Value>, ReadError> {
+    let mut reader = Reader::new();
+    reader.tag_reader = Some(tag_reader);
+    reader.options = Some(options);
+    match reader.read(input) {
+        Ok(_) => Ok(reader.values),
+        Err(err) => Err(ReadError(err, reader.cursor)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        list_with_values, map_with_values, read, set_with_values, vector_with_values, ReadError,
-        ReaderError, Value::*,
+        list_with_values, map_with_values, read, read_partial, read_with_comments,
+        read_with_options, set_with_values, vector_with_values, Comment, PartialForm, ReadError,
+        ReadOptions, ReaderError, Value::*,
     };
+    use crate::value::PersistentQueue;
     use itertools::Itertools;
 
     #[test]
@@ -803,6 +1377,21 @@ mod tests {
                 Box::new(|err| matches!(err, ReaderError::CouldNotParseNumber(_))),
                 0,
             ),
+            (
+                "(1 #_)",
+                Box::new(|err| matches!(err, ReaderError::ExpectedMoreInput)),
+                3,
+            ),
+            (
+                "^1 a",
+                Box::new(|err| matches!(err, ReaderError::MetadataMustBeMapOrKeyword(_))),
+                0,
+            ),
+            (
+                "^:a",
+                Box::new(|err| matches!(err, ReaderError::ExpectedMoreInput)),
+                0,
+            ),
             (
                 "-/",
                 Box::new(|err| matches!(err, ReaderError::MissingIdentifier)),
@@ -979,15 +1568,50 @@ mod tests {
                 0,
             ),
             (
-                "#!some-form",
+                "1 #!some-form",
                 Box::new(|err| matches!(err, ReaderError::CouldNotParseDispatch('!'))),
-                0,
+                2,
             ),
             (
                 "#'(not-a-symbol)",
                 Box::new(|err| matches!(err, ReaderError::VarDispatchRequiresSymbol(_))),
                 0,
             ),
+            (
+                "#foo 1",
+                Box::new(|err| matches!(err, ReaderError::UnknownTag(_))),
+                0,
+            ),
+            (
+                "1 #bytes 1",
+                Box::new(|err| matches!(err, ReaderError::InvalidTaggedLiteral(..))),
+                2,
+            ),
+            (
+                "#bytes \"!!\"",
+                Box::new(|err| matches!(err, ReaderError::InvalidTaggedLiteral(..))),
+                0,
+            ),
+            (
+                "#bytes",
+                Box::new(|err| matches!(err, ReaderError::ExpectedMoreInput)),
+                0,
+            ),
+            (
+                "#uuid \"not-a-uuid\"",
+                Box::new(|err| matches!(err, ReaderError::InvalidTaggedLiteral(..))),
+                0,
+            ),
+            (
+                "#inst \"yesterday\"",
+                Box::new(|err| matches!(err, ReaderError::InvalidTaggedLiteral(..))),
+                0,
+            ),
+            (
+                "#queue (1)",
+                Box::new(|err| matches!(err, ReaderError::InvalidTaggedLiteral(..))),
+                0,
+            ),
             (
                 "@",
                 Box::new(|err| matches!(err, ReaderError::ExpectedMoreInput)),
@@ -1365,13 +1989,44 @@ mod tests {
                 ])],
                 "[+ 1 [+ 2 3]]",
             ),
+            ("#bytes \"\"", vec![Bytes(vec![].into())], "#bytes \"\""),
+            (
+                "#bytes   \"aGk=\"",
+                vec![Bytes(vec![104, 105].into())],
+                "#bytes \"aGk=\"",
+            ),
+            (
+                "#uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\"",
+                vec![Uuid(
+                    uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap(),
+                )],
+                "#uuid \"67e55044-10b1-426f-9247-bb680e5fe0c8\"",
+            ),
+            (
+                "#inst \"1970-01-01T00:00:01.500Z\"",
+                vec![Instant(1500)],
+                "#inst \"1970-01-01T00:00:01.500Z\"",
+            ),
+            (
+                "#inst \"1970-01-01T01:00:00+01:00\"",
+                vec![Instant(0)],
+                "#inst \"1970-01-01T00:00:00.000Z\"",
+            ),
+            (
+                "#queue [1   :a]",
+                vec![Queue(PersistentQueue::from_iter([
+                    Number(1),
+                    Keyword("a".into(), None),
+                ]))],
+                "#queue [1 :a]",
+            ),
             ("#{}", vec![set_with_values(vec![])], "#{}"),
             ("#{1}", vec![set_with_values(vec![Number(1)])], "#{1}"),
             ("#{   1}", vec![set_with_values(vec![Number(1)])], "#{1}"),
             ("#{   1  }", vec![set_with_values(vec![Number(1)])], "#{1}"),
             (
                 "#{   \"hi\"  }",
-                vec![set_with_values(vec![String("hi".to_string())])],
+                vec![set_with_values(vec![String("hi".into())])],
                 "#{\"hi\"}",
             ),
             (
@@ -1569,6 +2224,63 @@ mod tests {
                 ])],
                 "(splice-unquote (1 2 3))",
             ),
+            (
+                "^{:doc \"x\"} a",
+                vec![WithMeta(
+                    Box::new(Symbol("a".into(), None)),
+                    Box::new(map_with_values(vec![(
+                        Keyword("doc".into(), None),
+                        String("x".into()),
+                    )])),
+                )],
+                "a",
+            ),
+            (
+                "^String a",
+                vec![WithMeta(
+                    Box::new(Symbol("a".into(), None)),
+                    Box::new(map_with_values(vec![(
+                        Keyword("tag".into(), None),
+                        String("String".into()),
+                    )])),
+                )],
+                "a",
+            ),
+            (
+                "^:private   [1]",
+                vec![WithMeta(
+                    Box::new(vector_with_values(vec![Number(1)])),
+                    Box::new(map_with_values(vec![(
+                        Keyword("private".into(), None),
+                        Bool(true),
+                    )])),
+                )],
+                "[1]",
+            ),
+            (
+                "'^:a ^{:a 2 :b 3} (x)",
+                vec![list_with_values(vec![
+                    Symbol("quote".into(), None),
+                    WithMeta(
+                        Box::new(list_with_values(vec![Symbol("x".into(), None)])),
+                        Box::new(map_with_values(vec![
+                            (Keyword("a".into(), None), Bool(true)),
+                            (Keyword("b".into(), None), Number(3)),
+                        ])),
+                    ),
+                ])],
+                "(quote (x))",
+            ),
+            (
+                "#!/usr/bin/env sigil\n(+ 1 2)",
+                vec![list_with_values(vec![
+                    Symbol("+".into(), None),
+                    Number(1),
+                    Number(2),
+                ])],
+                "(+ 1 2)",
+            ),
+            ("#!/usr/bin/env sigil", vec![], ""),
             ("1 #_(1 2 3) 3", vec![Number(1), Number(3)], "1 3"),
             (
                 "1 (1 2 #_[1 2 3 :keyw]) 3",
@@ -1596,6 +2308,40 @@ mod tests {
                 ],
                 "1 (1 2) 4",
             ),
+            (
+                "[#_ #_ 1 2 3]",
+                vec![vector_with_values(vec![Number(3)])],
+                "[3]",
+            ),
+            ("#_ #_ 1 2", vec![], ""),
+            (
+                "{:a 1 #_:b}",
+                vec![map_with_values(vec![(
+                    Keyword("a".into(), None),
+                    Number(1),
+                )])],
+                "{:a 1}",
+            ),
+            ("#{1 #_2}", vec![set_with_values(vec![Number(1)])], "#{1}"),
+            (
+                "'#_1 2",
+                vec![list_with_values(vec![
+                    Symbol("quote".into(), None),
+                    Number(2),
+                ])],
+                "(quote 2)",
+            ),
+            (
+                "^:a #_b c",
+                vec![WithMeta(
+                    Box::new(Symbol("c".into(), None)),
+                    Box::new(map_with_values(vec![(
+                        Keyword("a".into(), None),
+                        Bool(true),
+                    )])),
+                )],
+                "c",
+            ),
         ];
         for (input, expected_read, expected_print) in cases {
             match read(input) {
@@ -1619,4 +2365,161 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_read_with_comments() {
+        let input = ";; adds one\n;; to x\n(defn inc [x]\n  ; the sum\n  (+ x 1)) ; trailing\n\n; detached\n\n:a";
+        let forms = read_with_comments(input, &ReadOptions::new()).unwrap();
+        let comment = |text: &str, form: &str| Comment {
+            text: text.to_string(),
+            form: {
+                let start = input.find(form).unwrap();
+                start..start + form.len()
+            },
+        };
+        let defn = "(defn inc [x]\n  ; the sum\n  (+ x 1))";
+        assert_eq!(
+            forms,
+            vec![
+                (
+                    read(defn).unwrap().remove(0),
+                    vec![
+                        comment(";; adds one", defn),
+                        comment(";; to x", defn),
+                        comment("; the sum", "(+ x 1)"),
+                    ]
+                ),
+                (Keyword("a".to_string(), None), vec![]),
+            ]
+        );
+        assert_eq!(read(input).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_partial() {
+        let input =
+            "(def! a 1) ; note\n(defn f [x\n  (+ x 1)\n\n(def! b #_ 9 2))\n]\n#_ 3 [c {:d}]";
+        let forms = read_partial(input);
+        let spans = forms
+            .iter()
+            .map(|form| match form {
+                PartialForm::Form(_, span) | PartialForm::Error(_, span) => &input[span.clone()],
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                "(def! a 1)",
+                "(defn f [x\n  (+ x 1)",
+                "(def! b #_ 9 2)",
+                ")",
+                "]",
+                "[c {:d}]"
+            ]
+        );
+        assert!(matches!(
+            &forms[0],
+            PartialForm::Form(List(elems), _) if elems.len() == 3
+        ));
+        assert!(matches!(
+            &forms[1],
+            PartialForm::Error(ReadError(ReaderError::UnbalancedCollection(']'), 26), _)
+        ));
+        assert!(matches!(&forms[2], PartialForm::Form(List(elems), _) if elems.len() == 3));
+        assert!(matches!(
+            &forms[3],
+            PartialForm::Error(ReadError(ReaderError::UnexpectedInput(')'), 55), _)
+        ));
+        assert!(matches!(
+            &forms[5],
+            PartialForm::Error(ReadError(ReaderError::MapLiteralWithUnpairedElements, _), _)
+        ));
+        assert!(read_partial(" ; only a comment").is_empty());
+    }
+
+    #[test]
+    fn test_read_with_options() {
+        let options = ReadOptions::new()
+            .with_extension("js", |form| match form {
+                Vector(elems) => Ok(list_with_values(
+                    [Symbol("array".into(), None)]
+                        .into_iter()
+                        .chain(elems.iter().cloned()),
+                )),
+                _ => Err("expected a vector".to_string()),
+            })
+            .with_extension("$", |form| {
+                Ok(list_with_values([Symbol("env".into(), None), form.clone()]))
+            })
+            .with_extension("uuid", |_| Ok(Nil));
+        let forms = read_with_options(
+            "#js [1 2] (f #$ HOME) #uuid \"00000000-0000-0000-0000-000000000000\"",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            forms[..2],
+            [
+                list_with_values([Symbol("array".into(), None), Number(1), Number(2)]),
+                list_with_values([
+                    Symbol("f".into(), None),
+                    list_with_values([Symbol("env".into(), None), Symbol("HOME".into(), None)]),
+                ]),
+            ]
+        );
+        // built-in tags take precedence
+        assert!(matches!(forms[2], Uuid(..)));
+
+        let err = read_with_options("[1 #js {}]", &options).unwrap_err();
+        assert!(matches!(
+            &err.0,
+            ReaderError::ReaderExtensionFailed(name, form, message)
+                if name == "js" && matches!(**form, Map(..)) && message == "expected a vector"
+        ));
+        assert_eq!(err.1, 3);
+        assert!(matches!(
+            read_with_options("#sql \"x\"", &options).unwrap_err().0,
+            ReaderError::UnknownTag(..)
+        ));
+        assert!(matches!(
+            read("#$ HOME").unwrap_err().0,
+            ReaderError::CouldNotParseDispatch('$')
+        ));
+    }
+
+    #[test]
+    fn test_read_with_aliases() {
+        let options = ReadOptions::new().with_alias("h", "hash");
+        let forms =
+            read_with_options("::h/x #::h{:a 1 :_/b 2 :c/d 3} #:n {:a 1}", &options).unwrap();
+        assert_eq!(
+            forms,
+            [
+                Keyword("x".into(), Some("hash".into())),
+                map_with_values([
+                    (Keyword("a".into(), Some("hash".into())), Number(1)),
+                    (Keyword("b".into(), None), Number(2)),
+                    (Keyword("d".into(), Some("c".into())), Number(3)),
+                ]),
+                map_with_values([(Keyword("a".into(), Some("n".into())), Number(1))]),
+            ]
+        );
+        for (source, expected_index) in [("[::g/x]", 1), ("[1 #::g{:a 1}]", 3), ("#:{:a 1}", 0)] {
+            let err = read_with_options(source, &options).unwrap_err();
+            assert!(matches!(err.0, ReaderError::InvalidNamespace));
+            assert_eq!(err.1, expected_index);
+        }
+        assert!(matches!(
+            read_with_options("::h/x:y", &options).unwrap_err().0,
+            ReaderError::InvalidIdentifier
+        ));
+        assert!(matches!(
+            read_with_options("#:n [:a]", &options).unwrap_err().0,
+            ReaderError::NamespaceMapRequiresMap(Vector(..))
+        ));
+        assert!(matches!(
+            read("::h/x").unwrap_err().0,
+            ReaderError::InvalidNamespace
+        ));
+    }
 }
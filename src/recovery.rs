@@ -0,0 +1,42 @@
+use crate::interpreter::EvaluationError;
+use crate::value::Value;
+use std::fmt;
+use std::rc::Rc;
+
+/// What to do with an error about to propagate from `Interpreter::evaluate`,
+/// as decided by the hook given to `InterpreterBuilder::with_recovery_hook`.
+#[derive(Debug, Clone)]
+pub enum Recovery {
+    /// Let the error propagate unchanged.
+    Propagate,
+    /// Evaluate to this value instead of the error.
+    Substitute(Value),
+    /// Evaluate this form in place of the form that failed, e.g. the same call with other
+    /// arguments. An error from the retry propagates without consulting the hook again.
+    Retry(Value),
+    /// Propagate an exception with this message and data, caused by the original error,
+    /// as if it were thrown with `ex-info`.
+    Throw(String, Value),
+}
+
+type RecoveryFn = dyn Fn(&EvaluationError, &Value) -> Recovery;
+
+#[derive(Clone)]
+pub(crate) struct RecoveryHook(Rc<RecoveryFn>);
+
+impl RecoveryHook {
+    pub(crate) fn new(hook: impl Fn(&EvaluationError, &Value) -> Recovery + 'static) -> Self {
+        Self(Rc::new(hook))
+    }
+
+    // the `Recovery` for `err` raised while evaluating the top-level `form`
+    pub(crate) fn recover(&self, err: &EvaluationError, form: &Value) -> Recovery {
+        (self.0)(err, form)
+    }
+}
+
+impl fmt::Debug for RecoveryHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecoveryHook(<fn>)")
+    }
+}
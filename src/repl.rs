@@ -1,5 +1,5 @@
 use crate::interpreter::{EvaluationError, Interpreter, SymbolIndex};
-use crate::reader::{is_structural, is_symbolic, is_token, read, ReadError};
+use crate::reader::{is_structural, is_symbolic, is_token, ReadError};
 use crate::value::Value;
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
@@ -155,10 +155,15 @@ impl<P: AsRef<Path>> StdRepl<P> {
     }
 
     pub fn run_from_source<'a>(&mut self, source: &'a str) -> Result<Vec<Value>, ReplError<'a>> {
-        let forms = read(source).map_err(|err| ReplError::Read(err, source))?;
+        let forms = self
+            .interpreter
+            .read(source)
+            .map_err(|err| ReplError::Read(err, source))?;
         let mut results = vec![];
-        for form in forms.iter() {
-            match self.interpreter.evaluate(form) {
+        for form in &forms {
+            let result = self.interpreter.evaluate(form);
+            self.interpreter.record_result(&result);
+            match result {
                 Ok(result) => {
                     results.push(result);
                 }
@@ -189,7 +194,11 @@ impl<P: AsRef<Path>> StdRepl<P> {
             match next_line {
                 Ok(line) => {
                     self.editor.add_history_entry(line.as_str());
-                    match self.run_from_source(&line) {
+                    let outcome = self.run_from_source(&line);
+                    for diagnostic in self.interpreter.take_diagnostics() {
+                        println!("{}", diagnostic);
+                    }
+                    match outcome {
                         Ok(results) => {
                             for result in results {
                                 println!("{}", result.to_readable_string());
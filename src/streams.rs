@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+#[derive(Debug)]
+pub(crate) enum Stream {
+    Reader(BufReader<File>),
+    Writer(BufWriter<File>),
+}
+
+// files opened with `reader` and `writer`, by the id held in the handle returned for each
+#[derive(Debug, Default)]
+pub(crate) struct Streams {
+    next_id: i64,
+    open: HashMap<i64, Stream>,
+}
+
+impl Streams {
+    pub(crate) fn open(&mut self, stream: Stream) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.open.insert(id, stream);
+        id
+    }
+
+    pub(crate) fn get_mut(&mut self, id: i64) -> Option<&mut Stream> {
+        self.open.get_mut(&id)
+    }
+
+    // the stream, if it was still open; dropping it flushes any buffered writes
+    pub(crate) fn close(&mut self, id: i64) -> Option<Stream> {
+        self.open.remove(&id)
+    }
+}
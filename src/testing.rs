@@ -1,9 +1,14 @@
-use crate::interpreter::Interpreter;
+use crate::interpreter::{Interpreter, InterpreterBuilder};
 use crate::reader::read;
 use crate::value::Value;
 
 const EXPECTED_STARTING_SCOPE_LEN: usize = 1;
 
+thread_local! {
+    // each case evaluates in a copy of this interpreter so the core is only built once per thread
+    static BASE_INTERPRETER: Interpreter = InterpreterBuilder::default().with_eager_core().build();
+}
+
 pub fn run_eval_test(test_cases: &[(&str, Value)]) {
     let mut has_err = false;
     for (input, expected) in test_cases {
@@ -20,7 +25,7 @@ pub fn run_eval_test(test_cases: &[(&str, Value)]) {
             }
         };
 
-        let mut interpreter = Interpreter::default();
+        let mut interpreter = BASE_INTERPRETER.with(Interpreter::clone_isolated);
         let mut final_result: Option<Value> = None;
         let original_scope_len = interpreter.scopes.len();
         assert!(original_scope_len == EXPECTED_STARTING_SCOPE_LEN);
@@ -1,18 +1,25 @@
+use crate::analyzer::lambda_parameter_key;
 use crate::interpreter::{EvaluationError, EvaluationResult, Interpreter};
-use itertools::{join, sorted, Itertools};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use itertools::{join, Itertools};
+// maps and sets are ordered so that iteration (e.g. when printing or
+// producing a seq) does not depend on hashing
 pub use rpds::{
-    HashTrieMap as PersistentMap, HashTrieSet as PersistentSet, List as PersistentList,
-    Vector as PersistentVector,
+    List as PersistentList, Queue as PersistentQueue, RedBlackTreeMap as PersistentMap,
+    RedBlackTreeSet as PersistentSet, Vector as PersistentVector,
 };
 use std::cell::RefCell;
 use std::cmp::{Eq, Ord, Ordering, PartialEq};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::iter::{FromIterator, IntoIterator};
 use std::mem::discriminant;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use uuid::Uuid;
 
 pub fn list_with_values(values: impl IntoIterator<Item = Value>) -> Value {
     Value::List(PersistentList::from_iter(values))
@@ -31,19 +38,48 @@ pub fn set_with_values(values: impl IntoIterator<Item = Value>) -> Value {
 }
 
 pub fn var_with_value(value: Value, namespace: &str, identifier: &str) -> Value {
-    Value::Var(VarImpl {
-        data: Rc::new(RefCell::new(Some(value))),
-        namespace: namespace.to_string(),
-        identifier: identifier.to_string(),
-    })
+    Value::Var(VarImpl::new(Some(value), namespace, identifier))
 }
 
 pub fn unbound_var(namespace: &str, identifier: &str) -> Value {
-    Value::Var(VarImpl {
-        data: Rc::new(RefCell::new(None)),
-        namespace: namespace.to_string(),
-        identifier: identifier.to_string(),
-    })
+    Value::Var(VarImpl::new(None, namespace, identifier))
+}
+
+// incremented by each `Interpreter::fork`, so that the vars made before
+// an interpreter was last forked can be told apart, see `VarImpl::generation`
+static VAR_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// starts a new generation of vars, returning it
+pub(crate) fn next_var_generation() -> u64 {
+    VAR_GENERATION.fetch_add(1, AtomicOrdering::Relaxed) + 1
+}
+
+// a cell for a `let*` binding that may be referenced before it is initialized,
+// e.g. by a `fn*` bound earlier in the same bindings
+pub(crate) fn local_cell(identifier: &str) -> VarImpl {
+    VarImpl::new(None, "", identifier)
+}
+
+// a single-character string, without going through an intermediate `String`
+pub(crate) fn char_string(c: char) -> Value {
+    Value::String(Rc::from(&*c.encode_utf8(&mut [0; 4])))
+}
+
+pub fn bytes_with_values(values: impl IntoIterator<Item = u8>) -> Value {
+    Value::Bytes(values.into_iter().collect())
+}
+
+/// Format the instant `millis` since the Unix epoch as an RFC3339 timestamp in UTC.
+pub fn format_instant(millis: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Parse an RFC3339 timestamp into milliseconds since the Unix epoch.
+pub fn parse_instant(input: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(input)
+        .ok()
+        .map(|t| t.timestamp_millis())
 }
 
 pub fn atom_with_value(value: Value) -> Value {
@@ -62,6 +98,15 @@ pub fn exception(msg: &str, data: &Value) -> ExceptionImpl {
     ExceptionImpl::User(UserException {
         message: msg.to_string(),
         data: Box::new(data.clone()),
+        cause: None,
+    })
+}
+
+pub fn exception_with_cause(msg: &str, data: &Value, cause: &ExceptionImpl) -> ExceptionImpl {
+    ExceptionImpl::User(UserException {
+        message: msg.to_string(),
+        data: Box::new(data.clone()),
+        cause: Some(Box::new(cause.clone())),
     })
 }
 
@@ -79,6 +124,9 @@ pub type NativeFn = fn(&mut Interpreter, &[Value]) -> EvaluationResult<Value>;
 pub struct FnImpl {
     pub body: PersistentList<Value>,
     pub arity: usize,
+    // the parameter names as written, including any `&`, to describe the expected arguments;
+    // boxed as they are only read to describe the fn, keeping `Value` small
+    pub params: Box<PersistentVector<Value>>,
     // allow for nested fns
     pub level: usize,
     pub variadic: bool,
@@ -87,7 +135,9 @@ pub struct FnImpl {
 #[derive(Debug, Clone, Eq)]
 pub struct FnWithCapturesImpl {
     pub f: FnImpl,
-    pub captures: HashMap<String, Option<Value>>,
+    // boxed to keep closures from widening every `Value`
+    #[allow(clippy::box_collection)]
+    pub captures: Box<HashMap<String, Option<Value>>>,
 }
 
 impl PartialOrd for FnWithCapturesImpl {
@@ -136,22 +186,228 @@ impl Hash for FnWithCapturesImpl {
 #[derive(Clone)]
 pub struct VarImpl {
     data: Rc<RefCell<Option<Value>>>,
+    // the map attached when the var was defined, e.g. with `(def! ^{:doc "..."} name value)`
+    meta: Rc<RefCell<Option<Value>>>,
     namespace: String,
     pub identifier: String,
+    // the value of `VAR_GENERATION` when the var was made
+    generation: u64,
 }
 
 impl VarImpl {
+    fn new(value: Option<Value>, namespace: &str, identifier: &str) -> Self {
+        Self {
+            data: Rc::new(RefCell::new(value)),
+            meta: Rc::new(RefCell::new(None)),
+            namespace: namespace.to_string(),
+            identifier: identifier.to_string(),
+            generation: VAR_GENERATION.load(AtomicOrdering::Relaxed),
+        }
+    }
+
+    // a new var holding the value and metadata `self` holds now
+    pub(crate) fn copy(&self) -> Self {
+        let copy = Self::new(
+            self.data.borrow().clone(),
+            &self.namespace,
+            &self.identifier,
+        );
+        copy.set_meta(self.meta());
+        copy
+    }
+
     pub fn update(&self, value: Value) {
         *self.data.borrow_mut() = Some(value);
     }
+
+    pub(crate) fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    // whether `self` was made with `local_cell` rather than interned in some namespace
+    pub(crate) fn is_local_cell(&self) -> bool {
+        self.namespace.is_empty()
+    }
+
+    pub fn meta(&self) -> Option<Value> {
+        self.meta.borrow().clone()
+    }
+
+    pub(crate) fn set_meta(&self, meta: Option<Value>) {
+        *self.meta.borrow_mut() = meta;
+    }
+
+    // whether `self` and `other` refer to the same storage
+    pub(crate) fn is_same(&self, other: &VarImpl) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+
+    // the address of the var's storage, shared by every reference to the var
+    pub(crate) fn address(&self) -> usize {
+        Rc::as_ptr(&self.data) as usize
+    }
+
+    // the generation the var was made in, a var made before its interpreter was
+    // last forked, i.e. of an earlier generation, may be shared with another interpreter
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // a reference to this var which does not keep it alive
+    pub(crate) fn downgrade(&self) -> WeakVarImpl {
+        WeakVarImpl {
+            data: Rc::downgrade(&self.data),
+            meta: Rc::downgrade(&self.meta),
+            namespace: self.namespace.clone(),
+            identifier: self.identifier.clone(),
+            generation: self.generation,
+        }
+    }
+}
+
+impl fmt::Debug for VarImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&Value::Var(self.clone()), f)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct WeakVarImpl {
+    data: Weak<RefCell<Option<Value>>>,
+    meta: Weak<RefCell<Option<Value>>>,
+    namespace: String,
+    identifier: String,
+    generation: u64,
+}
+
+impl WeakVarImpl {
+    // the var, unless every strong reference to it has been dropped
+    pub(crate) fn upgrade(&self) -> Option<VarImpl> {
+        Some(VarImpl {
+            data: self.data.upgrade()?,
+            meta: self.meta.upgrade()?,
+            namespace: self.namespace.clone(),
+            identifier: self.identifier.clone(),
+            generation: self.generation,
+        })
+    }
+
+    // the qualified name of the var, e.g. `core/inc`
+    pub(crate) fn name(&self) -> String {
+        format!("{}/{}", self.namespace, self.identifier)
+    }
 }
 
 type AtomImpl = Rc<RefCell<Value>>;
 
+// a reference to an atom or var which does not keep it alive, see `weak-ref`
+#[derive(Clone, Debug)]
+pub struct WeakRefImpl(WeakTarget);
+
+#[derive(Clone, Debug)]
+enum WeakTarget {
+    Atom(Weak<RefCell<Value>>),
+    // boxed so `Value` does not grow
+    Var(Box<WeakVarImpl>),
+}
+
+impl WeakRefImpl {
+    // `None` if `target` is neither an atom nor a var
+    pub(crate) fn new(target: &Value) -> Option<Self> {
+        match target {
+            Value::Atom(atom) => Some(Self(WeakTarget::Atom(Rc::downgrade(atom)))),
+            Value::Var(var) => Some(Self(WeakTarget::Var(Box::new(var.downgrade())))),
+            _ => None,
+        }
+    }
+
+    // the target, unless every strong reference to it has been dropped
+    pub(crate) fn upgrade(&self) -> Option<Value> {
+        match &self.0 {
+            WeakTarget::Atom(atom) => atom.upgrade().map(Value::Atom),
+            WeakTarget::Var(var) => var.upgrade().map(Value::Var),
+        }
+    }
+
+    // identifies the storage of the target, live or not, so weak refs compare
+    // and hash by identity rather than by a value that may disappear
+    fn address(&self) -> usize {
+        match &self.0 {
+            WeakTarget::Atom(atom) => atom.as_ptr() as usize,
+            WeakTarget::Var(var) => var.data.as_ptr() as usize,
+        }
+    }
+}
+
+// a bounded cache evicting the least recently used entry, see `lru-cache`; updated in
+// place, so compared and hashed by identity like an atom that is never dereferenced
+#[derive(Clone, Debug)]
+pub struct LruCacheImpl(Rc<RefCell<LruCache>>);
+
+#[derive(Debug)]
+struct LruCache {
+    capacity: usize,
+    // counts puts, so the first entry of `order` is the least recently used
+    tick: u64,
+    // key -> (value, tick of its last put), ordered like the keys of a map
+    entries: BTreeMap<Value, (Value, u64)>,
+    // tick of the last put -> key
+    order: BTreeMap<u64, Value>,
+}
+
+impl LruCacheImpl {
+    // `capacity` must not be zero
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self(Rc::new(RefCell::new(LruCache {
+            capacity,
+            tick: 0,
+            entries: BTreeMap::new(),
+            order: BTreeMap::new(),
+        })))
+    }
+
+    // the value held for `key`, without counting as a use of `key`
+    pub(crate) fn get(&self, key: &Value) -> Option<Value> {
+        let cache = self.0.borrow();
+        cache.entries.get(key).map(|(value, _)| value.clone())
+    }
+
+    // holds `value` for `key` as the most recently used entry,
+    // evicting the least recently used entry if full
+    pub(crate) fn put(&self, key: Value, value: Value) {
+        let mut cache = self.0.borrow_mut();
+        cache.tick += 1;
+        let tick = cache.tick;
+        match cache.entries.insert(key.clone(), (value, tick)) {
+            Some((_, previous)) => {
+                cache.order.remove(&previous);
+            }
+            None if cache.entries.len() > cache.capacity => {
+                if let Some((_, oldest)) = cache.order.pop_first() {
+                    cache.entries.remove(&oldest);
+                }
+            }
+            None => {}
+        }
+        cache.order.insert(tick, key);
+    }
+
+    // (entries held, capacity)
+    fn size(&self) -> (usize, usize) {
+        let cache = self.0.borrow();
+        (cache.entries.len(), cache.capacity)
+    }
+
+    fn address(&self) -> usize {
+        self.0.as_ptr() as usize
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct UserException {
     message: String,
     data: Box<Value>,
+    cause: Option<Box<ExceptionImpl>>,
 }
 
 impl UserException {
@@ -181,6 +437,33 @@ pub enum ExceptionImpl {
 }
 
 impl ExceptionImpl {
+    pub fn message(&self) -> String {
+        match self {
+            ExceptionImpl::User(exc) => exc.message.clone(),
+            ExceptionImpl::System(err) => err.to_string(),
+        }
+    }
+
+    // errors raised by the interpreter only carry data describing arithmetic and arity errors
+    pub fn data(&self) -> Value {
+        match self {
+            ExceptionImpl::User(exc) => *exc.data.clone(),
+            ExceptionImpl::System(err) => err.data(),
+        }
+    }
+
+    pub fn cause(&self) -> Option<ExceptionImpl> {
+        match self {
+            ExceptionImpl::User(exc) => exc.cause.as_deref().cloned(),
+            ExceptionImpl::System(err) => match err.as_ref() {
+                EvaluationError::WrongArityForFn { source, .. } => {
+                    Some(ExceptionImpl::System(source.clone()))
+                }
+                _ => None,
+            },
+        }
+    }
+
     fn to_readable_string(&self) -> String {
         let mut result = String::new();
         match self {
@@ -190,7 +473,7 @@ impl ExceptionImpl {
             ExceptionImpl::System(err) => write!(
                 &mut result,
                 "{}",
-                Value::String(err.to_string()).to_readable_string()
+                Value::String(err.to_string().into()).to_readable_string()
             )
             .expect("can write to string"),
         }
@@ -202,12 +485,17 @@ impl PartialEq for ExceptionImpl {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (
-                ExceptionImpl::User(UserException { message, data }),
+                ExceptionImpl::User(UserException {
+                    message,
+                    data,
+                    cause,
+                }),
                 ExceptionImpl::User(UserException {
                     message: other_message,
                     data: other_data,
+                    cause: other_cause,
                 }),
-            ) => message == other_message && data == other_data,
+            ) => message == other_message && data == other_data && cause == other_cause,
             _ => false,
         }
     }
@@ -225,12 +513,17 @@ impl Ord for ExceptionImpl {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
             (
-                ExceptionImpl::User(UserException { message, data }),
+                ExceptionImpl::User(UserException {
+                    message,
+                    data,
+                    cause,
+                }),
                 ExceptionImpl::User(UserException {
                     message: other_message,
                     data: other_data,
+                    cause: other_cause,
                 }),
-            ) => (message, data).cmp(&(other_message, other_data)),
+            ) => (message, data, cause).cmp(&(other_message, other_data, other_cause)),
             (ExceptionImpl::User(..), ExceptionImpl::System(..)) => Ordering::Less,
             (ExceptionImpl::System(..), ExceptionImpl::User(..)) => Ordering::Greater,
             (ExceptionImpl::System(a), ExceptionImpl::System(b)) => {
@@ -244,9 +537,14 @@ impl Hash for ExceptionImpl {
     fn hash<H: Hasher>(&self, state: &mut H) {
         discriminant(self).hash(state);
         match self {
-            ExceptionImpl::User(UserException { message, data }) => {
+            ExceptionImpl::User(UserException {
+                message,
+                data,
+                cause,
+            }) => {
                 message.hash(state);
                 data.hash(state);
+                cause.hash(state);
             }
             ExceptionImpl::System(err) => {
                 err.to_string().hash(state);
@@ -258,7 +556,7 @@ impl Hash for ExceptionImpl {
 impl fmt::Display for ExceptionImpl {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ExceptionImpl::User(UserException { message, data }) => {
+            ExceptionImpl::User(UserException { message, data, .. }) => {
                 if !message.is_empty() {
                     write!(f, "{}, ", message)?;
                 }
@@ -276,7 +574,7 @@ pub enum Value {
     Nil,
     Bool(bool),
     Number(i64),
-    String(String),
+    String(Rc<str>),
     // identifier with optional namespace
     Keyword(String, Option<String>),
     // identifier with optional namespace
@@ -293,12 +591,39 @@ pub enum Value {
     Atom(AtomImpl),
     Macro(FnImpl),
     Exception(ExceptionImpl),
+    Bytes(Rc<[u8]>),
+    Uuid(Uuid),
+    // milliseconds since the Unix epoch
+    Instant(i64),
+    Queue(PersistentQueue<Value>),
+    WeakRef(WeakRefImpl),
+    LruCache(LruCacheImpl),
+    // stops a `reduce` early with the wrapped value, see `reduced`
+    Reduced(Box<Value>),
+    // the parameter at some index of the fn at some level, as resolved by the analyzer
+    Parameter(usize, usize),
+    // a collection, symbol or fn with a map of metadata, see `with-meta`;
+    // the metadata takes no part in equality, ordering or hashing
+    WithMeta(Box<Value>, Box<Value>),
+}
+
+// lists, vectors and queues with equal elements in the same order are equal
+fn sequential_eq<'a>(
+    x_len: usize,
+    x: impl Iterator<Item = &'a Value>,
+    y_len: usize,
+    y: impl Iterator<Item = &'a Value>,
+) -> bool {
+    x_len == y_len && x.zip(y).all(|(a, b)| a == b)
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         use Value::*;
 
+        if let WithMeta(other, _) = other {
+            return self == &**other;
+        }
         match self {
             Nil => matches!(other, Nil),
             Bool(ref x) => match other {
@@ -323,24 +648,14 @@ impl PartialEq for Value {
             },
             List(ref x) => match other {
                 List(ref y) => x == y,
-                Vector(ref y) => {
-                    if x.len() == y.len() {
-                        x.iter().zip(y.iter()).map(|(a, b)| a == b).all(|x| x)
-                    } else {
-                        false
-                    }
-                }
+                Vector(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
+                Queue(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
                 _ => false,
             },
             Vector(ref x) => match other {
                 Vector(ref y) => x == y,
-                List(ref y) => {
-                    if x.len() == y.len() {
-                        x.iter().zip(y.iter()).map(|(a, b)| a == b).all(|x| x)
-                    } else {
-                        false
-                    }
-                }
+                List(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
+                Queue(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
                 _ => false,
             },
             Map(ref x) => match other {
@@ -360,13 +675,8 @@ impl PartialEq for Value {
                 _ => false,
             },
             Primitive(x) => match other {
-                Primitive(y) => {
-                    let x_ptr = x as *const NativeFn;
-                    let x_identifier = x_ptr as usize;
-                    let y_ptr = y as *const NativeFn;
-                    let y_identifier = y_ptr as usize;
-                    x_identifier == y_identifier
-                }
+                // the same native fn, rather than the same `Value` holding it
+                Primitive(y) => *x as usize == *y as usize,
                 _ => false,
             },
             Var(VarImpl {
@@ -397,6 +707,41 @@ impl PartialEq for Value {
                 Exception(ref y) => x == y,
                 _ => false,
             },
+            Bytes(ref x) => match other {
+                Bytes(ref y) => x == y,
+                _ => false,
+            },
+            Uuid(ref x) => match other {
+                Uuid(ref y) => x == y,
+                _ => false,
+            },
+            Instant(ref x) => match other {
+                Instant(ref y) => x == y,
+                _ => false,
+            },
+            Queue(ref x) => match other {
+                Queue(ref y) => x == y,
+                List(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
+                Vector(ref y) => sequential_eq(x.len(), x.iter(), y.len(), y.iter()),
+                _ => false,
+            },
+            WeakRef(ref x) => match other {
+                WeakRef(ref y) => x.address() == y.address(),
+                _ => false,
+            },
+            LruCache(ref x) => match other {
+                LruCache(ref y) => x.address() == y.address(),
+                _ => false,
+            },
+            Reduced(ref x) => match other {
+                Reduced(ref y) => x == y,
+                _ => false,
+            },
+            Parameter(index, level) => match other {
+                Parameter(other_index, other_level) => (index, level) == (other_index, other_level),
+                _ => false,
+            },
+            WithMeta(x, _) => &**x == other,
         }
     }
 }
@@ -415,6 +760,9 @@ impl Ord for Value {
     fn cmp(&self, other: &Self) -> Ordering {
         use Value::*;
 
+        if let WithMeta(other, _) = other {
+            return self.cmp(other);
+        }
         match self {
             Nil => match other {
                 Nil => Ordering::Equal,
@@ -468,7 +816,7 @@ impl Ord for Value {
                 | Symbol(_, _)
                 | List(_)
                 | Vector(_) => Ordering::Greater,
-                Map(ref y) => sorted(x).cmp(sorted(y)),
+                Map(ref y) => x.iter().cmp(y.iter()),
                 _ => Ordering::Less,
             },
             Set(ref x) => match other {
@@ -481,7 +829,7 @@ impl Ord for Value {
                 | List(_)
                 | Vector(_)
                 | Map(_) => Ordering::Greater,
-                Set(ref y) => sorted(x).cmp(sorted(y)),
+                Set(ref y) => x.iter().cmp(y.iter()),
                 _ => Ordering::Less,
             },
             Fn(ref x) => match other {
@@ -526,13 +874,7 @@ impl Ord for Value {
                 | Set(_)
                 | Fn(_)
                 | FnWithCaptures(_) => Ordering::Greater,
-                Primitive(y) => {
-                    let x_ptr = x as *const NativeFn;
-                    let x_identifier = x_ptr as usize;
-                    let y_ptr = y as *const NativeFn;
-                    let y_identifier = y_ptr as usize;
-                    x_identifier.cmp(&y_identifier)
-                }
+                Primitive(y) => (*x as usize).cmp(&(*y as usize)),
                 _ => Ordering::Less,
             },
             Var(VarImpl {
@@ -636,7 +978,104 @@ impl Ord for Value {
                 | Atom(_)
                 | Macro(_) => Ordering::Greater,
                 Exception(ref y) => x.cmp(y),
+                _ => Ordering::Less,
+            },
+            Bytes(ref x) => match other {
+                Nil
+                | Bool(_)
+                | Number(_)
+                | String(_)
+                | Keyword(_, _)
+                | Symbol(_, _)
+                | List(_)
+                | Vector(_)
+                | Map(_)
+                | Set(_)
+                | Fn(_)
+                | FnWithCaptures(_)
+                | Primitive(_)
+                | Var(_)
+                | Recur(_)
+                | Atom(_)
+                | Macro(_)
+                | Exception(_) => Ordering::Greater,
+                Bytes(ref y) => x.cmp(y),
+                _ => Ordering::Less,
+            },
+            Uuid(ref x) => match other {
+                Nil
+                | Bool(_)
+                | Number(_)
+                | String(_)
+                | Keyword(_, _)
+                | Symbol(_, _)
+                | List(_)
+                | Vector(_)
+                | Map(_)
+                | Set(_)
+                | Fn(_)
+                | FnWithCaptures(_)
+                | Primitive(_)
+                | Var(_)
+                | Recur(_)
+                | Atom(_)
+                | Macro(_)
+                | Exception(_)
+                | Bytes(_) => Ordering::Greater,
+                Uuid(ref y) => x.cmp(y),
+                _ => Ordering::Less,
             },
+            Instant(ref x) => match other {
+                Nil
+                | Bool(_)
+                | Number(_)
+                | String(_)
+                | Keyword(_, _)
+                | Symbol(_, _)
+                | List(_)
+                | Vector(_)
+                | Map(_)
+                | Set(_)
+                | Fn(_)
+                | FnWithCaptures(_)
+                | Primitive(_)
+                | Var(_)
+                | Recur(_)
+                | Atom(_)
+                | Macro(_)
+                | Exception(_)
+                | Bytes(_)
+                | Uuid(_) => Ordering::Greater,
+                Instant(ref y) => x.cmp(y),
+                _ => Ordering::Less,
+            },
+            Queue(ref x) => match other {
+                Queue(ref y) => x.iter().cmp(y.iter()),
+                WeakRef(_) | LruCache(_) | Reduced(_) | Parameter(..) => Ordering::Less,
+                _ => Ordering::Greater,
+            },
+            WeakRef(ref x) => match other {
+                WeakRef(ref y) => x.address().cmp(&y.address()),
+                LruCache(_) | Reduced(_) | Parameter(..) => Ordering::Less,
+                _ => Ordering::Greater,
+            },
+            LruCache(ref x) => match other {
+                LruCache(ref y) => x.address().cmp(&y.address()),
+                Reduced(_) | Parameter(..) => Ordering::Less,
+                _ => Ordering::Greater,
+            },
+            Reduced(ref x) => match other {
+                Reduced(ref y) => x.cmp(y),
+                Parameter(..) => Ordering::Less,
+                _ => Ordering::Greater,
+            },
+            Parameter(index, level) => match other {
+                Parameter(other_index, other_level) => {
+                    (index, level).cmp(&(other_index, other_level))
+                }
+                _ => Ordering::Greater,
+            },
+            WithMeta(x, _) => (**x).cmp(other),
         }
     }
 }
@@ -645,6 +1084,9 @@ impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
         use Value::*;
 
+        if let WithMeta(inner, _) = self {
+            return inner.hash(state);
+        }
         // mix in the particular variant
         discriminant(self).hash(state);
 
@@ -665,23 +1107,20 @@ impl Hash for Value {
             Vector(v) => v.hash(state),
             Map(m) => {
                 m.size().hash(state);
-                sorted(m).for_each(|binding| binding.hash(state));
+                m.iter().for_each(|binding| binding.hash(state));
             }
             Set(s) => {
                 s.size().hash(state);
-                sorted(s).for_each(|elem| elem.hash(state));
+                s.iter().for_each(|elem| elem.hash(state));
             }
             Fn(lambda) => lambda.hash(state),
             FnWithCaptures(lambda) => lambda.hash(state),
-            Primitive(f) => {
-                let ptr = f as *const NativeFn;
-                let identifier = ptr as usize;
-                identifier.hash(state);
-            }
+            Primitive(f) => (*f as usize).hash(state),
             Var(VarImpl {
                 data,
                 namespace,
                 identifier,
+                ..
             }) => {
                 data.borrow().hash(state);
                 namespace.hash(state);
@@ -693,6 +1132,43 @@ impl Hash for Value {
             }
             Macro(lambda) => lambda.hash(state),
             Exception(e) => e.hash(state),
+            Bytes(b) => b.hash(state),
+            Uuid(u) => u.hash(state),
+            Instant(t) => t.hash(state),
+            Queue(q) => {
+                q.len().hash(state);
+                q.iter().for_each(|elem| elem.hash(state));
+            }
+            WeakRef(w) => w.address().hash(state),
+            LruCache(cache) => cache.address().hash(state),
+            Reduced(v) => v.hash(state),
+            Parameter(index, level) => {
+                index.hash(state);
+                level.hash(state);
+            }
+            WithMeta(..) => unreachable!("hashed the value without its metadata"),
+        }
+    }
+}
+
+// FNV-1a, which unlike `std`'s default hasher is not seeded per process
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
         }
     }
 }
@@ -739,6 +1215,7 @@ impl fmt::Debug for Value {
                 data,
                 namespace,
                 identifier,
+                ..
             }) => match data.borrow().as_ref() {
                 Some(inner) => {
                     write!(f, "Var({:?}/{:?}, {:?})", namespace, identifier, inner)
@@ -751,6 +1228,21 @@ impl fmt::Debug for Value {
             Exception(exception) => {
                 write!(f, "Exception({:?})", exception)
             }
+            Bytes(bytes) => write!(f, "Bytes({:?})", bytes),
+            Uuid(uuid) => write!(f, "Uuid({})", uuid),
+            Instant(t) => write!(f, "Instant({})", t),
+            Queue(elems) => write!(f, "Queue({:?})", elems.iter().format(", ")),
+            WeakRef(w) => match w.upgrade() {
+                Some(target) => write!(f, "WeakRef({:?})", target),
+                None => write!(f, "WeakRef(collected)"),
+            },
+            LruCache(cache) => {
+                let (len, capacity) = cache.size();
+                write!(f, "LruCache({}/{})", len, capacity)
+            }
+            Reduced(v) => write!(f, "Reduced({:?})", v),
+            Parameter(index, level) => write!(f, "Parameter({}, {})", index, level),
+            WithMeta(v, meta) => write!(f, "WithMeta({:?}, {:?})", v, meta),
         }
     }
 }
@@ -789,13 +1281,17 @@ impl fmt::Display for Value {
                 write!(f, "{{{}}}", join(inner, ", "))
             }
             Set(elems) => write!(f, "#{{{}}}", join(elems, " ")),
-            Fn(_) => write!(f, "<fn*>"),
-            FnWithCaptures(..) => write!(f, "<fn* +captures>",),
+            Fn(FnImpl { params, .. }) => write!(f, "<fn* [{}]>", join(params.iter(), " ")),
+            FnWithCaptures(FnWithCapturesImpl {
+                f: FnImpl { params, .. },
+                ..
+            }) => write!(f, "<fn* [{}] +captures>", join(params.iter(), " ")),
             Primitive(_) => write!(f, "<native function>"),
             Var(VarImpl {
                 data,
                 namespace,
                 identifier,
+                ..
             }) => {
                 if data.borrow().is_some() {
                     write!(f, "#'{}/{}", namespace, identifier)
@@ -805,10 +1301,28 @@ impl fmt::Display for Value {
             }
             Recur(elems) => write!(f, "[{}]", join(elems, " ")),
             Atom(v) => write!(f, "(atom {})", *v.borrow()),
-            Macro(_) => write!(f, "<macro>"),
+            Macro(FnImpl { params, .. }) => write!(f, "<macro [{}]>", join(params.iter(), " ")),
             Exception(exception) => {
                 write!(f, "{}", exception)
             }
+            Bytes(bytes) => write!(f, "#bytes \"{}\"", BASE64.encode(bytes)),
+            Uuid(uuid) => write!(f, "{}", uuid),
+            Instant(t) => match format_instant(*t) {
+                Some(formatted) => write!(f, "{}", formatted),
+                None => write!(f, "{}", t),
+            },
+            Queue(elems) => write!(f, "#queue [{}]", join(elems, " ")),
+            WeakRef(w) => match w.upgrade() {
+                Some(target) => write!(f, "<weak-ref {}>", target),
+                None => write!(f, "<weak-ref collected>"),
+            },
+            LruCache(cache) => {
+                let (len, capacity) = cache.size();
+                write!(f, "<lru-cache {}/{}>", len, capacity)
+            }
+            Reduced(v) => write!(f, "<reduced {}>", v),
+            Parameter(index, level) => write!(f, "{}", lambda_parameter_key(*index, *level)),
+            WithMeta(v, _) => write!(f, "{}", v),
         }
     }
 }
@@ -843,7 +1357,339 @@ fn unescape_string(input: &str) -> String {
     result
 }
 
+// Associative operations shared by `get`, `contains?`, `assoc`, `dissoc`,
+// `update` and keyword invocation.
+impl Value {
+    /// Whether this value counts as true in a conditional, i.e. it is neither `nil` nor `false`.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Bool(false))
+    }
+
+    /// Returns a hash of this value that is the same across runs of a program.
+    /// Primitives are hashed by their address so are only stable within a run.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Indicates if `lookup` and `contains_key` are meaningful for this value.
+    pub fn is_associative(&self) -> bool {
+        matches!(
+            self,
+            Value::Nil | Value::Map(..) | Value::Vector(..) | Value::Set(..) | Value::String(..)
+        )
+    }
+
+    /// Find the value associated with `key`, if any.
+    /// Vectors and strings are keyed by index and sets by their members.
+    pub fn lookup(&self, key: &Value) -> Option<Value> {
+        match self {
+            Value::Map(map) => map.get(key).cloned(),
+            Value::Vector(elems) => match key {
+                Value::Number(index) if *index >= 0 => elems.get(*index as usize).cloned(),
+                _ => None,
+            },
+            Value::Set(elems) => elems.get(key).cloned(),
+            Value::String(s) => match key {
+                Value::Number(index) if *index >= 0 => {
+                    s.chars().nth(*index as usize).map(char_string)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn contains_key(&self, key: &Value) -> bool {
+        match self {
+            Value::Map(map) => map.contains_key(key),
+            Value::Vector(elems) => {
+                matches!(key, Value::Number(index) if *index >= 0 && (*index as usize) < elems.len())
+            }
+            Value::Set(elems) => elems.contains(key),
+            Value::String(s) => {
+                matches!(key, Value::Number(index) if *index >= 0 && (*index as usize) < s.chars().count())
+            }
+            _ => false,
+        }
+    }
+
+    /// Associate `key` with `val`, returning the updated collection.
+    /// `nil` is treated as an empty map. A vector index may refer to an existing
+    /// element or to one past the end, in which case `val` is appended.
+    pub fn assoc(&self, key: Value, val: Value) -> EvaluationResult<Value> {
+        match self {
+            Value::Nil => Ok(map_with_values([(key, val)])),
+            Value::Map(map) => Ok(Value::Map(map.insert(key, val))),
+            Value::Vector(elems) => match key {
+                Value::Number(index) if index >= 0 => {
+                    let index = index as usize;
+                    match index.cmp(&elems.len()) {
+                        Ordering::Less => Ok(Value::Vector(
+                            elems.set(index, val).expect("index is in bounds"),
+                        )),
+                        Ordering::Equal => Ok(Value::Vector(elems.push_back(val))),
+                        Ordering::Greater => {
+                            Err(EvaluationError::IndexOutOfBounds(index, elems.len()))
+                        }
+                    }
+                }
+                other => Err(EvaluationError::WrongType {
+                    expected: "Number",
+                    realized: other,
+                }),
+            },
+            other => Err(EvaluationError::WrongType {
+                expected: "Nil, Map, Vector",
+                realized: other.clone(),
+            }),
+        }
+    }
+
+    /// Remove any association for `key`, returning the updated collection.
+    pub fn dissoc(&self, key: &Value) -> EvaluationResult<Value> {
+        match self {
+            Value::Nil => Ok(Value::Nil),
+            Value::Map(map) => Ok(Value::Map(map.remove(key))),
+            other => Err(EvaluationError::WrongType {
+                expected: "Nil, Map",
+                realized: other.clone(),
+            }),
+        }
+    }
+}
+
+// Borrowing views for hosts reading results without cloning them.
+impl Value {
+    /// This value without any metadata attached with `with-meta`.
+    pub fn without_meta(&self) -> &Value {
+        match self {
+            Value::WithMeta(value, _) => value,
+            value => value,
+        }
+    }
+
+    /// The metadata attached to this value with `with-meta`, if any.
+    pub fn meta(&self) -> Option<&Value> {
+        match self {
+            Value::WithMeta(_, meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// Attach `meta`, a map or `nil`, to this collection, symbol or fn
+    /// in place of any metadata it already has.
+    pub fn with_meta(self, meta: Value) -> EvaluationResult<Value> {
+        let value = match self {
+            Value::WithMeta(value, _) => *value,
+            value => value,
+        };
+        match meta {
+            Value::Nil => Ok(value),
+            meta @ Value::Map(..) => match value {
+                Value::List(..)
+                | Value::Vector(..)
+                | Value::Map(..)
+                | Value::Set(..)
+                | Value::Queue(..)
+                | Value::Symbol(..)
+                | Value::Fn(..)
+                | Value::FnWithCaptures(..)
+                | Value::Primitive(..) => Ok(Value::WithMeta(Box::new(value), Box::new(meta))),
+                other => Err(EvaluationError::WrongType {
+                    expected: "List, Vector, Map, Set, Queue, Symbol, Fn",
+                    realized: other,
+                }),
+            },
+            other => Err(EvaluationError::WrongType {
+                expected: "Map, Nil",
+                realized: other,
+            }),
+        }
+    }
+
+    pub fn as_number(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The name of a keyword or symbol, without any namespace.
+    pub fn as_name(&self) -> Option<&str> {
+        match self.without_meta() {
+            Value::Keyword(identifier, _) | Value::Symbol(identifier, _) => Some(identifier),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&PersistentMap<Value, Value>> {
+        match self.without_meta() {
+            Value::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_vector(&self) -> Option<&PersistentVector<Value>> {
+        match self.without_meta() {
+            Value::Vector(elems) => Some(elems),
+            _ => None,
+        }
+    }
+
+    /// Iterates the elements of a list, vector or set by reference.
+    pub fn elements(&self) -> Option<Box<dyn Iterator<Item = &Value> + '_>> {
+        match self.without_meta() {
+            Value::List(elems) => Some(Box::new(elems.iter())),
+            Value::Vector(elems) => Some(Box::new(elems.iter())),
+            Value::Set(elems) => Some(Box::new(elems.iter())),
+            Value::Queue(elems) => Some(Box::new(elems.iter())),
+            _ => None,
+        }
+    }
+
+    /// Follows `path` through nested collections: each segment names a keyword,
+    /// or failing that a string, key of a map, or an index into a vector.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        path.iter().try_fold(self, |value, segment| match value {
+            Value::Map(map) => map
+                .get(&Value::Keyword(segment.to_string(), None))
+                .or_else(|| map.get(&Value::String((*segment).into()))),
+            Value::Vector(elems) => elems.get(segment.parse().ok()?),
+            _ => None,
+        })
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = EvaluationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value.as_number().ok_or(EvaluationError::WrongType {
+            expected: "Number",
+            realized: value,
+        })
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = EvaluationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value.as_bool().ok_or(EvaluationError::WrongType {
+            expected: "Bool",
+            realized: value,
+        })
+    }
+}
+
+// keywords and symbols convert to their name
+impl TryFrom<Value> for String {
+    type Error = EvaluationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s.to_string()),
+            Value::Keyword(identifier, _) | Value::Symbol(identifier, _) => Ok(identifier),
+            other => Err(EvaluationError::WrongType {
+                expected: "String, Keyword, Symbol",
+                realized: other,
+            }),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value>,
+    T::Error: Into<EvaluationError>,
+{
+    type Error = EvaluationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(vec![]),
+            Value::List(elems) => try_from_all(elems.iter()),
+            Value::Vector(elems) => try_from_all(elems.iter()),
+            Value::Set(elems) => try_from_all(elems.iter()),
+            other => Err(EvaluationError::WrongType {
+                expected: "Nil, List, Vector, Set",
+                realized: other,
+            }),
+        }
+    }
+}
+
+fn try_from_all<'a, T>(elems: impl Iterator<Item = &'a Value>) -> Result<Vec<T>, EvaluationError>
+where
+    T: TryFrom<Value>,
+    T::Error: Into<EvaluationError>,
+{
+    elems
+        .map(|elem| T::try_from(elem.clone()).map_err(Into::into))
+        .collect()
+}
+
+// keys convert as for `String`
+impl<T> TryFrom<Value> for HashMap<String, T>
+where
+    T: TryFrom<Value>,
+    T::Error: Into<EvaluationError>,
+{
+    type Error = EvaluationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(HashMap::new()),
+            Value::Map(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    let v = T::try_from(v.clone()).map_err(Into::into)?;
+                    Ok((String::try_from(k.clone())?, v))
+                })
+                .collect(),
+            other => Err(EvaluationError::WrongType {
+                expected: "Nil, Map",
+                realized: other,
+            }),
+        }
+    }
+}
+
+/// The two modes in which a `Value` can be printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintMode {
+    /// Print a form the reader can consume, e.g. strings are quoted and escaped.
+    /// Used by `pr`, `prn` and `pr-str`.
+    Readable,
+    /// Print for human consumption, e.g. strings are written verbatim.
+    /// Used by `print`, `println` and `print-str`.
+    Display,
+}
+
 impl Value {
+    pub fn to_string_in_mode(&self, mode: PrintMode) -> String {
+        match mode {
+            PrintMode::Readable => self.to_readable_string(),
+            PrintMode::Display => self.to_string(),
+        }
+    }
+
     pub fn to_readable_string(&self) -> String {
         let mut f = String::new();
 
@@ -888,15 +1734,31 @@ impl Value {
                     .format(" ")
             )
             .expect("can write to string"),
+            Value::Queue(elems) => write!(
+                &mut f,
+                "#queue [{}]",
+                elems
+                    .iter()
+                    .map(|elem| elem.to_readable_string())
+                    .format(" ")
+            )
+            .expect("can write to string"),
             Value::String(s) => {
                 let unescaped_string = unescape_string(s);
                 write!(&mut f, "\"{}\"", unescaped_string).expect("can write to string");
             }
             Value::Atom(v) => write!(&mut f, "(atom {})", v.borrow().to_readable_string())
                 .expect("can write to string"),
+            Value::Uuid(uuid) => write!(&mut f, "#uuid \"{}\"", uuid).expect("can write to string"),
+            Value::Instant(t) => match format_instant(*t) {
+                Some(formatted) => write!(&mut f, "#inst \"{}\"", formatted),
+                None => write!(&mut f, "#inst {}", t),
+            }
+            .expect("can write to string"),
             Value::Exception(e) => {
                 write!(&mut f, "{}", e.to_readable_string()).expect("can write to string")
             }
+            Value::WithMeta(v, _) => f = v.to_readable_string(),
             other => {
                 write!(&mut f, "{}", other).expect("can write to string");
             }
@@ -970,4 +1832,59 @@ mod tests {
         assert_eq!(b.cmp(c), Ordering::Less);
         assert_eq!(b.cmp(y), Ordering::Less);
     }
+    #[test]
+    fn test_stable_hash() {
+        // pinned so that a change to the hash of values is noticed
+        assert_eq!(Nil.stable_hash(), 12161962213042174405);
+        assert_eq!(Number(1).stable_hash(), 513837244993915590);
+        assert_eq!(
+            String("a".into()).stable_hash(),
+            String("a".into()).stable_hash()
+        );
+        assert_ne!(Number(1).stable_hash(), Number(2).stable_hash());
+    }
+
+    #[test]
+    fn test_views() {
+        let value = crate::reader::read(r#"{:a {"b" [1 true "c"]} :d #{:e}}"#)
+            .unwrap()
+            .remove(0);
+        assert_eq!(value.get_path(&["a", "b", "0"]), Some(&Number(1)));
+        assert_eq!(
+            value.get_path(&["a", "b", "2"]).and_then(Value::as_str),
+            Some("c")
+        );
+        assert_eq!(value.get_path(&["a", "b", "3"]), None);
+        assert_eq!(value.get_path(&["a", "x"]), None);
+        assert_eq!(value.get_path(&[]), Some(&value));
+        assert_eq!(value.as_map().map(|map| map.size()), Some(2));
+        let names = value
+            .get_path(&["d"])
+            .and_then(Value::elements)
+            .unwrap()
+            .filter_map(Value::as_name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["e"]);
+        assert!(Nil.elements().is_none());
+
+        let elems = value.get_path(&["a", "b"]).unwrap().clone();
+        assert!(Vec::<i64>::try_from(elems.clone()).is_err());
+        assert_eq!(Vec::<Value>::try_from(elems).unwrap().len(), 3);
+        assert_eq!(i64::try_from(Number(3)).unwrap(), 3);
+        assert!(bool::try_from(Nil).is_err());
+        assert_eq!(
+            Vec::<std::string::String>::try_from(vector_with_values([
+                String("a".into()),
+                Keyword("b".to_string(), None)
+            ]))
+            .unwrap(),
+            vec!["a", "b"]
+        );
+        let map = HashMap::<std::string::String, i64>::try_from(map_with_values([(
+            Keyword("x".to_string(), None),
+            Number(1),
+        )]))
+        .unwrap();
+        assert_eq!(map.get("x"), Some(&1));
+    }
 }